@@ -0,0 +1,223 @@
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+
+use crate::shapes::shape::{Shape, Svg};
+use crate::shapes::table::Table;
+use crate::shapes::text_box::TextBox;
+use crate::vec_draw_doc::VecDrawDoc;
+
+/// 찾기 옵션
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FindOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool,        // 찾을 내용을 정규식으로 해석한다. (바꿀 내용에서 $1 등으로 그룹을 참조할 수 있다)
+}
+
+/// 찾은 글자가 있는 곳
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchSource {
+    TextBox,            // 텍스트 박스 (표 셀 포함). 바꿀 수 있다.
+    SvgText(usize),     // 가져온 SVG의 n번째 `<text>` 요소. 바꿀 수 없다.
+}
+
+/// 찾은 글자 범위. start, end는 글자 단위 인덱스이다.
+#[derive(Clone)]
+pub struct TextMatch {
+    pub shape: Arc<Mutex<Box<dyn Shape>>>,
+    pub source: MatchSource,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// 문서 전체 찾기/바꾸기. 찾은 결과 목록과 현재 위치를 기억한다.
+pub struct FindReplace {
+    pattern: Option<Regex>,
+    options: FindOptions,
+    matches: Vec<TextMatch>,
+    current: Option<usize>,
+}
+
+static INSTANCE: Lazy<Arc<Mutex<FindReplace>>> = Lazy::new(|| Arc::new(Mutex::new(FindReplace {
+    pattern: None,
+    options: FindOptions::default(),
+    matches: Vec::new(),
+    current: None,
+})));
+
+impl FindReplace {
+    pub fn instance() -> Arc<Mutex<FindReplace>> {
+        Arc::clone(&INSTANCE)
+    }
+
+    /// 문서 전체에서 query를 찾는다. 찾은 개수를 반환하고, 정규식이 잘못되었으면 오류 메시지를 반환한다.
+    pub fn search(&mut self, query: &str, options: FindOptions) -> Result<usize, String> {
+        self.options = options;
+        self.current = None;
+        self.matches.clear();
+        self.pattern = None;
+        if query.is_empty() {
+            return Ok(0);
+        }
+
+        let source = if options.regex { query.to_string() } else { regex::escape(query) };
+        let source = if options.whole_word { format!(r"\b(?:{})\b", source) } else { source };
+        let pattern = RegexBuilder::new(&source)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|error| error.to_string())?;
+        self.pattern = Some(pattern);
+        self.matches = self.collect_matches();
+        Ok(self.matches.len())
+    }
+
+    /// 찾은 결과를 지운다. (찾기 창을 닫을 때)
+    pub fn clear(&mut self) {
+        self.pattern = None;
+        self.matches.clear();
+        self.current = None;
+    }
+
+    /// (현재 위치, 찾은 개수). 아직 이동하지 않았으면 현재 위치는 None
+    pub fn status(&self) -> (Option<usize>, usize) {
+        (self.current, self.matches.len())
+    }
+
+    pub fn current(&self) -> Option<TextMatch> {
+        self.current.and_then(|index| self.matches.get(index)).cloned()
+    }
+
+    /// 다음(forward) 또는 이전 결과로 이동한다. 끝에 닿으면 반대쪽 끝으로 돌아간다.
+    pub fn step(&mut self, forward: bool) -> Option<TextMatch> {
+        let count = self.matches.len();
+        if count == 0 {
+            return None;
+        }
+        let index = match (self.current, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(index), true) => (index + 1) % count,
+            (Some(index), false) => (index + count - 1) % count,
+        };
+        self.current = Some(index);
+        self.current()
+    }
+
+    /// 현재 결과를 replacement로 바꾸고 다음 결과로 이동한다. 바꿀 수 없는 결과(SVG 글자)면 건너뛴다.
+    /// 바꾼 뒤의 현재 결과를 반환한다.
+    pub fn replace_current(&mut self, replacement: &str) -> Option<TextMatch> {
+        let Some(index) = self.current else { return self.step(true); };
+        let target = self.matches.get(index).cloned()?;
+        if target.source != MatchSource::TextBox {
+            return self.step(true);
+        }
+
+        let replaced = self.replace_match(&target, replacement);
+        self.matches = self.collect_matches();
+
+        // 바꾼 글자 뒤의 첫 결과로 이동한다. (바꾼 글자 안에서 다시 찾은 결과는 건너뛴다)
+        let replaced_end = target.start + replaced.map_or(target.end - target.start, |text| text.chars().count());
+        let next = (index..self.matches.len())
+            .find(|&i| !(Arc::ptr_eq(&self.matches[i].shape, &target.shape) && self.matches[i].start < replaced_end));
+        self.current = match next {
+            Some(next) => Some(next),
+            None if self.matches.is_empty() => None,
+            None => Some(0),
+        };
+        self.current()
+    }
+
+    /// 텍스트 박스에서 찾은 결과를 모두 바꾸고 바꾼 개수를 반환한다.
+    pub fn replace_all(&mut self, replacement: &str) -> usize {
+        let targets = self.matches.iter().filter(|m| m.source == MatchSource::TextBox).cloned().collect::<Vec<_>>();
+
+        // 뒤에서부터 바꾸어 앞쪽 결과의 인덱스가 바뀌지 않게 한다.
+        let count = targets.iter().rev().filter(|target| self.replace_match(target, replacement).is_some()).count();
+        self.matches = self.collect_matches();
+        self.current = None;
+        count
+    }
+
+    /// 텍스트 박스의 한 결과를 바꾼다. 찾은 뒤 글자가 바뀌어 더 이상 맞지 않으면 None
+    fn replace_match(&self, target: &TextMatch, replacement: &str) -> Option<String> {
+        let pattern = self.pattern.as_ref()?;
+        let mut shape = target.shape.lock().unwrap();
+        let text_box = shape.as_any_mut().downcast_mut::<TextBox>()?;
+
+        let byte_index = |index: usize| text_box.text.char_indices().nth(index).map_or(text_box.text.len(), |(i, _)| i);
+        let (byte_start, byte_end) = (byte_index(target.start), byte_index(target.end));
+        let captures = pattern.captures_at(&text_box.text, byte_start)?;
+        let whole = captures.get(0)?;
+        if whole.start() != byte_start || whole.end() != byte_end {
+            return None;
+        }
+
+        let mut text = String::new();
+        if self.options.regex {
+            captures.expand(replacement, &mut text);
+        }
+        else {
+            text.push_str(replacement);
+        }
+        text_box.replace_range(target.start, target.end, &text);
+        Some(text)
+    }
+
+    /// 문서 순서대로 모든 텍스트 박스, 표 셀, SVG 글자에서 찾는다.
+    fn collect_matches(&self) -> Vec<TextMatch> {
+        let Some(pattern) = &self.pattern else { return Vec::new(); };
+        let shapes = VecDrawDoc::instance().lock().unwrap().shapes.clone();
+
+        let mut matches = Vec::new();
+        let push_text_box = |shape: &Arc<Mutex<Box<dyn Shape>>>, matches: &mut Vec<TextMatch>| {
+            let text = shape.lock().unwrap().as_any().downcast_ref::<TextBox>().map(|text_box| text_box.text.clone());
+            if let Some(text) = text {
+                for (start, end) in find_ranges(pattern, &text) {
+                    matches.push(TextMatch{ shape: Arc::clone(shape), source: MatchSource::TextBox, start, end });
+                }
+            }
+        };
+
+        for shape in &shapes {
+            let guard = shape.lock().unwrap();
+            if guard.as_any().is::<TextBox>() {
+                drop(guard);
+                push_text_box(shape, &mut matches);
+            }
+            else if let Some(table) = guard.as_any().downcast_ref::<Table>() {
+                let cells = (0..table.row_count())
+                    .flat_map(|row| (0..table.column_count()).map(move |col| (row, col)))
+                    .filter_map(|(row, col)| table.cell_text_box(row, col))
+                    .collect::<Vec<_>>();
+                drop(guard);
+                cells.iter().for_each(|cell| push_text_box(cell, &mut matches));
+            }
+            else if let Some(svg) = guard.as_any().downcast_ref::<Svg>() {
+                for (index, element) in svg.text_elements().iter().enumerate() {
+                    for (start, end) in find_ranges(pattern, &element.text) {
+                        matches.push(TextMatch{ shape: Arc::clone(shape), source: MatchSource::SvgText(index), start, end });
+                    }
+                }
+            }
+        }
+        matches
+    }
+}
+
+/// text에서 pattern과 맞는 글자 범위 (글자 단위, 빈 범위 제외)
+fn find_ranges(pattern: &Regex, text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let (mut byte, mut index) = (0, 0);
+    let mut char_index = |target: usize| {
+        index += text[byte..target].chars().count();
+        byte = target;
+        index
+    };
+    for found in pattern.find_iter(text).filter(|found| !found.is_empty()) {
+        let start = char_index(found.start());
+        let end = char_index(found.end());
+        ranges.push((start, end));
+    }
+    ranges
+}
@@ -0,0 +1,116 @@
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+
+use crate::shapes::shape::{PropertyValue, Shape, ShapeFormat};
+use crate::shapes::text_box::{TextBox, TextLayout};
+
+/// 되돌리기/다시 실행할 때 도형에 다시 적용하는 상태
+#[derive(Clone)]
+pub enum ShapeState {
+    Format(Box<ShapeFormat>),
+    /// 속성 창에서 편집한 기하 속성 하나 (이름, 값)
+    Property(String, PropertyValue),
+    /// 텍스트 박스의 배치 설정
+    TextLayout(TextLayout),
+}
+
+impl ShapeState {
+    fn apply_to(&self, shape: &mut dyn Shape) {
+        match self {
+            ShapeState::Format(format) => format.apply_to(shape),
+            ShapeState::Property(name, value) => shape.set_property(name, value),
+            ShapeState::TextLayout(layout) => {
+                if let Some(text_box) = shape.as_any_mut().downcast_mut::<TextBox>() {
+                    text_box.set_text_layout(layout);
+                }
+            }
+        }
+    }
+}
+
+/// 바뀐 도형과 변경 전/후 값 목록
+type Changes<T> = Vec<(Arc<Mutex<Box<dyn Shape>>>, T, T)>;
+
+/// 한 번의 변경: 바뀐 도형마다 변경 전/후 상태를 저장한다.
+pub struct FormatChange {
+    source: String,     // 변경을 일으킨 툴바 항목 (연속 입력을 합칠 때 사용)
+    entries: Changes<ShapeState>,
+    open: bool,         // 같은 항목의 다음 입력을 합칠 수 있는지
+}
+
+impl FormatChange {
+    pub fn new(source: &str, entries: Changes<ShapeFormat>) -> Self {
+        let entries = entries.into_iter().map(|(shape, before, after)| (shape, ShapeState::Format(Box::new(before)), ShapeState::Format(Box::new(after)))).collect();
+        FormatChange { source: source.to_string(), entries, open: true }
+    }
+
+    /// 속성 창에서 도형 하나의 기하 속성을 바꾼 변경
+    pub fn property(source: &str, shape: Arc<Mutex<Box<dyn Shape>>>, name: &str, before: PropertyValue, after: PropertyValue) -> Self {
+        let entries = vec![(shape, ShapeState::Property(name.to_string(), before), ShapeState::Property(name.to_string(), after))];
+        FormatChange { source: source.to_string(), entries, open: true }
+    }
+
+    /// 텍스트 박스의 배치 설정을 바꾼 변경
+    pub fn text_layout(source: &str, entries: Changes<TextLayout>) -> Self {
+        let entries = entries.into_iter().map(|(shape, before, after)| (shape, ShapeState::TextLayout(before), ShapeState::TextLayout(after))).collect();
+        FormatChange { source: source.to_string(), entries, open: true }
+    }
+
+    fn is_same_target(&self, other: &FormatChange) -> bool {
+        self.source == other.source
+            && self.entries.len() == other.entries.len()
+            && self.entries.iter().zip(other.entries.iter()).all(|(a, b)| Arc::ptr_eq(&a.0, &b.0))
+    }
+}
+
+/// 되돌리기/다시 실행 기록
+pub struct History {
+    undo_stack: Vec<FormatChange>,
+    redo_stack: Vec<FormatChange>,
+}
+
+static INSTANCE: Lazy<Arc<Mutex<History>>> = Lazy::new(|| Arc::new(Mutex::new(History { undo_stack: Vec::new(), redo_stack: Vec::new() })));
+
+impl History {
+    pub fn instance() -> Arc<Mutex<History>> {
+        Arc::clone(&INSTANCE)
+    }
+
+    /// 변경을 기록한다. 슬라이더를 끄는 동안처럼 같은 항목으로 같은 도형들을 연속해서 바꾸면 하나의 변경으로 합친다.
+    pub fn record(&mut self, change: FormatChange) {
+        if change.entries.is_empty() {
+            return;
+        }
+        self.redo_stack.clear();
+
+        if let Some(last) = self.undo_stack.last_mut() {
+            if last.open && last.is_same_target(&change) {
+                last.entries.iter_mut().zip(change.entries).for_each(|(entry, (_, _, after))| entry.2 = after);
+                return;
+            }
+        }
+        self.undo_stack.push(change);
+    }
+
+    /// 진행 중인 변경을 마친다. (입력 요소의 change 이벤트에서 호출)
+    pub fn seal(&mut self) {
+        if let Some(last) = self.undo_stack.last_mut() {
+            last.open = false;
+        }
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(mut change) = self.undo_stack.pop() else { return false; };
+        change.entries.iter().for_each(|(shape, before, _)| before.apply_to(shape.lock().unwrap().as_mut()));
+        change.open = false;
+        self.redo_stack.push(change);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(change) = self.redo_stack.pop() else { return false; };
+        change.entries.iter().for_each(|(shape, _, after)| after.apply_to(shape.lock().unwrap().as_mut()));
+        self.undo_stack.push(change);
+        true
+    }
+}
@@ -12,7 +12,7 @@ use wasm_bindgen_futures::JsFuture;
 use web_sys::DomRect;
 use web_sys::Window;
 use web_sys::{window, Document, CanvasRenderingContext2d, HtmlCanvasElement, InputEvent, HtmlTextAreaElement, HtmlInputElement, HtmlImageElement, MouseEvent, WheelEvent, DragEvent, File, FileReader, Element, Path2d
-    , HtmlDivElement , DomParser, HtmlElement, Node, NodeList, ImageData, Blob, KeyboardEvent, CompositionEvent, TextMetrics};
+    , HtmlDivElement , DomParser, HtmlElement, Node, NodeList, ImageData, Blob, KeyboardEvent, CompositionEvent, TextMetrics, PointerEvent};
 use std::char::UNICODE_VERSION;
 use std::fs::OpenOptions;
 
@@ -26,18 +26,38 @@ mod shapes{
     pub mod geometry;
     pub mod shape;
     pub mod line;
+    pub mod curve;
     pub mod rectangle;
     pub mod ellipse;
+    pub mod polygon;
+    pub mod symbol;
+    pub mod table;
     pub mod text_box;
+    pub mod theme;
+    pub mod text_run;
+    pub mod text_path;
 }
 
 mod vec_draw_doc;
 use crate::vec_draw_doc::VecDrawDoc;
 
+mod symbol_library;
+use crate::symbol_library::{SymbolDefinition, SymbolLibrary};
+
+mod style_library;
+use crate::style_library::{NamedStyle, StyleLibrary, restyle_document};
+
+mod history;
+use crate::history::{History, FormatChange};
+
+mod find_replace;
+use crate::find_replace::{FindReplace, FindOptions, MatchSource, TextMatch};
+
 use crate::shapes::geometry::{Point2D, Vector2D};
 use std::cmp::PartialEq;
-use crate::shapes::shape::{Shape, Pencil, Svg};
-use crate::shapes::{line::Line, rectangle::Rectangle, ellipse::Ellipse, text_box::TextBox, text_box::TextBoxManager};
+use crate::shapes::shape::{Shape, Pencil, Svg, SvgText, Brush, StrokeSample, Fill, StrokeStyle, LineCap, LineJoin, ShapeStyle, ShapeFormat, PropertyValue, TextFont, BlendMode, Shadow, Gradient, GradientKind, GradientStop, svg_with_compositing};
+use crate::shapes::theme::Theme;
+use crate::shapes::{line::Line, curve::Curve, rectangle::Rectangle, ellipse::Ellipse, polygon::RegularPolygon, symbol::SymbolInstance, table::Table, text_box::TextBox, text_box::TextBoxManager, text_box::{TextWrap, TextAlign, VerticalAlign, TextDirection}, text_run::{RunStyle, Script}, text_path::PathSide};
 
 pub mod state;
 use crate::state::State;
@@ -100,6 +120,14 @@ pub fn start() -> Result<(), JsValue> {
     // ✅ 모드 선택 UI
     setup_mode_buttons();
     let _ = setup_keyboard_shortcuts();
+    let _ = setup_symbol_library();
+    let _ = setup_style_library();
+    let _ = setup_table_tools();
+    let _ = setup_gradient_editor();
+    let _ = setup_property_inspector();
+    let _ = setup_theme();
+    let _ = setup_text_tools();
+    let _ = setup_find_panel();
 
     // 초기 캔버스 상태
     let last_mouse_pos = Rc::new(RefCell::new((0.0, 0.0)));
@@ -108,6 +136,8 @@ pub fn start() -> Result<(), JsValue> {
 
     // 드로잉 포인트
     let mouse_context_points: Rc<RefCell<Vec<Point2D>>> = Rc::new(RefCell::new(Vec::new()));
+    // 드로잉 포인트별 필압/기울기/시간 (펜슬)
+    let stroke_samples: Rc<RefCell<Vec<StrokeSample>>> = Rc::new(RefCell::new(Vec::new()));
 
     // 🎨 드래그 앤 드롭 이벤트 추가
     let canvas_clone = Rc::new(canvas.clone());
@@ -141,6 +171,23 @@ pub fn start() -> Result<(), JsValue> {
             event.prevent_default();
 
             if let Some(data_transfer) = event.data_transfer() {
+                // 라이브러리 패널에서 끌어온 심볼
+                let symbol_name = data_transfer.get_data(SYMBOL_DRAG_TYPE).unwrap_or_default();
+                if !symbol_name.is_empty() {
+                    let rect = canvas_clone.get_bounding_client_rect();
+                    let mouse_x = event.client_x() as f64 - rect.left();
+                    let mouse_y = event.client_y() as f64 - rect.top();
+                    let (drop_x, drop_y) = calculate_canvas_coordinates((mouse_x, mouse_y), (0.0, 0.0));
+
+                    let instance = VecDrawDoc::instance();
+                    let mut doc = instance.lock().unwrap();
+                    doc.add_shape(Box::new(SymbolInstance::new(&symbol_name, Point2D::new(drop_x, drop_y))));
+                    STATE.with(|state| {
+                        doc.draw(&context_clone, &state.borrow());
+                    });
+                    return;
+                }
+
                 if let Some(files) = data_transfer.files(){
                     for i in 0..files.length() {
                         if let Some(file) = files.get(i) {
@@ -287,28 +334,26 @@ pub fn start() -> Result<(), JsValue> {
                 offset.set_y(mouse_y - zoom_factor * (mouse_y - offset.y));
                 state.borrow_mut().set_offset(&offset);
 
-                // 잔상 방지를 위해 전체 캔버스를 리셋
-                context_clone.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0).unwrap(); // 변환 초기화
-                context_clone.clear_rect(0.0, 0.0, client_rect.width(), client_rect.height()); // 전체 캔버스 지우기
-                context_clone.set_fill_style(&JsValue::from_str(state.borrow().fill_color()));
-                context_clone.fill_rect(0.0, 0.0, client_rect.width(), client_rect.height());
-
-                // 캔버스 다시 그리기
-                let _ = context_clone.set_transform(state.borrow().scale(), 0.0, 0.0, state.borrow().scale(), offset.x, offset.y);
-                let instance = VecDrawDoc::instance();
-                instance.lock().unwrap().draw(&context_clone, &*state.borrow());
+                redraw_zoomed(&context_clone, &client_rect, &state.borrow());
             });
         })?;
     }
 
+    // 포인터 이벤트로 처리하므로 마우스 다운의 기본 동작(포커스 이동 등)만 막는다.
+    add_event_listener(&canvas, "mousedown", move |event: MouseEvent| {
+        event.prevent_default();
+    })?;
+
     // 마우스 다운 이벤트 (팬 시작)
     { 
         let last_mouse_pos = Rc::clone(&last_mouse_pos);
         let canvas_clone = canvas.clone();
         let mouse_context_points= Rc::clone(&mouse_context_points);
+        let stroke_samples = Rc::clone(&stroke_samples);
 
-        add_event_listener(&canvas, "mousedown", move |event: MouseEvent| {
+        add_pointerevent_listener(&canvas, "pointerdown", move |event: PointerEvent| {
             event.prevent_default();
+            let _ = canvas_clone.set_pointer_capture(event.pointer_id());
 
             let client_rect = canvas_clone.get_bounding_client_rect();
 
@@ -328,6 +373,21 @@ pub fn start() -> Result<(), JsValue> {
                 }else if state.borrow().action_mode() == &state::ActionMode::Selection{
                     let (current_x, current_y) = calculate_canvas_coordinates((mouse_x, mouse_y), (scroll_x, scroll_y));
 
+                    // 셀 편집 중이면 셀 안을 누른 경우 캐럿을 옮기고, 밖을 누른 경우 입력을 마친다.
+                    let editing = TEXTBOXMANAGER.with(|tbm|{
+                        if let Some(ref mut manager) = *tbm.borrow_mut() {
+                            if manager.hit_attached(current_x, current_y){
+                                manager.on_pointer_down(current_x, current_y, event.shift_key());
+                                return true;
+                            }
+                            if manager.is_active(){ manager.finish_input(); }
+                        }
+                        false
+                    });
+                    if editing{
+                        return;
+                    }
+
                     let instance = VecDrawDoc::instance();
                     let doc = instance.lock().unwrap();
 
@@ -344,9 +404,22 @@ pub fn start() -> Result<(), JsValue> {
                         let control_point_index = shape.get_control_point(current_x, current_y, state.borrow().scale());
                         shape.set_selected_control_point(control_point_index);
                         shape.set_selected(true);
+
+                        // 표는 클릭한 셀을 선택한다. (Shift: 선택 영역 확장)
+                        if control_point_index == -1{
+                            if let Some(table) = shape.as_any_mut().downcast_mut::<Table>(){
+                                table.select_cell_at(current_x, current_y, event.shift_key());
+                            }
+                        }
                     });
 
                     doc.draw(&context_clone, &*state.borrow());
+                    drop(doc);
+
+                    if selection_changed{
+                        sync_toolbar_with_selection();
+                        refresh_property_inspector();
+                    }
                 }
                 else if state.borrow().action_mode() == &state::ActionMode::Drawing{
                     let (current_x, current_y) = calculate_canvas_coordinates((mouse_x, mouse_y), (scroll_x, scroll_y));
@@ -354,14 +427,35 @@ pub fn start() -> Result<(), JsValue> {
                         TEXTBOXMANAGER.with(|tbm|{
                             if let Some(ref mut manager) = *tbm.borrow_mut() {
                                 if !manager.is_active(){
+                                    // 표의 셀을 클릭하면 셀 텍스트를 편집한다.
+                                    if let Some((table, cell_text)) = find_table_cell_text(current_x, current_y, state.borrow().scale()){
+                                        manager.attach_table_cell(table, cell_text);
+                                        return;
+                                    }
+
+                                    // 기존 텍스트 박스를 클릭하면 다시 편집한다.
+                                    if let Some(text_box) = find_text_box(current_x, current_y, state.borrow().scale()){
+                                        manager.attach(text_box);
+                                        manager.on_pointer_down(current_x, current_y, false);
+                                        return;
+                                    }
+
                                     let instance = VecDrawDoc::instance();
                                     let mut doc = instance.lock().unwrap();
 
-                                    doc.add_shape(Box::new(TextBox::new(current_x, current_y)));
+                                    let mut text_box = TextBox::new(current_x, current_y);
+                                    text_box.set_font(state.borrow().text_font().clone());
+                                    let (align, vertical_align) = state.borrow().text_align();
+                                    text_box.set_align(align);
+                                    text_box.set_vertical_align(vertical_align);
+                                    doc.add_shape(Box::new(text_box));
                                     // TextBoxManager 시작
                                     if let Some(shape) = doc.nth(doc.count() - 1){
                                         manager.attach(Arc::clone(&shape));
                                     }
+                                }else if manager.hit_attached(current_x, current_y){
+                                    // 편집 중인 텍스트 박스 안을 누르면 캐럿을 옮긴다.
+                                    manager.on_pointer_down(current_x, current_y, event.shift_key());
                                 }else{
                                     manager.finish_input();
                                 }
@@ -369,6 +463,43 @@ pub fn start() -> Result<(), JsValue> {
                         });
                     }
                 }
+                else if state.borrow().action_mode() == &state::ActionMode::Eyedropper{
+                    // 화면에 그려진 픽셀에서 색상을 뽑는다. (Shift: 채우기 색상)
+                    let canvas_x = mouse_x * canvas_clone.width() as f64 / client_rect.width();
+                    let canvas_y = mouse_y * canvas_clone.height() as f64 / client_rect.height();
+                    if let Some((color, alpha)) = sample_canvas_color(&context_clone, canvas_x, canvas_y){
+                        apply_sampled_color(&color, alpha, event.shift_key());
+                    }
+                }
+                else if state.borrow().action_mode() == &state::ActionMode::FormatPainter{
+                    let (current_x, current_y) = calculate_canvas_coordinates((mouse_x, mouse_y), (scroll_x, scroll_y));
+
+                    let instance = VecDrawDoc::instance();
+                    let doc = instance.lock().unwrap();
+                    let unders = doc.get_shapes_under_mouse(current_x, current_y, state.borrow().scale());
+
+                    // 처음 클릭한 도형의 서식을 복사하고, 이후 클릭한 도형에 붙여 넣는다. 빈 곳을 클릭하면 복사한 서식을 지운다.
+                    let copied_format = state.borrow().copied_format().cloned();
+                    match (copied_format, unders.first()) {
+                        (None, Some(shape)) => {
+                            let format = ShapeFormat::from_shape(shape.lock().unwrap().as_ref());
+                            state.borrow_mut().set_copied_format(Some(format));
+                        }
+                        (Some(format), Some(shape)) => {
+                            let before = ShapeFormat::from_shape(shape.lock().unwrap().as_ref());
+                            format.apply_to(shape.lock().unwrap().as_mut());
+
+                            let instance = History::instance();
+                            let mut history = instance.lock().unwrap();
+                            history.record(FormatChange::new("format-painter", vec![(Arc::clone(shape), before, format)]));
+                            history.seal();
+                            drop(history);
+
+                            doc.draw(&context_clone, &state.borrow());
+                        }
+                        (_, None) => state.borrow_mut().set_copied_format(None),
+                    }
+                }
 
                 // ✅ 현재 캔버스 상태 백업 (이전 선택 영역 복원용)
                 /*IMAGE_BACKUP.with(|backup| {
@@ -382,6 +513,7 @@ pub fn start() -> Result<(), JsValue> {
 
                 let (current_x, current_y) = calculate_canvas_coordinates((mouse_x, mouse_y), (scroll_x, scroll_y));
                 mouse_context_points.borrow_mut().push(Point2D { x: current_x, y: current_y });
+                *stroke_samples.borrow_mut() = vec![stroke_sample(&event)];
             });
         })?;
     }
@@ -396,8 +528,9 @@ pub fn start() -> Result<(), JsValue> {
         let mouse_context_points= Rc::clone(&mouse_context_points);
 
         let animation_requested_clone = Rc::clone(&animation_requested);
+        let stroke_samples = Rc::clone(&stroke_samples);
 
-        add_event_listener(&canvas, "mousemove", move |event: MouseEvent| {
+        add_pointerevent_listener(&canvas, "pointermove", move |event: PointerEvent| {
             event.prevent_default();
 
             let client_rect = canvas_clone.get_bounding_client_rect();
@@ -411,6 +544,18 @@ pub fn start() -> Result<(), JsValue> {
             let mouse_x = event.client_x() as f64 - client_rect.left();
             let mouse_y = event.client_y() as f64 - client_rect.top();
 
+            // 텍스트 박스 안에서 누른 채로 움직이면 글자를 선택한다.
+            let drag_selecting = TEXTBOXMANAGER.with(|tbm| {
+                let mut tbm = tbm.borrow_mut();
+                let Some(manager) = tbm.as_mut().filter(|manager| manager.is_drag_selecting()) else { return false; };
+                let (current_x, current_y) = calculate_canvas_coordinates((mouse_x, mouse_y), (scroll_x, scroll_y));
+                manager.on_pointer_drag(current_x, current_y);
+                true
+            });
+            if drag_selecting {
+                return;
+            }
+
             STATE.with(|state| {
                 canvas_clone.set_class_name("cursor-default");
 
@@ -448,21 +593,25 @@ pub fn start() -> Result<(), JsValue> {
                             let instance = VecDrawDoc::instance();
                             let mut doc = instance.lock().unwrap();
                             doc.erase(current_x, current_y, state.borrow().scale());
+                            detach_orphaned_text_paths(&doc);
                             doc.draw(&context_clone, &*state.borrow());
                         }else if state.borrow().action_mode() == &state::ActionMode::Drawing{
-                            let (last_x, last_y) = calculate_canvas_coordinates((last_x, last_y), (scroll_x, scroll_y));
                             let (current_x, current_y) = calculate_canvas_coordinates((mouse_x, mouse_y), (scroll_x, scroll_y));
                             
                             let drawing_mode = *state.borrow().drawing_mode();
                             match drawing_mode {
                                 DrawingMode::Pencil =>{
-                                    context_clone.set_stroke_style(&JsValue::from_str(state.borrow().color()));
-                                    context_clone.begin_path();
-                                    context_clone.move_to(last_x, last_y);
-                                    context_clone.line_to(current_x, current_y);
-                                    context_clone.stroke();
-
                                     mouse_context_points.borrow_mut().push(Point2D { x: current_x, y: current_y });
+                                    stroke_samples.borrow_mut().push(stroke_sample(&event));
+
+                                    // 굵기가 점마다 달라지므로 진행 중인 선 전체를 다시 그린다.
+                                    let instance = VecDrawDoc::instance();
+                                    let doc = instance.lock().unwrap();
+                                    doc.draw(&context_clone, &state.borrow());
+
+                                    let mut pencil = Pencil::with_samples(state.borrow().color().to_string(), state.borrow().line_width(), state.borrow().brush()
+                                        , mouse_context_points.borrow().clone(), stroke_samples.borrow().clone());
+                                    pencil.draw(&context_clone, state.borrow().scale());
                                 }
                                 DrawingMode::Line =>{
                                     let start_point = *mouse_context_points.borrow().get(0).unwrap();
@@ -493,6 +642,25 @@ pub fn start() -> Result<(), JsValue> {
                                         mouse_context_points.borrow_mut().push(end_point);
                                     }
                                 }
+                                DrawingMode::Curve =>{
+                                    let start_point = *mouse_context_points.borrow().first().unwrap();
+
+                                    let instance = VecDrawDoc::instance();
+                                    let doc = instance.lock().unwrap();
+                                    doc.draw(&context_clone, &state.borrow());
+
+                                    let end_point = Point2D::new(current_x, current_y);
+                                    let curve = Curve::new(state.borrow().color().to_string(), state.borrow().line_width(), start_point, end_point);
+                                    curve.draw_xor(&context_clone, state.borrow().scale());
+
+                                    if mouse_context_points.borrow().len() == 1{
+                                        mouse_context_points.borrow_mut().push(end_point);
+                                    }
+                                    else{
+                                        mouse_context_points.borrow_mut().remove(1);
+                                        mouse_context_points.borrow_mut().push(end_point);
+                                    }
+                                }
                                 DrawingMode::Rectangle =>{
                                     let start_point = *mouse_context_points.borrow().get(0).unwrap();
 
@@ -552,6 +720,45 @@ pub fn start() -> Result<(), JsValue> {
                                         mouse_context_points.borrow_mut().push(end_point);
                                     }
                                 }
+                                DrawingMode::Polygon | DrawingMode::Star =>{
+                                    let start_point = *mouse_context_points.borrow().first().unwrap();
+
+                                    let instance = VecDrawDoc::instance();
+                                    let doc = instance.lock().unwrap();
+                                    doc.draw(&context_clone, &state.borrow());
+
+                                    let end_point = Point2D::new(current_x, current_y);
+                                    let polygon = create_regular_polygon(&state.borrow(), start_point, end_point);
+                                    polygon.draw_xor(&context_clone, state.borrow().scale());
+
+                                    if mouse_context_points.borrow().len() == 1{
+                                        mouse_context_points.borrow_mut().push(end_point);
+                                    }
+                                    else{
+                                        mouse_context_points.borrow_mut().remove(1);
+                                        mouse_context_points.borrow_mut().push(end_point);
+                                    }
+                                }
+                                DrawingMode::Table =>{
+                                    let start_point = *mouse_context_points.borrow().first().unwrap();
+
+                                    let instance = VecDrawDoc::instance();
+                                    let doc = instance.lock().unwrap();
+                                    doc.draw(&context_clone, &state.borrow());
+
+                                    let end_point = Point2D::new(current_x, current_y);
+                                    let table = Table::new(state.borrow().color().to_string(), state.borrow().line_width(), start_point
+                                        , end_point.x - start_point.x, end_point.y - start_point.y, Table::DEFAULT_ROWS, Table::DEFAULT_COLUMNS);
+                                    table.draw_xor(&context_clone, state.borrow().scale());
+
+                                    if mouse_context_points.borrow().len() == 1{
+                                        mouse_context_points.borrow_mut().push(end_point);
+                                    }
+                                    else{
+                                        mouse_context_points.borrow_mut().remove(1);
+                                        mouse_context_points.borrow_mut().push(end_point);
+                                    }
+                                }
                                 DrawingMode::Text => {
                                 }
                                 _ => info!("not supported drawing mode: {drawing_mode}"), // 값을 콘솔에 출력
@@ -568,13 +775,17 @@ pub fn start() -> Result<(), JsValue> {
                                 let dx = current_x - last_x;
                                 let dy = current_y - last_y;
 
-                                doc.get_selected_shapes().iter().for_each(|shape| {
+                                selected.iter().for_each(|shape| {
                                     let mut shape = shape.lock().unwrap();
                                     let selected_control_point = shape.get_selected_control_point();
                                     if selected_control_point != -1{
                                         shape.move_control_point_by(selected_control_point, dx, dy);
                                     }else{
-                                        shape.move_by(dx, dy);
+                                        // 경로도 함께 옮기는 중이면 경로 위의 글자는 경로를 따라 움직이므로 따로 옮기지 않는다.
+                                        let follows_selection = shape.as_any().downcast_ref::<TextBox>().is_some_and(|text_box| text_box.follows_any(&selected));
+                                        if !follows_selection {
+                                            shape.move_by(dx, dy);
+                                        }
                                     }
                                 });
 
@@ -618,10 +829,17 @@ pub fn start() -> Result<(), JsValue> {
     {
         let context_clone = Rc::new(context.clone());
         let mouse_context_points= Rc::clone(&mouse_context_points);
+        let stroke_samples = Rc::clone(&stroke_samples);
 
-        add_event_listener(&canvas, "mouseup", move |event: MouseEvent| {
+        add_pointerevent_listener(&canvas, "pointerup", move |event: PointerEvent| {
             event.prevent_default();
 
+            TEXTBOXMANAGER.with(|tbm| {
+                if let Some(ref mut manager) = *tbm.borrow_mut() {
+                    manager.end_drag();
+                }
+            });
+
             STATE.with(|state| {
                 IS_MOUSE_PRESSED.with(|pressed| *pressed.borrow_mut() = false);
                 state.borrow_mut().set_is_panning(&false);
@@ -635,7 +853,8 @@ pub fn start() -> Result<(), JsValue> {
                     let drawing_mode = state_ref.drawing_mode();
                     match drawing_mode{
                         DrawingMode::Pencil =>{
-                            let pencil = Pencil::new(state.borrow().color().to_string(), state.borrow().line_width(), mouse_context_points.borrow().clone());
+                            let pencil = Pencil::with_samples(state.borrow().color().to_string(), state.borrow().line_width(), state.borrow().brush()
+                                , mouse_context_points.borrow().clone(), stroke_samples.borrow().clone());
 
                             let instance = VecDrawDoc::instance();
                             let mut doc = instance.lock().unwrap();
@@ -645,19 +864,33 @@ pub fn start() -> Result<(), JsValue> {
                             let mouse_context_points_ref = mouse_context_points.borrow();
                             let start = mouse_context_points_ref.get(0).unwrap();
                             let end = mouse_context_points_ref.get(mouse_context_points.borrow().len() - 1).unwrap();
-                            let line = Line::new(state.borrow().color().to_string(), state.borrow().line_width(), *start, *end);
+                            let mut line = Line::new(state.borrow().color().to_string(), state.borrow().line_width(), *start, *end);
+                            line.set_stroke_style(state.borrow().stroke_style().clone());
 
                             let instance = VecDrawDoc::instance();
                             let mut doc = instance.lock().unwrap();
                             doc.add_shape(Box::new(line));
                         }
+                        DrawingMode::Curve =>{
+                            let mouse_context_points_ref = mouse_context_points.borrow();
+                            let start = mouse_context_points_ref.first().unwrap();
+                            let end = mouse_context_points_ref.last().unwrap();
+                            let mut curve = Curve::new(state.borrow().color().to_string(), state.borrow().line_width(), *start, *end);
+                            curve.set_stroke_style(state.borrow().stroke_style().clone());
+
+                            let instance = VecDrawDoc::instance();
+                            let mut doc = instance.lock().unwrap();
+                            doc.add_shape(Box::new(curve));
+                        }
                         DrawingMode::Rectangle =>{
                             let mouse_context_points_ref = mouse_context_points.borrow();
                             let start = mouse_context_points_ref.get(0).unwrap();
                             let end = mouse_context_points_ref.get(mouse_context_points.borrow().len() - 1).unwrap();
                             let width = end.x - start.x;
                             let height = end.y - start.y;
-                            let rectangle = Rectangle::new(state.borrow().color().to_string(), state.borrow().line_width(), *start, width, height);
+                            let mut rectangle = Rectangle::new(state.borrow().color().to_string(), state.borrow().line_width(), *start, width, height);
+                            rectangle.set_fill(state.borrow().shape_fill().cloned());
+                            rectangle.set_stroke_style(state.borrow().stroke_style().clone());
 
                             let instance = VecDrawDoc::instance();
                             let mut doc = instance.lock().unwrap();
@@ -670,12 +903,37 @@ pub fn start() -> Result<(), JsValue> {
                             let width = end.x - start.x;
                             let height = end.y - start.y;
                             let center = Point2D::new((start.x + end.x) * 0.5, (start.y + end.y) * 0.5);
-                            let ellipse = Ellipse::new(center, width * 0.5, height * 0.5, 0.0, 0.0, std::f64::consts::PI * 2.0, state.borrow().color().to_string(), state.borrow().line_width());
+                            let mut ellipse = Ellipse::new(center, width * 0.5, height * 0.5, 0.0, 0.0, std::f64::consts::PI * 2.0, state.borrow().color().to_string(), state.borrow().line_width());
+                            ellipse.set_fill(state.borrow().shape_fill().cloned());
+                            ellipse.set_stroke_style(state.borrow().stroke_style().clone());
 
                             let instance = VecDrawDoc::instance();
                             let mut doc = instance.lock().unwrap();
                             doc.add_shape(Box::new(ellipse));
                         }
+                        DrawingMode::Polygon | DrawingMode::Star =>{
+                            let mouse_context_points_ref = mouse_context_points.borrow();
+                            let start = mouse_context_points_ref.first().unwrap();
+                            let end = mouse_context_points_ref.get(mouse_context_points.borrow().len() - 1).unwrap();
+                            let mut polygon = create_regular_polygon(&state_ref, *start, *end);
+                            polygon.set_fill(state_ref.shape_fill().cloned());
+                            polygon.set_stroke_style(state_ref.stroke_style().clone());
+
+                            let instance = VecDrawDoc::instance();
+                            let mut doc = instance.lock().unwrap();
+                            doc.add_shape(Box::new(polygon));
+                        }
+                        DrawingMode::Table =>{
+                            let mouse_context_points_ref = mouse_context_points.borrow();
+                            let start = mouse_context_points_ref.first().unwrap();
+                            let end = mouse_context_points_ref.get(mouse_context_points.borrow().len() - 1).unwrap();
+                            let table = Table::new(state.borrow().color().to_string(), state.borrow().line_width(), *start
+                                , end.x - start.x, end.y - start.y, Table::DEFAULT_ROWS, Table::DEFAULT_COLUMNS);
+
+                            let instance = VecDrawDoc::instance();
+                            let mut doc = instance.lock().unwrap();
+                            doc.add_shape(Box::new(table));
+                        }
                         DrawingMode::Text =>{
                             /*
                             let mouse_context_points_ref = mouse_context_points.borrow();
@@ -694,10 +952,17 @@ pub fn start() -> Result<(), JsValue> {
                 }
 
                 mouse_context_points.borrow_mut().clear();
+                stroke_samples.borrow_mut().clear();
 
                 let instance = VecDrawDoc::instance();
                 let doc = instance.lock().unwrap();
                 doc.draw(&context_clone, &*state.borrow());
+                drop(doc);
+
+                // 조절점으로 옮기거나 크기를 바꾼 결과를 속성 창에 반영한다.
+                if state.borrow().action_mode() == &ActionMode::Selection{
+                    refresh_property_inspector();
+                }
             });
         })?;
     }
@@ -751,6 +1016,32 @@ pub fn start() -> Result<(), JsValue> {
             .add_event_listener_with_callback("compositionend", composition_end_closure.as_ref().unchecked_ref())?;
         composition_end_closure.forget();
                           
+        // 📋 복사, 잘라내기, 붙여넣기 (편집 중인 텍스트 박스의 글자만 다룬다)
+        for name in ["copy", "cut"] {
+            let cut = name == "cut";
+            let closure = Closure::wrap(Box::new(move |event: web_sys::ClipboardEvent| {
+                let text = TEXTBOXMANAGER.with(|tbm| tbm.borrow_mut().as_mut().and_then(|manager| manager.copy_selection(cut)));
+                if let (Some(text), Some(data)) = (text, event.clipboard_data()) {
+                    let _ = data.set_data("text/plain", &text);
+                    event.prevent_default();
+                }
+            }) as Box<dyn FnMut(_)>);
+            textarea.add_event_listener_with_callback(name, closure.as_ref().unchecked_ref())?;
+            closure.forget();
+        }
+
+        let paste_closure = Closure::wrap(Box::new(move |event: web_sys::ClipboardEvent| {
+            let Some(text) = event.clipboard_data().and_then(|data| data.get_data("text/plain").ok()) else { return; };
+            event.prevent_default();
+            TEXTBOXMANAGER.with(|tbm|{
+                if let Some(ref mut manager) = *tbm.borrow_mut() {
+                    manager.paste(&text);
+                }
+            });
+        }) as Box<dyn FnMut(_)>);
+        textarea.add_event_listener_with_callback("paste", paste_closure.as_ref().unchecked_ref())?;
+        paste_closure.forget();
+
         //let input_manager = manager.clone();
         let input_closure = Closure::wrap(Box::new(move |event: InputEvent| {
             TEXTBOXMANAGER.with(|tbm|{
@@ -810,8 +1101,10 @@ pub fn start() -> Result<(), JsValue> {
             let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
                 if let Some(target) = event.target() {
                     if let Ok(input) = target.dyn_into::<HtmlInputElement>() {
-                        state_clone.borrow_mut().set_color(&input.value());
-                
+                        let color = input.value();
+                        state_clone.borrow_mut().set_color(&color);
+                        restyle_selection("color-picker", |shape| shape.set_color(&color));
+
                         info!("Color changed to ={}", state_clone.borrow().color()); // 값을 콘솔에 출력
                     }
                 }
@@ -833,6 +1126,7 @@ pub fn start() -> Result<(), JsValue> {
                     if let Ok(input) = target.dyn_into::<HtmlInputElement>() {
                         if let Ok(value) = input.value().parse::<f64>() {
                             state_clone.borrow_mut().set_line_width(value);
+                            restyle_selection("line-width", |shape| shape.set_line_width(value));
                             info!("Line width changed to: {}", state_clone.borrow().line_width()); // 콘솔 출력
                         }
                     }
@@ -847,6 +1141,234 @@ pub fn start() -> Result<(), JsValue> {
         });
     }
 
+    // 다각형 변 개수 / 별 꼭짓점 개수 변경 이벤트
+    {
+        let context_clone = Rc::new(context.clone());
+
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(target) = event.target() {
+                if let Ok(input) = target.dyn_into::<HtmlInputElement>() {
+                    if let Ok(value) = input.value().parse::<u32>() {
+                        let sides = value.clamp(RegularPolygon::MIN_SIDES, RegularPolygon::MAX_SIDES);
+                        STATE.with(|state| {
+                            state.borrow_mut().set_polygon_sides(sides);
+
+                            // 선택된 다각형에도 적용
+                            let instance = VecDrawDoc::instance();
+                            let doc = instance.lock().unwrap();
+                            doc.get_selected_shapes().iter().for_each(|shape| {
+                                if let Some(polygon) = shape.lock().unwrap().as_any_mut().downcast_mut::<RegularPolygon>() {
+                                    polygon.set_sides(sides);
+                                }
+                            });
+                            doc.draw(&context_clone, &state.borrow());
+                        });
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        let polygon_sides = document.get_element_by_id("polygon-sides").unwrap();
+        polygon_sides.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
+    // 별의 안쪽 반지름 비율 변경 이벤트
+    {
+        let context_clone = Rc::new(context.clone());
+
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(target) = event.target() {
+                if let Ok(input) = target.dyn_into::<HtmlInputElement>() {
+                    if let Ok(value) = input.value().parse::<f64>() {
+                        STATE.with(|state| {
+                            state.borrow_mut().set_star_inner_ratio(value);
+
+                            // 선택된 별에도 적용
+                            let instance = VecDrawDoc::instance();
+                            let doc = instance.lock().unwrap();
+                            doc.get_selected_shapes().iter().for_each(|shape| {
+                                if let Some(polygon) = shape.lock().unwrap().as_any_mut().downcast_mut::<RegularPolygon>() {
+                                    if polygon.is_star() { polygon.set_inner_ratio(value); }
+                                }
+                            });
+                            doc.draw(&context_clone, &state.borrow());
+                        });
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        let star_inner_ratio = document.get_element_by_id("star-inner-ratio").unwrap();
+        star_inner_ratio.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
+    // 채우기 변경 이벤트 (사용 여부, 색상, 불투명도, 그라디언트)
+    {
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            update_fill_from_toolbar();
+        }) as Box<dyn FnMut(_)>);
+
+        // gradient-stops에는 정지점 입력의 이벤트가 전달된다.
+        for id in ["fill-enabled", "fill-color", "fill-alpha", "fill-type", "gradient-stops"] {
+            let element = document.get_element_by_id(id).unwrap();
+            element.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref()).unwrap();
+            element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref()).unwrap();
+        }
+        closure.forget();
+    }
+
+    // 선 스타일 변경 이벤트 (점선 프리셋, 점선 시작 위치, 끝 모양, 연결 모양, 마이터 한계)
+    {
+        let document_clone = document.clone();
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let value = |id: &str| {
+                let element = document_clone.get_element_by_id(id).unwrap();
+                js_sys::Reflect::get(&element, &"value".into()).ok().and_then(|value| value.as_string()).unwrap_or_default()
+            };
+
+            let style = StrokeStyle{
+                dash: StrokeStyle::dash_preset(&value("stroke-dash")).unwrap_or_default(),
+                dash_offset: value("dash-offset").parse::<f64>().unwrap_or(0.0),
+                cap: LineCap::from_name(&value("line-cap")).unwrap_or(LineCap::Butt),
+                join: LineJoin::from_name(&value("line-join")).unwrap_or(LineJoin::Miter),
+                miter_limit: value("miter-limit").parse::<f64>().map_or(10.0, |limit| limit.max(1.0)),
+            };
+            STATE.with(|state| state.borrow_mut().set_stroke_style(style.clone()));
+
+            // ✅ 선택된 도형에는 바뀐 항목만 적용한다. (선택 항목마다 나머지 값이 다를 수 있음)
+            let id = event.target().and_then(|target| target.dyn_into::<Element>().ok()).map(|element| element.id()).unwrap_or_default();
+            restyle_selection(&id, |shape| {
+                let mut stroke_style = shape.style().stroke_style;
+                match id.as_str() {
+                    "stroke-dash" => stroke_style.dash = style.dash.clone(),
+                    "dash-offset" => stroke_style.dash_offset = style.dash_offset,
+                    "line-cap" => stroke_style.cap = style.cap,
+                    "line-join" => stroke_style.join = style.join,
+                    _ => stroke_style.miter_limit = style.miter_limit,
+                }
+                shape.set_stroke_style(stroke_style);
+            });
+        }) as Box<dyn FnMut(_)>);
+
+        for id in ["stroke-dash", "dash-offset", "line-cap", "line-join", "miter-limit"] {
+            let element = document.get_element_by_id(id).unwrap();
+            element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref()).unwrap();
+        }
+        closure.forget();
+    }
+
+    // 불투명도 변경 이벤트 (선택된 도형에 적용)
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(target) = event.target() {
+                if let Ok(input) = target.dyn_into::<HtmlInputElement>() {
+                    if let Ok(value) = input.value().parse::<f64>() {
+                        restyle_selection("opacity", |shape| shape.set_opacity(value));
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        let opacity = document.get_element_by_id("opacity").unwrap();
+        opacity.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
+    // 블렌드 모드 변경 이벤트 (선택된 도형에 적용)
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(target) = event.target() {
+                if let Some(value) = js_sys::Reflect::get(&target, &"value".into()).ok().and_then(|value| value.as_string()) {
+                    if let Some(blend_mode) = BlendMode::from_name(&value) {
+                        restyle_selection("blend-mode", |shape| shape.set_blend_mode(blend_mode));
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        let blend_mode = document.get_element_by_id("blend-mode").unwrap();
+        blend_mode.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
+    // 그림자/흐림 효과 변경 이벤트 (선택된 도형에 적용)
+    {
+        let document_clone = document.clone();
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let input = |id: &str| document_clone.get_element_by_id(id).unwrap().dyn_into::<HtmlInputElement>().unwrap();
+            let number = |id: &str| input(id).value().parse::<f64>().unwrap_or(0.0);
+
+            let shadow = Shadow::new(&input("shadow-color").value(), number("shadow-alpha"),
+                number("shadow-offset-x"), number("shadow-offset-y"), number("shadow-blur"));
+            let blur = number("effect-blur").max(0.0);
+
+            // ✅ 선택된 도형에는 바뀐 항목만 적용한다. (선택 항목마다 나머지 값이 다를 수 있음)
+            let id = event.target().and_then(|target| target.dyn_into::<Element>().ok()).map(|element| element.id()).unwrap_or_default();
+            let shadow_enabled = input("shadow-enabled").checked();
+            restyle_selection(&id, |shape| {
+                let mut effects = shape.effects().clone();
+                match id.as_str() {
+                    "effect-blur" => effects.blur = blur,
+                    "shadow-enabled" => effects.shadow = shadow_enabled.then(|| shadow.clone()),
+                    // 그림자 속성은 그림자가 있는 도형에만 적용한다.
+                    _ => if let Some(current) = effects.shadow.as_mut() {
+                        match id.as_str() {
+                            "shadow-color" => current.color = shadow.color.clone(),
+                            "shadow-alpha" => current.alpha = shadow.alpha,
+                            "shadow-offset-x" => current.offset_x = shadow.offset_x,
+                            "shadow-offset-y" => current.offset_y = shadow.offset_y,
+                            _ => current.blur = shadow.blur,
+                        }
+                    }
+                }
+                shape.set_effects(effects);
+            });
+        }) as Box<dyn FnMut(_)>);
+
+        for id in ["shadow-enabled", "shadow-color", "shadow-alpha", "shadow-offset-x", "shadow-offset-y", "shadow-blur", "effect-blur"] {
+            let element = document.get_element_by_id(id).unwrap();
+            element.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref()).unwrap();
+            element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref()).unwrap();
+        }
+        closure.forget();
+    }
+
+    // 입력을 마치면(change) 진행 중인 서식 변경을 닫는다. 슬라이더를 끄는 동안의 입력은 하나의 되돌리기 단계로 합쳐진다.
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            History::instance().lock().unwrap().seal();
+            if let Some(element) = event.current_target().and_then(|target| target.dyn_into::<Element>().ok()) {
+                set_mixed(&element, false);
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        for id in ["color-picker", "line-width", "fill-enabled", "fill-color", "fill-alpha", "fill-type", "gradient-stops", "stroke-dash", "dash-offset", "line-cap", "line-join", "miter-limit",
+            "opacity", "blend-mode", "shadow-enabled", "shadow-color", "shadow-alpha", "shadow-offset-x", "shadow-offset-y", "shadow-blur", "effect-blur"] {
+            let element = document.get_element_by_id(id).unwrap();
+            element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref()).unwrap();
+        }
+        closure.forget();
+    }
+
+    // 펜슬 브러시 프리셋 변경 이벤트
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(target) = event.target() {
+                if let Some(value) = js_sys::Reflect::get(&target, &"value".into()).ok().and_then(|value| value.as_string()) {
+                    if let Some(brush) = Brush::from_name(&value) {
+                        STATE.with(|state| state.borrow_mut().set_brush(brush));
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        let brush_preset = document.get_element_by_id("brush-preset").unwrap();
+        brush_preset.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
     // 지우기 버튼 이벤트
     {
         let context_clone = Rc::new(context.clone());
@@ -858,6 +1380,11 @@ pub fn start() -> Result<(), JsValue> {
             STATE.with(|state| {
                 doc.draw(&context_clone, &*state.borrow());
             });
+            drop(doc);
+
+            // 이름 있는 스타일은 문서에 속하므로 함께 비운다.
+            StyleLibrary::instance().lock().unwrap().clear();
+            let _ = refresh_style_list();
         }) as Box<dyn FnMut(_)>);
 
         let clear_button = document.get_element_by_id("clear-btn").unwrap();
@@ -865,6 +1392,19 @@ pub fn start() -> Result<(), JsValue> {
         closure.forget();
     }
 
+    // 저장 버튼 이벤트 (SVG로 내보내기)
+    {
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::MouseEvent| {
+            if let Err(e) = download_text_file("drawing.svg", "image/svg+xml", &export_svg()) {
+                info!("Error saving file: {:?}", e);
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        let save_button = document.get_element_by_id("save-btn").unwrap();
+        save_button.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
     Ok(())
 }
 
@@ -889,19 +1429,51 @@ pub fn setup_keyboard_shortcuts() -> Result<(), JsValue> {
 
     let context_clone = context.clone();
     let closure = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+        // 속성 창 등의 입력 칸에서 누른 키는 도형 단축키로 처리하지 않는다.
+        if let Some(target) = event.target().and_then(|target| target.dyn_into::<Element>().ok()) {
+            if matches!(target.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT") && target.id() != "hidden-input" {
+                return;
+            }
+        }
+
+        // 텍스트를 편집하는 동안에는 모든 키를 TextBoxManager가 처리한다. (Ctrl+A는 글자 전체 선택, Delete는 글자 삭제)
+        // 편집 중이 아닐 때만 도형 단축키(전체 선택, 되돌리기, 삭제 등)로 처리한다.
         TEXTBOXMANAGER.with(|tbm|{
             if let Some(ref mut manager) = *tbm.borrow_mut() {
                 if manager.is_active(){
                     manager.on_keydown(event);
                 }
                 else{
-                    if event.ctrl_key() && event.key() == "a" {
+                    if (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("a") {
                         event.prevent_default(); // ✅ Prevent default browser "Select All" behavior
                         let _ = select_all_shapes(true);
+                        sync_toolbar_with_selection();
+                        refresh_property_inspector();
+                    }
+                    else if event.ctrl_key() && (event.key().eq_ignore_ascii_case("z") || event.key().eq_ignore_ascii_case("y")) {
+                        event.prevent_default();
+
+                        // Ctrl+Z: 되돌리기, Ctrl+Y / Ctrl+Shift+Z: 다시 실행
+                        let redo = event.key().eq_ignore_ascii_case("y") || event.shift_key();
+                        let instance = History::instance();
+                        let mut history = instance.lock().unwrap();
+                        let changed = if redo { history.redo() } else { history.undo() };
+                        drop(history);
+
+                        if changed {
+                            let _ = redraw_document();
+                            sync_toolbar_with_selection();
+                            refresh_property_inspector();
+                        }
+                    }
+                    else if event.ctrl_key() && event.key().eq_ignore_ascii_case("f") {
+                        event.prevent_default(); // 브라우저의 페이지 찾기 대신 문서 찾기 창을 연다.
+                        open_find_panel();
                     }
                     else if event.key() == "Escape"{
                         event.prevent_default(); // ✅ Prevent default behavior
                         let _ = select_all_shapes(false);
+                        refresh_property_inspector();
                     }
                     else if event.key() == "Delete"{
                         event.prevent_default();
@@ -910,8 +1482,10 @@ pub fn setup_keyboard_shortcuts() -> Result<(), JsValue> {
                             let instance = VecDrawDoc::instance();
                             let mut doc = instance.lock().unwrap();
                             doc.delete_selected();
+                            detach_orphaned_text_paths(&doc);
                             doc.draw(&context_clone, &*state.borrow());
                         });
+                        refresh_property_inspector();
                     }
                 }
             }
@@ -957,25 +1531,493 @@ fn select_all_shapes(selected: bool) -> Result<(), JsValue> {
 
 }
 
-fn setup_mode_buttons() {
+/// 문서의 모든 도형을 SVG 문자열로 내보낸다.
+#[wasm_bindgen]
+pub fn export_svg() -> String {
     let document = window().unwrap().document().unwrap();
+    let (width, height) = document
+        .get_element_by_id("drawing-canvas")
+        .and_then(|element| element.dyn_into::<HtmlCanvasElement>().ok())
+        .map(|canvas| (canvas.width(), canvas.height()))
+        .unwrap_or((0, 0));
 
-    let selection_button = document.get_element_by_id("selection-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
-    let eraser_button = document.get_element_by_id("eraser-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let defs = SymbolLibrary::to_svg_defs();
+    let metadata = StyleLibrary::instance().lock().unwrap().to_svg_metadata().unwrap_or_default();
 
-    let pencil_button = document.get_element_by_id("pencil-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
-    let line_button = document.get_element_by_id("line-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
-    let rectangle_button = document.get_element_by_id("rectangle-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
-    let ellipse_button = document.get_element_by_id("ellipse-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
-    let text_button = document.get_element_by_id("text-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let instance = VecDrawDoc::instance();
+    let doc = instance.lock().unwrap();
+    let elements = doc.shapes.iter().map(|shape| svg_with_compositing(shape.lock().unwrap().as_ref())).collect::<Vec<_>>().join("\n");
 
-    // Function to update active button UI
-    let update_ui = move |active_button: &HtmlElement| {
-        let selection_button = selection_button.clone();
-        let eraser_button = eraser_button.clone();
-        let pencil_button = pencil_button.clone();
-        let line_button = line_button.clone();
-        let rectangle_button = rectangle_button.clone();
+    format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n{}{}{}\n</svg>", width, height, metadata, defs, elements)
+}
+
+/// 텍스트 내용을 파일로 내려받는다.
+fn download_text_file(file_name: &str, mime_type: &str, content: &str) -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let anchor = document.create_element("a")?.dyn_into::<HtmlElement>()?;
+    let href = format!("data:{};charset=utf-8,{}", mime_type, String::from(js_sys::encode_uri_component(content)));
+    anchor.set_attribute("href", &href)?;
+    anchor.set_attribute("download", file_name)?;
+    anchor.click();
+    Ok(())
+}
+
+/// 라이브러리 패널에서 캔버스로 심볼을 끌어올 때 사용하는 데이터 형식
+const SYMBOL_DRAG_TYPE: &str = "application/x-vecdraw-symbol";
+
+/// 현재 문서를 캔버스에 다시 그린다.
+fn redraw_document() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let canvas = document
+        .get_element_by_id("drawing-canvas")
+        .expect("Canvas element not found")
+        .dyn_into::<HtmlCanvasElement>()?;
+    let context = canvas
+        .get_context("2d")?
+        .ok_or("Failed to get 2D context")?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+
+    let instance = VecDrawDoc::instance();
+    let doc = instance.lock().unwrap();
+    STATE.with(|state| {
+        doc.draw(&context, &state.borrow());
+    });
+
+    Ok(())
+}
+
+/// 파일 내용을 텍스트로 읽는다.
+async fn read_file_as_text(file: File) -> Result<String, JsValue> {
+    let reader = FileReader::new()?;
+
+    let promise = Promise::new(&mut |resolve, _| {
+        let onload_closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            resolve.call0(&JsValue::null()).unwrap();
+        }) as Box<dyn FnMut(_)>);
+
+        reader.set_onload(Some(onload_closure.as_ref().unchecked_ref()));
+        onload_closure.forget();
+    });
+
+    reader.read_as_text(&file)?;
+    JsFuture::from(promise).await?;
+
+    reader.result()?.as_string().ok_or_else(|| JsValue::from_str("file content is not text"))
+}
+
+/// 심볼 라이브러리 패널 이벤트를 등록한다.
+fn setup_symbol_library() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let library_panel = document.get_element_by_id("library-panel").unwrap().dyn_into::<HtmlDivElement>()?;
+
+    // ⬇️ 패널에 SVG 파일을 드롭하면 심볼로 등록
+    {
+        let closure = Closure::wrap(Box::new(move |event: DragEvent| {
+            event.prevent_default();
+        }) as Box<dyn FnMut(_)>);
+        library_panel.add_event_listener_with_callback("dragover", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+
+        let closure = Closure::wrap(Box::new(move |event: DragEvent| {
+            event.prevent_default();
+
+            let Some(files) = event.data_transfer().and_then(|data_transfer| data_transfer.files()) else { return; };
+            for i in 0..files.length() {
+                if let Some(file) = files.get(i) {
+                    let file_name = file.name();
+                    if !file_name.ends_with(".svg") {
+                        continue;
+                    }
+
+                    wasm_bindgen_futures::spawn_local(async move {
+                        match read_file_as_text(file).await {
+                            Ok(svg_data) => {
+                                let default_name = file_name.trim_end_matches(".svg");
+                                if let Some(name) = prompt_symbol_name(default_name) {
+                                    if let Err(e) = SymbolLibrary::instance().lock().unwrap().define(SymbolDefinition::from_svg(&name, &svg_data)) {
+                                        info!("Error defining symbol: {}", e);
+                                    }
+                                    let _ = refresh_symbol_list();
+                                    let _ = redraw_document();
+                                }
+                            }
+                            Err(e) => info!("Error reading file: {:?}", e),
+                        }
+                    });
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+        library_panel.add_event_listener_with_callback("drop", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    // 선택한 도형으로 심볼 만들기
+    {
+        let button = document.get_element_by_id("symbol-from-selection").unwrap();
+        add_click_listener(&button, move || {
+            let instance = VecDrawDoc::instance();
+            let mut doc = instance.lock().unwrap();
+            let selected = doc.get_selected_shapes();
+            if selected.is_empty() {
+                return;
+            }
+
+            let Some(name) = prompt_symbol_name("symbol") else { return; };
+
+            if SymbolLibrary::instance().lock().unwrap().references(&selected, &name) {
+                info!("Symbol '{}' cannot contain itself", name);
+                return;
+            }
+
+            // 선택한 도형을 마스터로 옮기고 그 자리에 인스턴스를 놓는다.
+            // 중첩된 인스턴스의 크기를 잴 때 라이브러리를 잠그므로 정의를 만든 뒤에 라이브러리를 잠근다.
+            doc.delete_selected();
+            detach_orphaned_text_paths(&doc);
+            let definition = SymbolDefinition::from_shapes(&name, selected);
+            let origin = definition.origin();
+            let center = Point2D::new(origin.x + definition.width() * 0.5, origin.y + definition.height() * 0.5);
+            if let Err(e) = SymbolLibrary::instance().lock().unwrap().define(definition) {
+                info!("Error defining symbol: {}", e);
+            }
+            doc.add_shape(Box::new(SymbolInstance::new(&name, center)));
+            drop(doc);
+
+            let _ = refresh_symbol_list();
+            let _ = redraw_document();
+        });
+    }
+
+    // 라이브러리 내보내기
+    {
+        let button = document.get_element_by_id("symbol-export").unwrap();
+        add_click_listener(&button, move || {
+            let json = SymbolLibrary::export_json();
+            match json {
+                Ok(json) => {
+                    if let Err(e) = download_text_file("symbols.json", "application/json", &json) {
+                        info!("Error saving file: {:?}", e);
+                    }
+                }
+                Err(e) => info!("Error exporting symbols: {:?}", e),
+            }
+        });
+    }
+
+    // 라이브러리 가져오기
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(input) = event.target().and_then(|target| target.dyn_into::<HtmlInputElement>().ok()) else { return; };
+            let Some(file) = input.files().and_then(|files| files.get(0)) else { return; };
+            input.set_value("");
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match read_file_as_text(file).await {
+                    Ok(json) => SymbolLibrary::instance().lock().unwrap().import_json(&json),
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(count) => info!("Imported {} symbols", count),
+                    Err(e) => info!("Error importing symbols: {:?}", e),
+                }
+                let _ = refresh_symbol_list();
+                let _ = redraw_document();
+            });
+        }) as Box<dyn FnMut(_)>);
+
+        let input = document.get_element_by_id("symbol-import").unwrap();
+        input.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    refresh_symbol_list()
+}
+
+/// 심볼 이름을 입력받는다. 취소하거나 빈 이름이면 None
+fn prompt_symbol_name(default_name: &str) -> Option<String> {
+    let window = window().unwrap();
+    window.prompt_with_message_and_default("심볼 이름", default_name).ok().flatten()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// 라이브러리 패널의 심볼 목록을 다시 만든다.
+fn refresh_symbol_list() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let list = document.get_element_by_id("symbol-list").unwrap();
+    list.set_inner_html("");
+
+    for definition in SymbolLibrary::snapshot() {
+        let name = definition.name().to_string();
+
+        let item = document.create_element("div")?;
+        item.set_class_name("library-item");
+        item.set_attribute("draggable", "true")?;
+        item.set_attribute("title", &name)?;
+
+        let img = document.create_element("img")?;
+        let src = format!("data:image/svg+xml,{}", String::from(js_sys::encode_uri_component(&definition.to_svg_file())));
+        img.set_attribute("src", &src)?;
+        img.set_attribute("alt", &name)?;
+        img.set_attribute("draggable", "false")?;
+        item.append_child(&img)?;
+
+        let label = document.create_element("div")?;
+        label.set_text_content(Some(&name));
+        item.append_child(&label)?;
+
+        // 🎯 드래그 시작: 캔버스에 놓으면 인스턴스 생성
+        {
+            let name = name.clone();
+            let closure = Closure::wrap(Box::new(move |event: DragEvent| {
+                if let Some(data_transfer) = event.data_transfer() {
+                    let _ = data_transfer.set_data(SYMBOL_DRAG_TYPE, &name);
+                }
+            }) as Box<dyn FnMut(_)>);
+            item.add_event_listener_with_callback("dragstart", closure.as_ref().unchecked_ref())?;
+            closure.forget();
+        }
+
+        // 선택한 도형으로 마스터 교체 (모든 인스턴스가 갱신됨)
+        {
+            let button = document.create_element("button")?;
+            button.set_text_content(Some("⟳"));
+            button.set_attribute("title", "선택한 도형으로 마스터 교체")?;
+            let name = name.clone();
+            add_click_listener(&button, move || {
+                let instance = VecDrawDoc::instance();
+                let mut doc = instance.lock().unwrap();
+                let selected = doc.get_selected_shapes();
+                if selected.is_empty() {
+                    return;
+                }
+                if SymbolLibrary::instance().lock().unwrap().references(&selected, &name) {
+                    info!("Symbol '{}' cannot contain itself", name);
+                    return;
+                }
+                doc.delete_selected();
+                detach_orphaned_text_paths(&doc);
+                drop(doc);
+
+                // 중첩된 인스턴스의 크기를 잴 때 라이브러리를 잠그므로 정의를 먼저 만든다.
+                let definition = SymbolDefinition::from_shapes(&name, selected);
+                if let Err(e) = SymbolLibrary::instance().lock().unwrap().define(definition) {
+                    info!("Error defining symbol: {}", e);
+                }
+                let _ = refresh_symbol_list();
+                let _ = redraw_document();
+            });
+            item.append_child(&button)?;
+        }
+
+        // 심볼 삭제 (인스턴스는 자리 표시 상자로 남는다)
+        {
+            let button = document.create_element("button")?;
+            button.set_text_content(Some("✕"));
+            button.set_attribute("title", "심볼 삭제")?;
+            let name = name.clone();
+            add_click_listener(&button, move || {
+                SymbolLibrary::instance().lock().unwrap().remove(&name);
+                let _ = refresh_symbol_list();
+                let _ = redraw_document();
+            });
+            item.append_child(&button)?;
+        }
+
+        list.append_child(&item)?;
+    }
+
+    Ok(())
+}
+
+fn setup_style_library() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+
+    // 현재 툴바 설정으로 스타일 만들기
+    {
+        let button = document.get_element_by_id("style-from-toolbar").unwrap();
+        add_click_listener(&button, move || {
+            let window = window().unwrap();
+            let name = window.prompt_with_message_and_default("스타일 이름", "style").ok().flatten()
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty());
+            let Some(name) = name else { return; };
+
+            define_style(NamedStyle::new(&name, current_shape_style()));
+        });
+    }
+
+    // 다른 문서(SVG)에서 스타일 가져오기
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(input) = event.target().and_then(|target| target.dyn_into::<HtmlInputElement>().ok()) else { return; };
+            let Some(file) = input.files().and_then(|files| files.get(0)) else { return; };
+            input.set_value("");
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match read_file_as_text(file).await {
+                    Ok(svg_data) => StyleLibrary::instance().lock().unwrap().import_svg(&svg_data),
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(names) => {
+                        info!("Imported {} styles", names.len());
+                        // 같은 이름의 스타일을 쓰는 도형은 가져온 정의로 바뀐다.
+                        let styles: Vec<NamedStyle> = {
+                            let library = StyleLibrary::instance();
+                            let library = library.lock().unwrap();
+                            names.iter().filter_map(|name| library.get(name).cloned()).collect()
+                        };
+                        let shapes = VecDrawDoc::instance().lock().unwrap().shapes.clone();
+                        styles.iter().for_each(|style| restyle_document(&shapes, style));
+                    }
+                    Err(e) => info!("Error importing styles: {:?}", e),
+                }
+                let _ = refresh_style_list();
+                let _ = redraw_document();
+            });
+        }) as Box<dyn FnMut(_)>);
+
+        let input = document.get_element_by_id("style-import").unwrap();
+        input.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    refresh_style_list()
+}
+
+/// 툴바의 현재 설정 (선 색상, 굵기, 채우기, 선 스타일, 글꼴)
+fn current_shape_style() -> ShapeStyle {
+    STATE.with(|state| {
+        let state = state.borrow();
+        ShapeStyle {
+            color: state.color().to_string(),
+            line_width: state.line_width(),
+            fill: state.shape_fill().cloned(),
+            stroke_style: state.stroke_style().clone(),
+            font: Some(state.text_font().clone()),
+        }
+    })
+}
+
+/// 스타일을 정의(또는 교체)하고 그 스타일을 쓰는 모든 도형을 다시 꾸민다.
+fn define_style(style: NamedStyle) {
+    StyleLibrary::instance().lock().unwrap().define(style.clone());
+
+    let shapes = VecDrawDoc::instance().lock().unwrap().shapes.clone();
+    restyle_document(&shapes, &style);
+
+    let _ = refresh_style_list();
+    let _ = redraw_document();
+}
+
+/// 라이브러리 패널의 스타일 목록을 다시 만든다.
+fn refresh_style_list() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let list = document.get_element_by_id("style-list").unwrap();
+    list.set_inner_html("");
+
+    let library = StyleLibrary::instance();
+    let library = library.lock().unwrap();
+    for named_style in library.styles() {
+        let name = named_style.name().to_string();
+        let style = named_style.style();
+
+        let item = document.create_element("div")?;
+        item.set_class_name("library-item");
+        item.set_attribute("title", &name)?;
+
+        // 미리보기: 채우기와 외곽선
+        let swatch = document.create_element("span")?;
+        swatch.set_class_name("style-swatch");
+        let background = style.fill.as_ref().map_or("transparent".to_string(), |fill| fill.to_css());
+        swatch.set_attribute("style", &format!("background: {}; border: {}px solid {};", background, style.line_width.min(6.0), style.color))?;
+        item.append_child(&swatch)?;
+
+        // 이름을 누르면 선택한 도형에 스타일 적용
+        {
+            let label = document.create_element("button")?;
+            label.set_text_content(Some(&name));
+            label.set_attribute("title", "선택한 도형에 적용")?;
+            let named_style = named_style.clone();
+            add_click_listener(&label, move || {
+                restyle_selection("named-style", |shape| {
+                    shape.set_style_name(Some(named_style.name().to_string()));
+                    shape.apply_style(named_style.style());
+                });
+                // 누를 때마다 하나의 변경이다.
+                History::instance().lock().unwrap().seal();
+                sync_toolbar_with_selection();
+            });
+            item.append_child(&label)?;
+        }
+
+        // 현재 툴바 설정으로 정의 교체 (스타일을 쓰는 모든 도형이 갱신됨)
+        {
+            let button = document.create_element("button")?;
+            button.set_text_content(Some("⟳"));
+            button.set_attribute("title", "현재 설정으로 스타일 교체")?;
+            let name = name.clone();
+            add_click_listener(&button, move || {
+                define_style(NamedStyle::new(&name, current_shape_style()));
+            });
+            item.append_child(&button)?;
+        }
+
+        // 스타일 삭제 (도형은 현재 모양을 유지하고 참조만 끊는다)
+        {
+            let button = document.create_element("button")?;
+            button.set_text_content(Some("✕"));
+            button.set_attribute("title", "스타일 삭제")?;
+            let name = name.clone();
+            add_click_listener(&button, move || {
+                StyleLibrary::instance().lock().unwrap().remove(&name);
+
+                let instance = VecDrawDoc::instance();
+                let doc = instance.lock().unwrap();
+                doc.shapes.iter().for_each(|shape| {
+                    let mut shape = shape.lock().unwrap();
+                    if shape.style_name() == Some(name.as_str()) {
+                        shape.set_style_name(None);
+                    }
+                });
+                drop(doc);
+
+                let _ = refresh_style_list();
+            });
+            item.append_child(&button)?;
+        }
+
+        list.append_child(&item)?;
+    }
+
+    Ok(())
+}
+
+fn setup_mode_buttons() {
+    let document = window().unwrap().document().unwrap();
+
+    let selection_button = document.get_element_by_id("selection-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let eraser_button = document.get_element_by_id("eraser-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+
+    let pencil_button = document.get_element_by_id("pencil-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let line_button = document.get_element_by_id("line-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let curve_button = document.get_element_by_id("curve-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let rectangle_button = document.get_element_by_id("rectangle-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let ellipse_button = document.get_element_by_id("ellipse-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let polygon_button = document.get_element_by_id("polygon-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let star_button = document.get_element_by_id("star-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let table_button = document.get_element_by_id("table-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let text_button = document.get_element_by_id("text-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let eyedropper_button = document.get_element_by_id("eyedropper-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+    let format_painter_button = document.get_element_by_id("format-painter-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+
+    // Function to update active button UI
+    let update_ui = move |active_button: &HtmlElement| {
+        let selection_button = selection_button.clone();
+        let eraser_button = eraser_button.clone();
+        let pencil_button = pencil_button.clone();
+        let line_button = line_button.clone();
+        let rectangle_button = rectangle_button.clone();
         let text_button = text_button.clone();
 
         selection_button.set_class_name("");
@@ -983,9 +2025,15 @@ fn setup_mode_buttons() {
 
         pencil_button.set_class_name("");
         line_button.set_class_name("");
+        curve_button.set_class_name("");
         rectangle_button.set_class_name("");
         ellipse_button.set_class_name("");
+        polygon_button.set_class_name("");
+        star_button.set_class_name("");
+        table_button.set_class_name("");
         text_button.set_class_name("");
+        eyedropper_button.set_class_name("");
+        format_painter_button.set_class_name("");
 
         active_button.set_class_name("active");
     };
@@ -1044,6 +2092,20 @@ fn setup_mode_buttons() {
         });
     }
 
+    // Curve mode Handler
+    {
+        let curve_button = document.get_element_by_id("curve-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+        let curve_button_clone = curve_button.clone();
+        let update_ui_clone = update_ui.clone();
+        add_click_listener(&curve_button, move || {
+            STATE.with(|state| {
+                state.borrow_mut().set_action_mode(&ActionMode::Drawing);
+                state.borrow_mut().set_drawing_mode(&DrawingMode::Curve);
+            });
+            update_ui_clone(&curve_button_clone);
+        });
+    }
+
     // Rectangle mode Handler
     {
         let rectangle_button = document.get_element_by_id("rectangle-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
@@ -1072,6 +2134,48 @@ fn setup_mode_buttons() {
         });
     }
 
+    // Polygon mode Handler
+    {
+        let polygon_button = document.get_element_by_id("polygon-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+        let polygon_button_clone = polygon_button.clone();
+        let update_ui_clone = update_ui.clone();
+        add_click_listener(&polygon_button, move || {
+            STATE.with(|state| {
+                state.borrow_mut().set_action_mode(&ActionMode::Drawing);
+                state.borrow_mut().set_drawing_mode(&DrawingMode::Polygon);
+            });
+            update_ui_clone(&polygon_button_clone);
+        });
+    }
+
+    // Star mode Handler
+    {
+        let star_button = document.get_element_by_id("star-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+        let star_button_clone = star_button.clone();
+        let update_ui_clone = update_ui.clone();
+        add_click_listener(&star_button, move || {
+            STATE.with(|state| {
+                state.borrow_mut().set_action_mode(&ActionMode::Drawing);
+                state.borrow_mut().set_drawing_mode(&DrawingMode::Star);
+            });
+            update_ui_clone(&star_button_clone);
+        });
+    }
+
+    // Table mode Handler
+    {
+        let table_button = document.get_element_by_id("table-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+        let table_button_clone = table_button.clone();
+        let update_ui_clone = update_ui.clone();
+        add_click_listener(&table_button, move || {
+            STATE.with(|state| {
+                state.borrow_mut().set_action_mode(&ActionMode::Drawing);
+                state.borrow_mut().set_drawing_mode(&DrawingMode::Table);
+            });
+            update_ui_clone(&table_button_clone);
+        });
+    }
+
     // Text mode Handler
     {
         let text_button = document.get_element_by_id("text-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
@@ -1085,6 +2189,67 @@ fn setup_mode_buttons() {
             update_ui_clone(&text_button_clone);
         });
     }
+
+    // Eyedropper mode Handler
+    {
+        let eyedropper_button = document.get_element_by_id("eyedropper-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+        let eyedropper_button_clone = eyedropper_button.clone();
+        let update_ui_clone = update_ui.clone();
+        add_click_listener(&eyedropper_button, move || {
+            STATE.with(|state| {
+                state.borrow_mut().set_action_mode(&ActionMode::Eyedropper);
+            });
+            update_ui_clone(&eyedropper_button_clone);
+        });
+    }
+
+    // Format painter mode Handler
+    {
+        let format_painter_button = document.get_element_by_id("format-painter-mode").unwrap().dyn_into::<HtmlElement>().unwrap();
+        let format_painter_button_clone = format_painter_button.clone();
+        let update_ui_clone = update_ui.clone();
+        add_click_listener(&format_painter_button, move || {
+            // 선택된 도형이 있으면 그 서식을 바로 복사한다.
+            let selected = VecDrawDoc::instance().lock().unwrap().get_selected_shapes();
+            let format = selected.first().map(|shape| ShapeFormat::from_shape(shape.lock().unwrap().as_ref()));
+            STATE.with(|state| {
+                state.borrow_mut().set_action_mode(&ActionMode::FormatPainter);
+                state.borrow_mut().set_copied_format(format);
+            });
+            update_ui_clone(&format_painter_button_clone);
+        });
+    }
+}
+
+/// 캔버스 픽셀 (x, y)의 색상을 (#rrggbb, 불투명도)로 읽는다.
+fn sample_canvas_color(context: &CanvasRenderingContext2d, x: f64, y: f64) -> Option<(String, f64)> {
+    let image_data = context.get_image_data(x.floor(), y.floor(), 1.0, 1.0).ok()?;
+    let data = image_data.data();
+    if data.len() < 4 {
+        return None;
+    }
+
+    Some((format!("#{:02x}{:02x}{:02x}", data[0], data[1], data[2]), data[3] as f64 / 255.0))
+}
+
+/// 추출한 색상을 선 색상(또는 채우기 색상)으로 지정하고 툴바에 반영한다.
+fn apply_sampled_color(color: &str, alpha: f64, as_fill: bool) {
+    let document = window().unwrap().document().unwrap();
+    let input = |id: &str| document.get_element_by_id(id).unwrap().dyn_into::<HtmlInputElement>().unwrap();
+
+    if as_fill {
+        input("fill-enabled").set_checked(true);
+        input("fill-color").set_value(color);
+        input("fill-alpha").set_value(&alpha.to_string());
+        if let Some(fill_type) = document.get_element_by_id("fill-type") {
+            let _ = js_sys::Reflect::set(&fill_type, &"value".into(), &"solid".into());
+        }
+        STATE.with(|state| state.borrow_mut().set_shape_fill(Some(Fill::new(color, alpha))));
+    }
+    else {
+        input("color-picker").set_value(color);
+        STATE.with(|state| state.borrow_mut().set_color(&color.to_string()));
+    }
 }
 
 fn add_click_listener(element: &web_sys::Element, callback: impl Fn() + 'static) {
@@ -1096,28 +2261,1251 @@ fn add_click_listener(element: &web_sys::Element, callback: impl Fn() + 'static)
     closure.forget();
 }
 
-/* 캔버스 좌표 계산 함수
-    마우스 이벤트에서 실제 캔버스 좌표를 계산합니다.
-    줌 레벨과 PAN 오프셋을 반영합니다.
-*/
-fn calculate_canvas_coordinates(mouse_pos: (f64, f64), scroll: (f64, f64)) -> (f64, f64) {
-    STATE.with(|state| {
-        let state = state.borrow();
+/// 표 편집 버튼 id와 선택된 표에 적용할 명령
+type TableCommand = (&'static str, fn(&mut Table));
 
-        let x = (mouse_pos.0 - state.offset().x) / state.scale() + scroll.0;
-        let y = (mouse_pos.1 - state.offset().y) / state.scale() + scroll.1;
-        return (x, y);
-    })
-}
+/// 표 편집 도구(행/열 삽입·삭제, 셀 병합, 셀 채우기·테두리)와 셀 더블 클릭 편집
+fn setup_table_tools() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let canvas = document
+        .get_element_by_id("drawing-canvas")
+        .expect("Canvas element not found")
+        .dyn_into::<HtmlCanvasElement>()?;
 
-fn add_event_listener<T>(canvas: &HtmlCanvasElement, event_type: &str, callback: T) -> Result<(), JsValue>
-where
-    T: 'static + FnMut(MouseEvent),
-{
-    let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(_)>);
-    canvas.add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())?;
-    closure.forget();
-    Ok(())
+    // 🖱️ 선택 모드에서 셀이나 텍스트 박스를 더블 클릭하면 텍스트를 편집한다.
+    {
+        let canvas_clone = canvas.clone();
+        add_event_listener(&canvas, "dblclick", move |event: MouseEvent| {
+            if STATE.with(|state| state.borrow().action_mode() != &ActionMode::Selection) {
+                return;
+            }
+
+            let client_rect = canvas_clone.get_bounding_client_rect();
+            let mouse_x = event.client_x() as f64 - client_rect.left();
+            let mouse_y = event.client_y() as f64 - client_rect.top();
+            let window = web_sys::window().unwrap();
+            let scroll = (window.scroll_x().unwrap_or(0.0), window.scroll_y().unwrap_or(0.0));
+            let (current_x, current_y) = calculate_canvas_coordinates((mouse_x, mouse_y), scroll);
+
+            // 편집 중인 셀 안의 더블 클릭은 단어 선택으로 처리한다.
+            if TEXTBOXMANAGER.with(|tbm| tbm.borrow().as_ref().is_some_and(|manager| manager.hit_attached(current_x, current_y))) {
+                return;
+            }
+
+            let scale = STATE.with(|state| state.borrow().scale());
+            if let Some((table, cell_text)) = find_table_cell_text(current_x, current_y, scale) {
+                TEXTBOXMANAGER.with(|tbm|{
+                    if let Some(ref mut manager) = *tbm.borrow_mut() {
+                        manager.attach_table_cell(table, cell_text);
+                    }
+                });
+            }
+            // 텍스트 박스를 더블 클릭하면 텍스트 도구로 바꿔 다시 편집하고, 편집을 마치면 선택 도구로 돌아온다.
+            else if let Some(text_box) = find_text_box(current_x, current_y, scale) {
+                if let Some(text_button) = window.document().unwrap().get_element_by_id("text-mode").and_then(|element| element.dyn_into::<HtmlElement>().ok()) {
+                    text_button.click();
+                }
+                TEXTBOXMANAGER.with(|tbm|{
+                    if let Some(ref mut manager) = *tbm.borrow_mut() {
+                        manager.attach(text_box);
+                        manager.set_return_tool(Some("selection-mode"));
+                        manager.place_caret_at(current_x, current_y);
+                    }
+                });
+            }
+        })?;
+    }
+
+    let commands: [TableCommand; 6] = [
+        ("table-insert-row", Table::insert_row),
+        ("table-delete-row", Table::delete_row),
+        ("table-insert-column", Table::insert_column),
+        ("table-delete-column", Table::delete_column),
+        ("table-merge-cells", Table::merge_selected_cells),
+        ("table-unmerge-cells", Table::unmerge_selected_cells),
+    ];
+    for (id, command) in commands {
+        let button = document.get_element_by_id(id).unwrap();
+        add_click_listener(&button, move || apply_to_selected_tables(command));
+    }
+
+    // 셀 채우기
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(input) = event.target().and_then(|target| target.dyn_into::<HtmlInputElement>().ok()) {
+                let color = input.value();
+                apply_to_selected_tables(|table| table.set_selected_cells_fill(Some(color.clone())));
+            }
+        }) as Box<dyn FnMut(_)>);
+        let cell_fill = document.get_element_by_id("table-cell-fill").unwrap();
+        cell_fill.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+
+        let button = document.get_element_by_id("table-cell-no-fill").unwrap();
+        add_click_listener(&button, move || apply_to_selected_tables(|table| table.set_selected_cells_fill(None)));
+    }
+
+    // 셀 테두리 (굵기는 현재 선 굵기를 사용)
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(input) = event.target().and_then(|target| target.dyn_into::<HtmlInputElement>().ok()) {
+                let color = input.value();
+                let line_width = STATE.with(|state| state.borrow().line_width());
+                apply_to_selected_tables(|table| table.set_selected_cells_border(&color, line_width));
+            }
+        }) as Box<dyn FnMut(_)>);
+        let cell_border = document.get_element_by_id("table-cell-border").unwrap();
+        cell_border.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    Ok(())
+}
+
+/// (표, 셀의 텍스트 박스)
+type TableCellText = (Arc<Mutex<Box<dyn Shape>>>, Arc<Mutex<Box<dyn Shape>>>);
+
+/// 주어진 좌표에 있는 표와 그 셀의 텍스트 박스를 반환한다.
+fn find_table_cell_text(x: f64, y: f64, scale: f64) -> Option<TableCellText> {
+    let instance = VecDrawDoc::instance();
+    let doc = instance.lock().unwrap();
+    doc.get_shapes_under_mouse(x, y, scale).into_iter().rev().find_map(|shape| {
+        let cell_text = {
+            let shape = shape.lock().unwrap();
+            let table = shape.as_any().downcast_ref::<Table>()?;
+            let (row, col) = table.cell_at(x, y)?;
+            table.cell_text_box(row, col)?
+        };
+        Some((shape, cell_text))
+    })
+}
+
+/// (x, y) 아래에 있는 맨 위 텍스트 박스
+fn find_text_box(x: f64, y: f64, scale: f64) -> Option<Arc<Mutex<Box<dyn Shape>>>> {
+    let instance = VecDrawDoc::instance();
+    let doc = instance.lock().unwrap();
+    doc.get_shapes_under_mouse(x, y, scale).into_iter().rev()
+        .find(|shape| shape.lock().unwrap().as_any().downcast_ref::<TextBox>().is_some())
+}
+
+/// 선택된 표에 f를 적용하고 다시 그린다.
+fn apply_to_selected_tables(f: impl Fn(&mut Table)) {
+    let instance = VecDrawDoc::instance();
+    let doc = instance.lock().unwrap();
+    doc.get_selected_shapes().iter().for_each(|shape| {
+        if let Some(table) = shape.lock().unwrap().as_any_mut().downcast_mut::<Table>() {
+            f(table);
+        }
+    });
+    drop(doc);
+
+    let _ = redraw_document();
+}
+
+/// 그라디언트 정지점 편집기 (정지점 추가/삭제)
+fn setup_gradient_editor() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+
+    {
+        let document_clone = document.clone();
+        let add_stop = document.get_element_by_id("gradient-add-stop").unwrap();
+        add_click_listener(&add_stop, move || {
+            let stops = document_clone.get_element_by_id("gradient-stops").unwrap();
+            if let Ok(row) = create_gradient_stop_row(&document_clone, 1.0, "#ffffff") {
+                let _ = stops.append_child(&row);
+                update_fill_from_toolbar();
+            }
+        });
+    }
+
+    // ❌ 삭제 버튼을 누르면 해당 정지점을 지운다. (최소 2개 유지)
+    {
+        let stops = document.get_element_by_id("gradient-stops").unwrap();
+        let stops_clone = stops.clone();
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(target) = event.target().and_then(|target| target.dyn_into::<Element>().ok()) else { return; };
+            if !target.class_name().split_whitespace().any(|name| name == "stop-remove") || stops_clone.child_element_count() <= 2 {
+                return;
+            }
+            if let Some(row) = target.parent_element() {
+                row.remove();
+                update_fill_from_toolbar();
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        stops.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
+    Ok(())
+}
+
+/// 정지점 편집 행: 색상, 위치, 삭제 버튼
+fn create_gradient_stop_row(document: &Document, offset: f64, color: &str) -> Result<Element, JsValue> {
+    let row = document.create_element("span")?;
+    row.set_class_name("gradient-stop");
+    row.set_inner_html(&format!(
+        r#"<input type="color" class="stop-color" value="{}"><input type="range" class="stop-offset" min="0" max="1" step="0.01" value="{}" title="정지점 위치"><button class="stop-remove" title="정지점 삭제">×</button>"#,
+        color, offset));
+    Ok(row)
+}
+
+/// 툴바의 채우기 설정을 읽는다.
+fn read_fill_from_toolbar(document: &Document) -> Option<Fill> {
+    let input = |id: &str| document.get_element_by_id(id).unwrap().dyn_into::<HtmlInputElement>().unwrap();
+    if !input("fill-enabled").checked() {
+        return None;
+    }
+
+    let alpha = input("fill-alpha").value().parse::<f64>().unwrap_or(1.0);
+    let fill_type = document.get_element_by_id("fill-type")
+        .and_then(|element| js_sys::Reflect::get(&element, &"value".into()).ok())
+        .and_then(|value| value.as_string())
+        .unwrap_or_default();
+    let Some(kind) = GradientKind::from_name(&fill_type) else {
+        return Some(Fill::new(&input("fill-color").value(), alpha));
+    };
+
+    let rows = document.query_selector_all("#gradient-stops .gradient-stop").unwrap();
+    let stops = (0..rows.length()).filter_map(|i| rows.item(i).and_then(|row| row.dyn_into::<Element>().ok())).filter_map(|row| {
+        let color = row.query_selector(".stop-color").ok()??.dyn_into::<HtmlInputElement>().ok()?.value();
+        let offset = row.query_selector(".stop-offset").ok()??.dyn_into::<HtmlInputElement>().ok()?.value().parse::<f64>().ok()?;
+        Some(GradientStop::new(offset, &color, 1.0))
+    }).collect();
+
+    Some(Fill::with_gradient(Gradient::new(kind, stops), alpha))
+}
+
+/// 툴바의 채우기를 새 도형에 사용하도록 저장하고, 선택된 도형에도 적용한다.
+/// 이미 같은 종류의 그라디언트를 가진 도형은 조절점 위치를 유지한다.
+fn update_fill_from_toolbar() {
+    let document = window().unwrap().document().unwrap();
+    let fill = read_fill_from_toolbar(&document);
+    STATE.with(|state| state.borrow_mut().set_shape_fill(fill.clone()));
+
+    let merge = |current: Option<&Fill>| -> Option<Fill> {
+        let mut fill = fill.clone()?;
+        if let (Some(gradient), Some(current)) = (fill.gradient.as_mut(), current.and_then(|current| current.gradient.as_ref())) {
+            if gradient.kind == current.kind {
+                gradient.start = current.start;
+                gradient.end = current.end;
+            }
+        }
+        Some(fill)
+    };
+
+    restyle_selection("fill", |shape| {
+        let fill = merge(shape.style().fill.as_ref());
+        shape.set_fill(fill);
+    });
+}
+
+/// 선택된 도형의 서식을 바꾸고 되돌리기 기록에 남긴 뒤 다시 그린다.
+/// source는 변경을 일으킨 툴바 항목으로, 같은 항목의 연속 입력(슬라이더 드래그 등)은 하나의 변경으로 합쳐진다.
+fn restyle_selection(source: &str, f: impl Fn(&mut dyn Shape)) {
+    let instance = VecDrawDoc::instance();
+    let doc = instance.lock().unwrap();
+    let entries = doc.get_selected_shapes().into_iter().map(|shape| {
+        let mut locked = shape.lock().unwrap();
+        let before = ShapeFormat::from_shape(locked.as_ref());
+        f(locked.as_mut());
+        let after = ShapeFormat::from_shape(locked.as_ref());
+        drop(locked);
+        (shape, before, after)
+    }).collect::<Vec<_>>();
+    drop(doc);
+
+    History::instance().lock().unwrap().record(FormatChange::new(source, entries));
+    let _ = redraw_document();
+}
+
+/// 글자 서식 버튼 id, 켜져 있는지 확인하는 함수, 켜고 끄는 함수
+type RunToggle = (&'static str, fn(&RunStyle) -> bool, fn(&mut RunStyle, bool));
+
+/// 글꼴 도구: 글꼴 이름, 크기, 굵기, 기울임. 편집 중인 텍스트 박스와 선택된 텍스트 박스에 적용하고, 새 텍스트 박스의 기본 글꼴로 쓴다.
+fn setup_text_tools() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+
+    // 글꼴 이름, 크기, 굵기
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(target) = event.target().and_then(|target| target.dyn_into::<Element>().ok()) else { return; };
+            let value = js_sys::Reflect::get(&target, &"value".into()).ok().and_then(|value| value.as_string()).unwrap_or_default();
+
+            match target.id().as_str() {
+                "font-family" => apply_text_font("font-family", |font| font.family = value.clone()),
+                "font-size" => {
+                    if let Ok(size) = value.parse::<f64>() {
+                        apply_text_font("font-size", |font| font.size = size.max(1.0));
+                    }
+                }
+                _ => {
+                    if let Ok(weight) = value.parse::<u16>() {
+                        apply_text_font("font-weight", |font| font.weight = weight);
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        for id in ["font-family", "font-size", "font-weight"] {
+            let element = document.get_element_by_id(id).unwrap();
+            element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+        }
+        closure.forget();
+    }
+
+    // 글자 서식 (편집 중인 텍스트 박스의 선택 범위, 선택 범위가 없으면 다음에 입력할 글자)
+    {
+        let toggles: [RunToggle; 6] = [
+            ("run-bold", |style| style.bold, |style, on| style.bold = on),
+            ("run-italic", |style| style.italic, |style, on| style.italic = on),
+            ("run-underline", |style| style.underline, |style, on| style.underline = on),
+            ("run-strikethrough", |style| style.strikethrough, |style, on| style.strikethrough = on),
+            ("run-superscript", |style| style.script == Script::Superscript,
+                |style, on| style.script = if on { Script::Superscript } else { Script::Normal }),
+            ("run-subscript", |style| style.script == Script::Subscript,
+                |style, on| style.script = if on { Script::Subscript } else { Script::Normal }),
+        ];
+        for (id, is_on, set) in toggles {
+            let button = document.get_element_by_id(id).unwrap();
+            add_click_listener(&button, move || {
+                update_text_runs(|text_box| text_box.toggle_run_style(is_on, set));
+            });
+        }
+
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(target) = event.target().and_then(|target| target.dyn_into::<Element>().ok()) else { return; };
+            let value = js_sys::Reflect::get(&target, &"value".into()).ok().and_then(|value| value.as_string()).unwrap_or_default();
+
+            if target.id() == "run-color" {
+                update_text_runs(|text_box| text_box.update_run_style(|style| style.color = Some(value.clone())));
+            }
+            else if let Ok(size) = value.parse::<f64>() {
+                update_text_runs(|text_box| text_box.update_run_style(|style| style.size = Some(size.max(1.0))));
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        for id in ["run-color", "run-size"] {
+            let element = document.get_element_by_id(id).unwrap();
+            element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+        }
+        closure.forget();
+    }
+
+    // 너비 모드, 가로/세로 정렬
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(target) = event.target().and_then(|target| target.dyn_into::<Element>().ok()) else { return; };
+            let value = js_sys::Reflect::get(&target, &"value".into()).ok().and_then(|value| value.as_string()).unwrap_or_default();
+
+            match target.id().as_str() {
+                "text-wrap" => {
+                    if let Some(wrap) = TextWrap::from_name(&value) {
+                        apply_text_layout("text-wrap", |text_box| text_box.set_wrap(wrap));
+                    }
+                }
+                "text-align" => {
+                    if let Some(align) = TextAlign::from_name(&value) {
+                        STATE.with(|state| {
+                            let (_, vertical_align) = state.borrow().text_align();
+                            state.borrow_mut().set_text_align((align, vertical_align));
+                        });
+                        apply_text_layout("text-align", |text_box| text_box.set_align(align));
+                    }
+                }
+                _ => {
+                    if let Some(vertical_align) = VerticalAlign::from_name(&value) {
+                        STATE.with(|state| {
+                            let (align, _) = state.borrow().text_align();
+                            state.borrow_mut().set_text_align((align, vertical_align));
+                        });
+                        apply_text_layout("text-vertical-align", |text_box| text_box.set_vertical_align(vertical_align));
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        for id in ["text-wrap", "text-align", "text-vertical-align"] {
+            let element = document.get_element_by_id(id).unwrap();
+            element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+        }
+        closure.forget();
+    }
+
+    // 글쓰기 방향 (왼쪽에서 오른쪽, 오른쪽에서 왼쪽, 세로쓰기)
+    {
+        let element = document.get_element_by_id("text-direction").unwrap();
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(target) = event.target().and_then(|target| target.dyn_into::<Element>().ok()) else { return; };
+            let value = js_sys::Reflect::get(&target, &"value".into()).ok().and_then(|value| value.as_string()).unwrap_or_default();
+            if let Some(direction) = TextDirection::from_name(&value) {
+                apply_text_layout("text-direction", |text_box| text_box.set_direction(direction));
+                sync_toolbar_with_selection();  // 방향에 따라 가로 정렬이 바뀐다.
+            }
+        }) as Box<dyn FnMut(_)>);
+        element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    // 경로 글자: 선택한 텍스트 박스를 함께 선택한 선, 베지어 곡선, 호, 자유곡선에 얹거나 뗀다.
+    {
+        let button = document.get_element_by_id("text-on-path").unwrap();
+        add_click_listener(&button, move || {
+            toggle_text_on_path();
+            sync_toolbar_with_selection();
+        });
+
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(target) = event.target().and_then(|target| target.dyn_into::<Element>().ok()) else { return; };
+            let value = js_sys::Reflect::get(&target, &"value".into()).ok().and_then(|value| value.as_string()).unwrap_or_default();
+
+            if target.id() == "path-side" {
+                if let Some(side) = PathSide::from_name(&value) {
+                    apply_text_layout("path-side", |text_box| text_box.update_path(|path| path.side = side));
+                }
+            }
+            else if let Ok(offset) = value.parse::<f64>() {
+                apply_text_layout("path-offset", |text_box| text_box.update_path(|path| path.start_offset = offset.max(0.0)));
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        for id in ["path-side", "path-offset"] {
+            let element = document.get_element_by_id(id).unwrap();
+            element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+        }
+        closure.forget();
+    }
+
+    // 기울임 (누를 때마다 켜고 끈다)
+    {
+        let button = document.get_element_by_id("font-italic").unwrap();
+        let button_clone = button.clone();
+        add_click_listener(&button, move || {
+            let italic = !STATE.with(|state| state.borrow().text_font().italic);
+            button_clone.set_class_name(if italic { "active" } else { "" });
+            apply_text_font("font-italic", |font| font.italic = italic);
+        });
+    }
+
+    Ok(())
+}
+
+/// 글꼴 변경을 새 텍스트 박스의 기본값, 편집 중인 텍스트 박스, 선택된 텍스트 박스에 적용한다.
+fn apply_text_font(source: &str, f: impl Fn(&mut TextFont)) {
+    STATE.with(|state| {
+        let mut font = state.borrow().text_font().clone();
+        f(&mut font);
+        state.borrow_mut().set_text_font(font);
+    });
+
+    let document = window().unwrap().document().unwrap();
+    let context = document.get_element_by_id("drawing-canvas").unwrap()
+        .dyn_into::<HtmlCanvasElement>().unwrap()
+        .get_context("2d").unwrap().unwrap()
+        .dyn_into::<CanvasRenderingContext2d>().unwrap();
+
+    restyle_selection(source, |shape| {
+        if let Some(text_box) = shape.as_any_mut().downcast_mut::<TextBox>() {
+            let mut font = text_box.font().clone();
+            f(&mut font);
+            text_box.set_font(font);
+            text_box.fit_to_text(&context);
+        }
+    });
+
+    TEXTBOXMANAGER.with(|tbm| {
+        if let Some(ref mut manager) = *tbm.borrow_mut() {
+            manager.update_attached(|text_box| {
+                let mut font = text_box.font().clone();
+                f(&mut font);
+                text_box.set_font(font);
+            });
+        }
+    });
+}
+
+/// 편집 중인 텍스트 박스의 글자 서식을 바꾼다.
+fn update_text_runs(f: impl Fn(&mut TextBox)) {
+    TEXTBOXMANAGER.with(|tbm| {
+        if let Some(ref mut manager) = *tbm.borrow_mut() {
+            manager.update_attached(&f);
+        }
+    });
+}
+
+/// 줄바꿈/정렬 변경을 선택된 텍스트 박스와 편집 중인 텍스트 박스에 적용한다.
+fn apply_text_layout(source: &str, f: impl Fn(&mut TextBox)) {
+    let document = window().unwrap().document().unwrap();
+    let context = document.get_element_by_id("drawing-canvas").unwrap()
+        .dyn_into::<HtmlCanvasElement>().unwrap()
+        .get_context("2d").unwrap().unwrap()
+        .dyn_into::<CanvasRenderingContext2d>().unwrap();
+
+    let selected = VecDrawDoc::instance().lock().unwrap().get_selected_shapes();
+    let entries = selected.into_iter().filter_map(|shape| {
+        let mut locked = shape.lock().unwrap();
+        let text_box = locked.as_any_mut().downcast_mut::<TextBox>()?;
+        let before = text_box.text_layout();
+        f(text_box);
+        text_box.relayout(&context);
+        let after = text_box.text_layout();
+        drop(locked);
+        Some((shape, before, after))
+    }).collect::<Vec<_>>();
+
+    // 선택 상자의 change 이벤트 한 번이 한 번의 변경이므로 바로 닫는다.
+    let instance = History::instance();
+    let mut history = instance.lock().unwrap();
+    history.record(FormatChange::text_layout(source, entries));
+    history.seal();
+    drop(history);
+    let _ = redraw_document();
+
+    TEXTBOXMANAGER.with(|tbm| {
+        if let Some(ref mut manager) = *tbm.borrow_mut() {
+            manager.update_attached(&f);
+        }
+    });
+}
+
+/// 문서에서 지운 도형을 경로로 쓰던 텍스트 박스를 경로에서 떼어 내 보통 텍스트 박스로 되돌린다.
+fn detach_orphaned_text_paths(doc: &VecDrawDoc) {
+    for shape in &doc.shapes {
+        let mut locked = shape.lock().unwrap();
+        let Some(text_box) = locked.as_any_mut().downcast_mut::<TextBox>() else { continue; };
+        if text_box.path().is_some() && !text_box.follows_any(&doc.shapes) {
+            text_box.detach_from_path();
+        }
+    }
+}
+
+/// 선택된 텍스트 박스를 함께 선택된 도형(선, 베지어 곡선, 호, 자유곡선)의 경로에 얹는다. 이미 경로에 얹힌 텍스트 박스는 떼어 낸다.
+fn toggle_text_on_path() {
+    let selected = VecDrawDoc::instance().lock().unwrap().get_selected_shapes();
+    let guide = selected.iter().find(|shape| !shape.lock().unwrap().guide_path().is_empty()).cloned();
+
+    for shape in &selected {
+        let mut shape = shape.lock().unwrap();
+        if let Some(text_box) = shape.as_any_mut().downcast_mut::<TextBox>() {
+            if text_box.path().is_some() {
+                text_box.detach_from_path();
+            }
+            else if let Some(guide) = &guide {
+                text_box.attach_to_path(guide.clone());
+            }
+        }
+    }
+    let _ = redraw_document();
+}
+
+/// 찾은 결과로 이동할 때 글자가 이보다 작게 보이면 확대한다. (화면 픽셀)
+const FIND_MIN_SCREEN_HEIGHT: f64 = 24.0;
+
+/// 찾기/바꾸기 창: 텍스트 박스(표 셀 포함)와 가져온 SVG의 글자를 찾고, 텍스트 박스의 글자를 바꾼다.
+fn setup_find_panel() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+
+    // 찾을 내용이나 옵션이 바뀌면 다시 찾는다.
+    {
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            run_find();
+        }) as Box<dyn FnMut(_)>);
+
+        document.get_element_by_id("find-query").unwrap().add_event_listener_with_callback("input", closure.as_ref().unchecked_ref())?;
+        for id in ["find-case", "find-whole-word", "find-regex"] {
+            let element = document.get_element_by_id(id).unwrap();
+            element.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+        }
+        closure.forget();
+    }
+
+    // 찾을 내용 칸: Enter 다음, Shift+Enter 이전 / 바꿀 내용 칸: Enter 바꾸기 / Esc 닫기
+    {
+        let closure = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+            let target_id = event.target().and_then(|target| target.dyn_into::<Element>().ok()).map(|target| target.id()).unwrap_or_default();
+            match event.key().as_str() {
+                "Enter" if target_id == "find-query" => {
+                    event.prevent_default();
+                    step_find(!event.shift_key());
+                }
+                "Enter" if target_id == "replace-text" => {
+                    event.prevent_default();
+                    replace_find_match(false);
+                }
+                "Escape" => {
+                    event.prevent_default();
+                    close_find_panel();
+                }
+                _ => {}
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        document.get_element_by_id("find-panel").unwrap().add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())?;
+        closure.forget();
+    }
+
+    add_click_listener(&document.get_element_by_id("find-next").unwrap(), || step_find(true));
+    add_click_listener(&document.get_element_by_id("find-previous").unwrap(), || step_find(false));
+    add_click_listener(&document.get_element_by_id("find-close").unwrap(), close_find_panel);
+    add_click_listener(&document.get_element_by_id("replace-one").unwrap(), || replace_find_match(false));
+    add_click_listener(&document.get_element_by_id("replace-all").unwrap(), || replace_find_match(true));
+
+    Ok(())
+}
+
+/// 찾기 창을 열고 찾을 내용 칸에 포커스를 준다.
+fn open_find_panel() {
+    let document = window().unwrap().document().unwrap();
+    let _ = document.get_element_by_id("find-panel").unwrap().remove_attribute("hidden");
+
+    let query = document.get_element_by_id("find-query").unwrap().dyn_into::<HtmlInputElement>().unwrap();
+    let _ = query.focus();
+    query.select();
+    run_find();
+}
+
+/// 찾기 창을 닫고 강조 표시를 지운다.
+fn close_find_panel() {
+    let document = window().unwrap().document().unwrap();
+    let _ = document.get_element_by_id("find-panel").unwrap().set_attribute("hidden", "");
+
+    FindReplace::instance().lock().unwrap().clear();
+    let _ = redraw_document();
+}
+
+/// 찾기 창의 찾을 내용과 옵션으로 문서 전체를 다시 찾는다.
+fn run_find() {
+    let document = window().unwrap().document().unwrap();
+    let input = |id: &str| document.get_element_by_id(id).unwrap().dyn_into::<HtmlInputElement>().unwrap();
+    let options = FindOptions{
+        case_sensitive: input("find-case").checked(),
+        whole_word: input("find-whole-word").checked(),
+        regex: input("find-regex").checked(),
+    };
+
+    let result = FindReplace::instance().lock().unwrap().search(&input("find-query").value(), options);
+    match result {
+        Ok(_) => update_find_status(),
+        Err(message) => set_find_status(&message, true),
+    }
+    let _ = redraw_document();   // 지난 강조 표시를 지운다.
+}
+
+/// 다음(forward) 또는 이전 결과로 이동한다.
+fn step_find(forward: bool) {
+    let found = FindReplace::instance().lock().unwrap().step(forward);
+    if let Some(found) = found {
+        show_find_match(&found);
+    }
+    update_find_status();
+}
+
+/// 현재 결과(all이면 텍스트 박스의 모든 결과)를 바꿀 내용으로 바꾼다.
+fn replace_find_match(all: bool) {
+    let document = window().unwrap().document().unwrap();
+    let replacement = document.get_element_by_id("replace-text").unwrap().dyn_into::<HtmlInputElement>().unwrap().value();
+
+    let instance = FindReplace::instance();
+    let mut find = instance.lock().unwrap();
+    if all {
+        let count = find.replace_all(&replacement);
+        drop(find);
+
+        let _ = redraw_document();
+        set_find_status(&format!("{}개를 바꿨습니다.", count), false);
+        return;
+    }
+
+    let next = find.replace_current(&replacement);
+    drop(find);
+    match next {
+        Some(next) => show_find_match(&next),
+        None => { let _ = redraw_document(); }
+    }
+    update_find_status();
+}
+
+/// 찾기 창 아래에 "현재 / 전체"를 표시한다.
+fn update_find_status() {
+    let document = window().unwrap().document().unwrap();
+    let query = document.get_element_by_id("find-query").unwrap().dyn_into::<HtmlInputElement>().unwrap().value();
+
+    let text = match FindReplace::instance().lock().unwrap().status() {
+        _ if query.is_empty() => String::new(),
+        (_, 0) => "찾는 내용이 없습니다.".to_string(),
+        (Some(current), count) => format!("{} / {}", current + 1, count),
+        (None, count) => format!("{}개 찾음", count),
+    };
+    set_find_status(&text, false);
+}
+
+/// 찾기 창 아래에 text를 표시한다. (정규식 오류는 빨간색)
+fn set_find_status(text: &str, is_error: bool) {
+    let document = window().unwrap().document().unwrap();
+    let status = document.get_element_by_id("find-status").unwrap();
+    status.set_text_content(Some(text));
+    status.set_class_name(if is_error { "find-status error" } else { "find-status" });
+}
+
+/// 찾은 결과가 화면 가운데에 보이도록 옮기고 확대한 뒤 강조 표시한다.
+fn show_find_match(found: &TextMatch) {
+    let document = window().unwrap().document().unwrap();
+    let canvas = document.get_element_by_id("drawing-canvas").unwrap().dyn_into::<HtmlCanvasElement>().unwrap();
+    let context = canvas.get_context("2d").unwrap().unwrap().dyn_into::<CanvasRenderingContext2d>().unwrap();
+
+    let bounds = match found.source {
+        MatchSource::TextBox => {
+            found.shape.lock().unwrap().bounds()
+        }
+        MatchSource::SvgText(index) => {
+            let Some(element) = svg_text_element(found, index) else { return; };
+            svg_text_span(&context, &element, found.start, found.end)
+        }
+    };
+    zoom_to_rect(&canvas, &context, bounds.0, bounds.1);
+
+    // 다시 그린 줄 배치에 맞춰 찾은 글자를 칠한다.
+    context.set_fill_style(&JsValue::from_str(&Theme::current().find_color));
+    match found.source {
+        MatchSource::TextBox => {
+            let shape = found.shape.lock().unwrap();
+            if let Some(text_box) = shape.as_any().downcast_ref::<TextBox>() {
+                text_box.fill_glyph_spans(&context, found.start, found.end);
+            }
+        }
+        MatchSource::SvgText(_) => {
+            let (min, max) = bounds;
+            context.fill_rect(min.x, min.y, max.x - min.x, max.y - min.y);
+        }
+    }
+}
+
+/// 찾은 결과가 있는 가져온 SVG의 index 번째 `<text>` 요소
+fn svg_text_element(found: &TextMatch, index: usize) -> Option<SvgText> {
+    let shape = found.shape.lock().unwrap();
+    shape.as_any().downcast_ref::<Svg>()?.text_elements().into_iter().nth(index)
+}
+
+/// SVG `<text>` 요소에서 [start, end) 글자의 영역 (min, max). 변환(transform)은 반영하지 않는다.
+fn svg_text_span(context: &CanvasRenderingContext2d, element: &SvgText, start: usize, end: usize) -> (Point2D, Point2D) {
+    let chars: Vec<char> = element.text.chars().collect();
+    let measure = |chars: &[char]| context.measure_text(&chars.iter().collect::<String>()).map_or(0.0, |metrics| metrics.width());
+
+    context.save();
+    context.set_font(&element.font);
+    let total = measure(&chars);
+    let (left, width) = (measure(&chars[..start]), measure(&chars[start..end]));
+    context.restore();
+
+    let x = element.x + left - match element.anchor.as_str() {
+        "middle" => total * 0.5,
+        "end" => total,
+        _ => 0.0,
+    };
+    (Point2D::new(x, element.y - element.font_size), Point2D::new(x + width, element.y + element.font_size * 0.25))
+}
+
+/// 문서 좌표의 영역이 캔버스 가운데에 오도록 화면을 옮기고 다시 그린다.
+/// 영역이 화면의 절반보다 크면 축소하고, 너무 작게 보이면 확대한다.
+fn zoom_to_rect(canvas: &HtmlCanvasElement, context: &CanvasRenderingContext2d, min: Point2D, max: Point2D) {
+    let client_rect = canvas.get_bounding_client_rect();
+    let (width, height) = ((max.x - min.x).max(1.0), (max.y - min.y).max(1.0));
+
+    STATE.with(|state| {
+        let fit = (client_rect.width() * 0.5 / width).min(client_rect.height() * 0.5 / height);
+        let scale = state.borrow().scale().clamp((FIND_MIN_SCREEN_HEIGHT / height).min(fit), fit);
+        let offset = Point2D::new(
+            client_rect.width() * 0.5 - (min.x + max.x) * 0.5 * scale,
+            client_rect.height() * 0.5 - (min.y + max.y) * 0.5 * scale);
+        state.borrow_mut().set_scale(scale);
+        state.borrow_mut().set_offset(&offset);
+
+        redraw_zoomed(context, &client_rect, &state.borrow());
+    });
+}
+
+/// 배율이나 이동량이 바뀐 뒤 잔상이 남지 않도록 전체 캔버스를 지우고 현재 배율로 다시 그린다.
+fn redraw_zoomed(context: &CanvasRenderingContext2d, client_rect: &DomRect, state: &State) {
+    context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0).unwrap(); // 변환 초기화
+    context.clear_rect(0.0, 0.0, client_rect.width(), client_rect.height()); // 전체 캔버스 지우기
+    context.set_fill_style(&JsValue::from_str(state.fill_color()));
+    context.fill_rect(0.0, 0.0, client_rect.width(), client_rect.height());
+
+    let (scale, offset) = (state.scale(), *state.offset());
+    let _ = context.set_transform(scale, 0.0, 0.0, scale, offset.x, offset.y);
+    let instance = VecDrawDoc::instance();
+    instance.lock().unwrap().draw(context, state);
+}
+
+/// 테마 선택: 선택, 마우스 오버, 조절점, 캐럿 색상과 캔버스 배경을 바꾼다.
+fn setup_theme() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let theme_select = document.get_element_by_id("theme-select").unwrap();
+
+    let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(target) = event.target() else { return; };
+        let name = js_sys::Reflect::get(&target, &"value".into()).ok().and_then(|value| value.as_string()).unwrap_or_default();
+        if let Some(theme) = Theme::from_name(&name) {
+            let _ = apply_theme(theme);
+        }
+    }) as Box<dyn FnMut(_)>);
+
+    theme_select.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+
+    apply_theme(Theme::current())
+}
+
+/// 테마를 현재 테마로 지정하고 화면에 반영한다.
+fn apply_theme(theme: Theme) -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let canvas = document
+        .get_element_by_id("drawing-canvas")
+        .expect("Canvas element not found")
+        .dyn_into::<HtmlCanvasElement>()?;
+
+    // 다시 그릴 때 캔버스를 채우는 색과 캔버스 요소의 배경을 함께 바꾼다.
+    STATE.with(|state| state.borrow_mut().set_fill_color(&theme.canvas_background));
+    canvas.style().set_property("background-color", &theme.canvas_background)?;
+    if let Some(body) = document.body() {
+        body.set_attribute("data-theme", theme.name)?;
+    }
+
+    Theme::set_current(theme);
+    redraw_document()
+}
+
+/// 속성 창: 선택한 도형 하나의 치수와 스타일을 입력 칸으로 보여주고, 입력하는 즉시 도형에 적용한다.
+fn setup_property_inspector() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let canvas = document
+        .get_element_by_id("drawing-canvas")
+        .expect("Canvas element not found")
+        .dyn_into::<HtmlCanvasElement>()?;
+    let context = canvas
+        .get_context("2d")?
+        .ok_or("Failed to get 2D context")?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+
+    let list = document.get_element_by_id("property-list").unwrap();
+    {
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(target) = event.target().and_then(|target| target.dyn_into::<Element>().ok()) else { return; };
+            let Some(name) = target.get_attribute("data-property") else { return; };
+            let value = js_sys::Reflect::get(&target, &"value".into()).ok().and_then(|value| value.as_string()).unwrap_or_default();
+
+            let selected = VecDrawDoc::instance().lock().unwrap().get_selected_shapes();
+            let [shape] = selected.as_slice() else { return; };
+
+            match name.as_str() {
+                // 스타일은 툴바와 같이 되돌리기 기록에 남긴다.
+                "color" => restyle_selection("inspector-color", |shape| shape.set_color(&value)),
+                "line_width" => {
+                    if let Ok(width) = value.parse::<f64>() {
+                        restyle_selection("inspector-line-width", |shape| shape.set_line_width(width.max(0.0)));
+                    }
+                }
+                _ => {
+                    let checkbox = target.dyn_ref::<HtmlInputElement>().filter(|input| input.type_() == "checkbox");
+                    let value = if let Some(checkbox) = checkbox {
+                        PropertyValue::Flag(checkbox.checked())
+                    } else if target.tag_name() == "TEXTAREA" {
+                        PropertyValue::Text(value)
+                    } else {
+                        match value.parse::<f64>() {
+                            Ok(number) => PropertyValue::Number(number),
+                            Err(_) => return,   // 입력 중인 값("-" 등)은 무시
+                        }
+                    };
+
+                    let mut locked = shape.lock().unwrap();
+                    let Some(before) = locked.properties().into_iter().find(|property| property.name == name).map(|property| property.value) else { return; };
+                    locked.set_property(&name, &value);
+                    if let Some(text_box) = locked.as_any_mut().downcast_mut::<TextBox>() {
+                        text_box.fit_to_text(&context);
+                    }
+                    drop(locked);
+
+                    // 기하 속성도 되돌리기 기록에 남긴다. (같은 칸의 연속 입력은 하나로 합친다)
+                    let source = format!("inspector-{}", name);
+                    let toggled = matches!(value, PropertyValue::Flag(_));
+                    History::instance().lock().unwrap().record(FormatChange::property(&source, Arc::clone(shape), &name, before, value));
+                    let _ = redraw_document();
+
+                    // 켜고 끄는 속성은 보이는 입력 칸을 바꿀 수 있다. (예: 모서리별 반지름)
+                    if toggled {
+                        refresh_property_inspector();
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        list.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
+    // 입력을 마치면 진행 중인 서식 변경을 닫는다.
+    {
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            History::instance().lock().unwrap().seal();
+        }) as Box<dyn FnMut(_)>);
+
+        list.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref()).unwrap();
+        closure.forget();
+    }
+
+    refresh_property_inspector();
+    Ok(())
+}
+
+/// 선택한 도형의 현재 값으로 속성 창을 다시 만든다.
+fn refresh_property_inspector() {
+    let document = window().unwrap().document().unwrap();
+    let Some(list) = document.get_element_by_id("property-list") else { return; };
+    list.set_inner_html("");
+
+    let selected = VecDrawDoc::instance().lock().unwrap().get_selected_shapes();
+    let [shape] = selected.as_slice() else {
+        let message = document.create_element("p").unwrap();
+        message.set_text_content(Some(if selected.is_empty() { "선택한 도형이 없습니다." } else { "도형을 하나만 선택하세요." }));
+        let _ = list.append_child(&message);
+        return;
+    };
+
+    let (properties, style) = {
+        let shape = shape.lock().unwrap();
+        (shape.properties(), shape.style())
+    };
+
+    for property in properties {
+        if let Ok(row) = create_property_row(&document, property.name, property.label, &property.value) {
+            let _ = list.append_child(&row);
+        }
+    }
+
+    // 스타일: 선 색상, 선 굵기 (선 굵기가 없는 도형은 색상만)
+    if let Ok(row) = create_property_row(&document, "color", "선 색상", &PropertyValue::Text(style.color.clone())) {
+        let _ = list.append_child(&row);
+    }
+    if style.line_width > 0.0 {
+        if let Ok(row) = create_property_row(&document, "line_width", "선 굵기", &PropertyValue::Number(style.line_width)) {
+            let _ = list.append_child(&row);
+        }
+    }
+}
+
+/// 속성 창의 한 줄: 이름과 입력 칸 (숫자는 number 입력, 색상은 color 입력, 그 밖의 텍스트는 textarea)
+fn create_property_row(document: &Document, name: &str, label: &str, value: &PropertyValue) -> Result<Element, JsValue> {
+    let row = document.create_element("label")?;
+    row.set_class_name("property-row");
+
+    let caption = document.create_element("span")?;
+    caption.set_text_content(Some(label));
+    row.append_child(&caption)?;
+
+    let field: Element = match value {
+        PropertyValue::Number(number) => {
+            let input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+            input.set_type("number");
+            input.set_step("any");
+            input.set_value(&((number * 100.0).round() / 100.0).to_string());
+            input.into()
+        }
+        PropertyValue::Text(text) if name == "color" => {
+            let input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+            input.set_type("color");
+            input.set_value(text);
+            input.into()
+        }
+        PropertyValue::Flag(checked) => {
+            let input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+            input.set_type("checkbox");
+            input.set_checked(*checked);
+            input.into()
+        }
+        PropertyValue::Text(text) => {
+            let textarea = document.create_element("textarea")?.dyn_into::<HtmlTextAreaElement>()?;
+            textarea.set_rows(3);
+            textarea.set_value(text);
+            textarea.into()
+        }
+    };
+    field.set_attribute("data-property", name)?;
+    row.append_child(&field)?;
+
+    Ok(row)
+}
+
+/// 요소의 "mixed" 클래스를 켜거나 끈다. (선택한 도형마다 값이 다를 때 표시)
+fn set_mixed(element: &Element, mixed: bool) {
+    let mut names = element.class_name().split_whitespace().filter(|name| *name != "mixed").map(str::to_string).collect::<Vec<_>>();
+    if mixed {
+        names.push("mixed".to_string());
+    }
+    element.set_class_name(&names.join(" "));
+
+    // 원래 툴팁은 data-title에 보관해 두었다가 되돌린다.
+    let title = element.get_attribute("data-title").or_else(|| element.get_attribute("title")).unwrap_or_default();
+    let _ = element.set_attribute("data-title", &title);
+    let _ = element.set_attribute("title", &if mixed { format!("{} (여러 값)", title).trim_start().to_string() } else { title });
+}
+
+/// 모든 값이 같으면 그 값을, 다르면 None을 반환한다.
+fn common_value<T: PartialEq>(mut values: impl Iterator<Item = T>) -> Option<T> {
+    let first = values.next()?;
+    values.all(|value| value == first).then_some(first)
+}
+
+/// 선택이 없으면 "여러 값" 표시를 모두 지운다. 툴바에는 새로 그릴 도형의 값이 남는다.
+fn reset_toolbar_mixed_state(document: &Document) {
+    for id in ["fill-enabled", "shadow-enabled"] {
+        document.get_element_by_id(id).unwrap().dyn_into::<HtmlInputElement>().unwrap().set_indeterminate(false);
+    }
+
+    let _ = document.get_element_by_id("text-vertical-align").unwrap().remove_attribute("disabled");
+
+    let Ok(mixed) = document.query_selector_all(".mixed") else { return; };
+    for index in 0..mixed.length() {
+        let Some(element) = mixed.get(index).and_then(|node| node.dyn_into::<Element>().ok()) else { continue; };
+        set_mixed(&element, false);
+
+        // 선택 상자는 숨겨진 "여러 값" 대신 첫 번째 항목을 보인다.
+        if element.tag_name().eq_ignore_ascii_case("select") {
+            let value = js_sys::Reflect::get(&element, &"value".into()).ok().and_then(|value| value.as_string()).unwrap_or_default();
+            if value.is_empty() || value == "mixed" {
+                if let Some(option) = element.query_selector("option:not([hidden])").ok().flatten() {
+                    let _ = js_sys::Reflect::set(&element, &"value".into(), &option.get_attribute("value").unwrap_or_default().into());
+                }
+            }
+        }
+    }
+}
+
+/// 선택된 도형의 서식을 툴바에 표시한다. 선택한 도형마다 값이 다른 항목은 "여러 값"으로 표시한다.
+fn sync_toolbar_with_selection() {
+    let selected = VecDrawDoc::instance().lock().unwrap().get_selected_shapes();
+    let document = window().unwrap().document().unwrap();
+    if selected.is_empty() {
+        reset_toolbar_mixed_state(&document);
+        return;
+    }
+    let formats = selected.iter().map(|shape| ShapeFormat::from_shape(shape.lock().unwrap().as_ref())).collect::<Vec<_>>();
+
+    let element = |id: &str| document.get_element_by_id(id).unwrap();
+    let input = |id: &str| element(id).dyn_into::<HtmlInputElement>().unwrap();
+    let set_select = |id: &str, value: Option<&str>| {
+        let select = element(id);
+        let _ = js_sys::Reflect::set(&select, &"value".into(), &value.unwrap_or("mixed").into());
+        set_mixed(&select, value.is_none());
+    };
+
+    // 선 색상
+    let color = common_value(formats.iter().map(|format| &format.style.color));
+    if let Some(color) = color {
+        input("color-picker").set_value(color);
+        STATE.with(|state| state.borrow_mut().set_color(color));
+    }
+    set_mixed(&element("color-picker"), color.is_none());
+
+    // 선 굵기
+    let line_width = common_value(formats.iter().map(|format| format.style.line_width));
+    if let Some(line_width) = line_width {
+        input("line-width").set_value(&line_width.to_string());
+        STATE.with(|state| state.borrow_mut().set_line_width(line_width));
+    }
+    set_mixed(&element("line-width"), line_width.is_none());
+
+    // 채우기
+    let fill = common_value(formats.iter().map(|format| &format.style.fill));
+    let fill_enabled = input("fill-enabled");
+    fill_enabled.set_indeterminate(fill.is_none());
+    match fill {
+        Some(Some(fill)) => {
+            fill_enabled.set_checked(true);
+            input("fill-color").set_value(&fill.color);
+            input("fill-alpha").set_value(&fill.alpha.to_string());
+            set_select("fill-type", Some(fill.gradient.as_ref().map_or("solid", |gradient| gradient.kind.as_str())));
+
+            // 그라디언트 정지점 편집 행을 다시 만든다.
+            if let Some(gradient) = &fill.gradient {
+                let stops = element("gradient-stops");
+                stops.set_inner_html("");
+                for stop in &gradient.stops {
+                    if let Ok(row) = create_gradient_stop_row(&document, stop.offset, &stop.color) {
+                        let _ = stops.append_child(&row);
+                    }
+                }
+            }
+            STATE.with(|state| state.borrow_mut().set_shape_fill(Some(fill.clone())));
+        }
+        Some(None) => {
+            fill_enabled.set_checked(false);
+            STATE.with(|state| state.borrow_mut().set_shape_fill(None));
+        }
+        None => {}
+    }
+    set_mixed(&element("fill-color"), fill.is_none());
+
+    // 선 스타일
+    let dash = common_value(formats.iter().map(|format| &format.style.stroke_style.dash));
+    let dash_name = dash.and_then(|dash| ["solid", "dashed", "dotted", "dash-dot"].into_iter()
+        .find(|name| StrokeStyle::dash_preset(name).as_ref() == Some(dash)));
+    set_select("stroke-dash", dash_name);
+    let dash_offset = common_value(formats.iter().map(|format| format.style.stroke_style.dash_offset));
+    if let Some(dash_offset) = dash_offset {
+        input("dash-offset").set_value(&dash_offset.to_string());
+    }
+    set_mixed(&element("dash-offset"), dash_offset.is_none());
+    set_select("line-cap", common_value(formats.iter().map(|format| format.style.stroke_style.cap)).map(|cap| cap.as_str()));
+    set_select("line-join", common_value(formats.iter().map(|format| format.style.stroke_style.join)).map(|join| join.as_str()));
+    let miter_limit = common_value(formats.iter().map(|format| format.style.stroke_style.miter_limit));
+    if let Some(miter_limit) = miter_limit {
+        input("miter-limit").set_value(&miter_limit.to_string());
+    }
+    set_mixed(&element("miter-limit"), miter_limit.is_none());
+    if let Some(stroke_style) = common_value(formats.iter().map(|format| &format.style.stroke_style)) {
+        STATE.with(|state| state.borrow_mut().set_stroke_style(stroke_style.clone()));
+    }
+
+    // 불투명도, 혼합 모드
+    let opacity = common_value(formats.iter().map(|format| format.appearance.opacity));
+    if let Some(opacity) = opacity {
+        input("opacity").set_value(&opacity.to_string());
+    }
+    set_mixed(&element("opacity"), opacity.is_none());
+    set_select("blend-mode", common_value(formats.iter().map(|format| format.appearance.blend_mode)).map(|blend_mode| blend_mode.as_str()));
+
+    // 글꼴 (텍스트 박스가 있을 때만)
+    if let Some(font) = common_value(formats.iter().filter_map(|format| format.style.font.as_ref())) {
+        set_select("font-family", Some(&font.family));
+        input("font-size").set_value(&font.size.to_string());
+        set_select("font-weight", Some(&font.weight.to_string()));
+        element("font-italic").set_class_name(if font.italic { "active" } else { "" });
+        STATE.with(|state| state.borrow_mut().set_text_font(font.clone()));
+    }
+
+    // 너비 모드, 정렬 (텍스트 박스가 있을 때만)
+    let text_boxes = selected.iter().filter_map(|shape| {
+        let shape = shape.lock().unwrap();
+        shape.as_any().downcast_ref::<TextBox>().map(|text_box| (text_box.wrap(), text_box.align(), text_box.vertical_align(), text_box.direction()))
+    }).collect::<Vec<_>>();
+    if !text_boxes.is_empty() {
+        set_select("text-wrap", common_value(text_boxes.iter().map(|layout| layout.0)).map(|wrap| wrap.as_str()));
+        set_select("text-align", common_value(text_boxes.iter().map(|layout| layout.1)).map(|align| align.as_str()));
+        set_select("text-vertical-align", common_value(text_boxes.iter().map(|layout| layout.2)).map(|vertical_align| vertical_align.as_str()));
+        set_select("text-direction", common_value(text_boxes.iter().map(|layout| layout.3)).map(|direction| direction.as_str()));
+    }
+    // 세로쓰기는 줄(열)을 오른쪽부터 쌓고 너비를 내용에 맞추므로 세로 정렬을 쓰지 않는다.
+    let all_vertical = !text_boxes.is_empty() && text_boxes.iter().all(|layout| layout.3 == TextDirection::Vertical);
+    let _ = element("text-vertical-align").toggle_attribute_with_force("disabled", all_vertical);
+
+    // 경로 글자 (경로에 얹힌 텍스트 박스가 있을 때만)
+    let paths = selected.iter().filter_map(|shape| {
+        let shape = shape.lock().unwrap();
+        shape.as_any().downcast_ref::<TextBox>().and_then(|text_box| text_box.path().map(|path| (path.side, path.start_offset)))
+    }).collect::<Vec<_>>();
+    element("text-on-path").set_class_name(if paths.is_empty() { "" } else { "active" });
+    if !paths.is_empty() {
+        set_select("path-side", common_value(paths.iter().map(|path| path.0)).map(|side| side.as_str()));
+        let offset = common_value(paths.iter().map(|path| path.1));
+        if let Some(offset) = offset {
+            input("path-offset").set_value(&offset.to_string());
+        }
+        set_mixed(&element("path-offset"), offset.is_none());
+    }
+
+    // 변 개수, 별 안쪽 비율 (정다각형이 있을 때만)
+    let polygons = selected.iter().filter_map(|shape| {
+        let shape = shape.lock().unwrap();
+        shape.as_any().downcast_ref::<RegularPolygon>().map(|polygon| (polygon.sides(), polygon.is_star().then(|| polygon.inner_ratio())))
+    }).collect::<Vec<_>>();
+    if !polygons.is_empty() {
+        let sides = common_value(polygons.iter().map(|polygon| polygon.0));
+        if let Some(sides) = sides {
+            input("polygon-sides").set_value(&sides.to_string());
+            STATE.with(|state| state.borrow_mut().set_polygon_sides(sides));
+        }
+        set_mixed(&element("polygon-sides"), sides.is_none());
+
+        let inner_ratio = common_value(polygons.iter().filter_map(|polygon| polygon.1));
+        if let Some(inner_ratio) = inner_ratio {
+            input("star-inner-ratio").set_value(&inner_ratio.to_string());
+            STATE.with(|state| state.borrow_mut().set_star_inner_ratio(inner_ratio));
+        }
+        set_mixed(&element("star-inner-ratio"), inner_ratio.is_none() && polygons.iter().any(|polygon| polygon.1.is_some()));
+    }
+
+    // 그림자, 흐림
+    let effects = common_value(formats.iter().map(|format| &format.appearance.effects));
+    let shadow_enabled = input("shadow-enabled");
+    shadow_enabled.set_indeterminate(effects.is_none());
+    if let Some(effects) = effects {
+        shadow_enabled.set_checked(effects.shadow.is_some());
+        if let Some(shadow) = &effects.shadow {
+            input("shadow-color").set_value(&shadow.color);
+            input("shadow-alpha").set_value(&shadow.alpha.to_string());
+            input("shadow-offset-x").set_value(&shadow.offset_x.to_string());
+            input("shadow-offset-y").set_value(&shadow.offset_y.to_string());
+            input("shadow-blur").set_value(&shadow.blur.to_string());
+        }
+        input("effect-blur").set_value(&effects.blur.to_string());
+    }
+    set_mixed(&element("effect-blur"), effects.is_none());
+}
+
+/// 드래그한 두 점(중심, 꼭짓점)으로 현재 모드의 정다각형 또는 별을 생성한다.
+fn create_regular_polygon(state: &State, center: Point2D, end: Point2D) -> RegularPolygon {
+    let radius = Vector2D::from_points(center, end).length();
+    if state.drawing_mode() == &DrawingMode::Star {
+        RegularPolygon::new_star(state.color().to_string(), state.line_width(), center, radius, state.polygon_sides(), state.star_inner_ratio())
+    } else {
+        RegularPolygon::new(state.color().to_string(), state.line_width(), center, radius, state.polygon_sides())
+    }
+}
+
+/* 캔버스 좌표 계산 함수
+    마우스 이벤트에서 실제 캔버스 좌표를 계산합니다.
+    줌 레벨과 PAN 오프셋을 반영합니다.
+*/
+fn calculate_canvas_coordinates(mouse_pos: (f64, f64), scroll: (f64, f64)) -> (f64, f64) {
+    STATE.with(|state| {
+        let state = state.borrow();
+
+        let x = (mouse_pos.0 - state.offset().x) / state.scale() + scroll.0;
+        let y = (mouse_pos.1 - state.offset().y) / state.scale() + scroll.1;
+        return (x, y);
+    })
+}
+
+fn add_event_listener<T>(canvas: &HtmlCanvasElement, event_type: &str, callback: T) -> Result<(), JsValue>
+where
+    T: 'static + FnMut(MouseEvent),
+{
+    let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(_)>);
+    canvas.add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+fn add_pointerevent_listener<T>(canvas: &HtmlCanvasElement, event_type: &str, callback: T) -> Result<(), JsValue>
+where
+    T: 'static + FnMut(PointerEvent),
+{
+    let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(_)>);
+    canvas.add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
+/// 포인터 이벤트에서 필압/기울기/시간을 읽는다. 마우스는 필압이 없는 것으로 본다.
+fn stroke_sample(event: &PointerEvent) -> StrokeSample {
+    let pressure = if event.pointer_type() == "mouse" { None } else { Some(event.pressure() as f64) };
+    StrokeSample {
+        pressure,
+        tilt_x: event.tilt_x() as f64,
+        tilt_y: event.tilt_y() as f64,
+        time: event.time_stamp(),
+    }
 }
 
 fn add_wheelevent_listener<T>(canvas: &HtmlCanvasElement, event_type: &str, callback: T) -> Result<(), JsValue>
@@ -0,0 +1,267 @@
+use std::any::Any;
+use wasm_bindgen::prelude::*;
+
+use web_sys::CanvasRenderingContext2d;
+
+use super::geometry::{Point2D, Vector2D};
+use super::theme::Theme;
+use super::shape::{Shape, StrokeStyle, Appearance, ShapeStyle, Property, PropertyValue};
+use super::text_path::project_to_polyline;
+
+/// 경로로 쓰거나 영역을 잴 때 곡선을 나누는 선분 개수
+const CURVE_SEGMENTS: usize = 32;
+
+/// 3차 베지어 곡선 (시작점, 제어점 두 개, 끝점)
+#[derive(Debug, Clone)]
+pub struct Curve{
+    selected: bool,
+    hovered: bool,
+    color: String,
+    line_width: f64,
+    start: Point2D,
+    control1: Point2D,
+    control2: Point2D,
+    end: Point2D,
+    stroke_style: StrokeStyle,
+    appearance: Appearance,
+    selected_control_point: i32,
+}
+impl Curve {
+    /// start에서 end까지 끄는 동안 만드는 곡선. 제어점은 현의 1/3, 2/3 지점에서 현의 왼쪽으로 들어 올려 완만한 호가 되게 한다.
+    pub fn new(color: String, line_width: f64, start: Point2D, end: Point2D) -> Self {
+        let chord = Vector2D::from_points(start, end);
+        let lift = Vector2D::new(chord.y * 0.25, -chord.x * 0.25);
+        Curve {
+            selected: false,
+            hovered: false,
+            color,
+            line_width,
+            start,
+            control1: Point2D::new(start.x + chord.x / 3.0 + lift.x, start.y + chord.y / 3.0 + lift.y),
+            control2: Point2D::new(start.x + chord.x * 2.0 / 3.0 + lift.x, start.y + chord.y * 2.0 / 3.0 + lift.y),
+            end,
+            stroke_style: StrokeStyle::default(),
+            appearance: Appearance::default(),
+            selected_control_point: -1,}
+    }
+
+    /// 0: 시작점, 1: 첫 번째 제어점, 2: 두 번째 제어점, 3: 끝점
+    fn control_points(&self) -> [Point2D; 4]{
+        [self.start, self.control1, self.control2, self.end]
+    }
+
+    /// t(0~1) 위치의 점
+    fn point_at(&self, t: f64) -> Point2D{
+        let u = 1.0 - t;
+        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+        Point2D::new(
+            a * self.start.x + b * self.control1.x + c * self.control2.x + d * self.end.x,
+            a * self.start.y + b * self.control1.y + c * self.control2.y + d * self.end.y)
+    }
+
+    /// 곡선을 꺾은선으로 나눈다.
+    fn flatten(&self) -> Vec<Point2D>{
+        (0..=CURVE_SEGMENTS).map(|i| self.point_at(i as f64 / CURVE_SEGMENTS as f64)).collect()
+    }
+
+    fn trace(&self, context: &CanvasRenderingContext2d){
+        context.begin_path();
+        context.move_to(self.start.x, self.start.y);
+        context.bezier_curve_to(self.control1.x, self.control1.y, self.control2.x, self.control2.y, self.end.x, self.end.y);
+    }
+}
+
+impl Shape for Curve{
+    fn color(&self) -> &str {
+        &self.color
+    }
+
+    fn line_width(&self) -> f64 {
+        self.line_width
+    }
+
+    fn is_hit(&self, x: f64, y: f64, scale: f64) -> bool {
+        let (_, distance) = project_to_polyline(&self.flatten(), Point2D::new(x, y));
+        distance < (5.0 / scale).max(self.line_width * 0.5)
+    }
+
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.line_width = width;
+    }
+
+    fn set_stroke_style(&mut self, style: StrokeStyle) {
+        self.stroke_style = style;
+    }
+
+    fn max_point(&self) -> Point2D{
+        self.flatten().iter().fold(Point2D::new(f64::MIN, f64::MIN), |acc, point| Point2D::new(acc.x.max(point.x), acc.y.max(point.y)))
+    }
+
+    fn min_point(&self) -> Point2D{
+        self.flatten().iter().fold(Point2D::new(f64::MAX, f64::MAX), |acc, point| Point2D::new(acc.x.min(point.x), acc.y.min(point.y)))
+    }
+
+    fn get_control_point(&self, x: f64, y: f64, scale: f64) -> i32{
+        let adjusted_width = 1.0 / scale * 5.0;
+        self.control_points().iter()
+            .position(|p| (x - p.x).powi(2) + (y - p.y).powi(2) < adjusted_width * adjusted_width)
+            .map_or(-1, |i| i as i32)
+    }
+
+    fn get_selected_control_point(&self) -> i32 {
+        self.selected_control_point
+    }
+
+    fn set_selected_control_point(&mut self, index: i32) {
+        self.selected_control_point = index;
+    }
+
+    fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, selected: bool){
+        self.selected = selected;
+    }
+
+    fn set_hovered(&mut self, value: bool) {
+        self.hovered = value;
+    }
+
+    fn move_by(&mut self, dx: f64, dy: f64) {
+        for point in [&mut self.start, &mut self.control1, &mut self.control2, &mut self.end] {
+            point.x += dx;
+            point.y += dy;
+        }
+    }
+
+    fn move_control_point_by(&mut self, index: i32, dx: f64, dy: f64) {
+        let point = match index {
+            0 => &mut self.start,
+            1 => &mut self.control1,
+            2 => &mut self.control2,
+            3 => &mut self.end,
+            _ => return,
+        };
+        point.x += dx;
+        point.y += dy;
+    }
+
+    fn draw(&mut self, context: &CanvasRenderingContext2d, scale: f64){
+        context.save();
+        self.appearance.apply(context);
+        if self.hovered{
+            context.set_stroke_style(&JsValue::from_str(&Theme::current().hover_color));
+        }
+        else{
+            context.set_stroke_style(&JsValue::from_str(&self.color));
+        }
+        let adjusted_width = self.line_width / scale;
+        context.set_line_width(adjusted_width);
+        self.stroke_style.apply(context, adjusted_width);
+        self.trace(context);
+        context.stroke();
+        context.restore();
+
+        if self.selected{ self.draw_control_points(context, scale);}
+    }
+
+    fn draw_xor(&self, context: &CanvasRenderingContext2d, scale: f64){
+        context.save();
+        context.set_global_composite_operation("xor").expect("something goes wrong when apply xor");
+
+        self.trace(context);
+        context.set_stroke_style(&JsValue::from_str(&self.color));
+        context.set_line_width(self.line_width / scale);
+
+        context.stroke();
+        context.restore();
+    }
+
+    /// 끝점과 제어점을 잇는 접선과 조절점 (제어점은 원으로 그린다)
+    fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
+        let theme = Theme::current();
+
+        context.save();
+        theme.apply_selection_outline(context, scale);
+        context.begin_path();
+        context.move_to(self.start.x, self.start.y);
+        context.line_to(self.control1.x, self.control1.y);
+        context.move_to(self.end.x, self.end.y);
+        context.line_to(self.control2.x, self.control2.y);
+        context.stroke();
+        context.restore();
+
+        theme.draw_handles(context, &[self.start, self.end], scale);
+        theme.draw_round_handle(context, self.control1, scale);
+        theme.draw_round_handle(context, self.control2, scale);
+    }
+
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
+
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
+
+    fn apply_style(&mut self, style: &ShapeStyle) {
+        self.color = style.color.clone();
+        self.line_width = style.line_width;
+        self.stroke_style = style.stroke_style.clone();
+    }
+
+    fn style(&self) -> ShapeStyle {
+        ShapeStyle{ color: self.color.clone(), line_width: self.line_width, fill: None, stroke_style: self.stroke_style.clone(), font: None }
+    }
+
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property::number("start_x", "시작점 X", self.start.x),
+            Property::number("start_y", "시작점 Y", self.start.y),
+            Property::number("control1_x", "제어점 1 X", self.control1.x),
+            Property::number("control1_y", "제어점 1 Y", self.control1.y),
+            Property::number("control2_x", "제어점 2 X", self.control2.x),
+            Property::number("control2_y", "제어점 2 Y", self.control2.y),
+            Property::number("end_x", "끝점 X", self.end.x),
+            Property::number("end_y", "끝점 Y", self.end.y),
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: &PropertyValue) {
+        let Some(value) = value.as_number() else { return; };
+        match name {
+            "start_x" => self.start.x = value,
+            "start_y" => self.start.y = value,
+            "control1_x" => self.control1.x = value,
+            "control1_y" => self.control1.y = value,
+            "control2_x" => self.control2.x = value,
+            "control2_y" => self.control2.y = value,
+            "end_x" => self.end.x = value,
+            "end_y" => self.end.y = value,
+            _ => {}
+        }
+    }
+
+    fn guide_path(&self) -> Vec<Point2D> {
+        self.flatten()
+    }
+
+    fn to_svg(&self) -> String {
+        format!(r#"<path d="M {} {} C {} {} {} {} {} {}" fill="none" stroke="{}" stroke-width="{}"{}/>"#,
+            self.start.x, self.start.y, self.control1.x, self.control1.y, self.control2.x, self.control2.y, self.end.x, self.end.y,
+            self.color, self.line_width, self.stroke_style.svg_attributes(self.line_width))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -16,7 +16,8 @@ use web_sys::{window, CanvasRenderingContext2d};
 
 use super::geometry::Vector2D;
 use super::geometry::{Point2D};
-use super::shape::{Shape};
+use super::theme::Theme;
+use super::shape::{Shape, Fill, StrokeStyle, Appearance, ShapeStyle, Property, PropertyValue, svg_fill_attributes};
 
 #[derive(Debug, Clone)]
 pub struct Ellipse{
@@ -32,6 +33,9 @@ pub struct Ellipse{
     line_width: f64,
     axis_x: Vector2D,
     axis_y: Vector2D,
+    fill: Option<Fill>,
+    stroke_style: StrokeStyle,
+    appearance: Appearance,
     selected_control_point: i32
 }
 impl Ellipse{
@@ -49,9 +53,24 @@ impl Ellipse{
             line_width , 
             axis_x: Vector2D::AXIS_X, 
             axis_y: Vector2D::AXIS_Y,
+            fill: None,
+            stroke_style: StrokeStyle::default(),
+            appearance: Appearance::default(),
             selected_control_point: -1}
     }
 
+    /// 회전하지 않은 좌표계에서의 (left, top, right, bottom)
+    fn local_bounds(&self) -> (f64, f64, f64, f64){
+        let (rx, ry) = (self.radius_x.abs(), self.radius_y.abs());
+        (self.center.x - rx, self.center.y - ry, self.center.x + rx, self.center.y + ry)
+    }
+
+    /// 그라디언트 채우기 조절점 (채우기가 그라디언트일 때만)
+    fn gradient_control_points(&self) -> Vec<Point2D>{
+        self.fill.as_ref().and_then(|fill| fill.gradient.as_ref())
+            .map_or(Vec::new(), |gradient| gradient.handle_points(self.local_bounds()).to_vec())
+    }
+
     fn control_points(&self) -> Vec<Point2D>{
         let control_pts = vec![
             Point2D::new(self.center.x - self.radius_x, self.center.y - self.radius_y), 
@@ -65,7 +84,7 @@ impl Ellipse{
             Point2D::new(self.center.x, self.center.y - self.radius_y - 30.0)
             ];
 
-        control_pts
+        control_pts.into_iter().chain(self.gradient_control_points()).collect()
     }
 
     fn axis_x(&self) -> Vector2D{
@@ -89,6 +108,22 @@ impl Shape for Ellipse{
         self.line_width
     }
 
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.line_width = width;
+    }
+
+    fn set_fill(&mut self, fill: Option<Fill>) {
+        self.fill = fill;
+    }
+
+    fn set_stroke_style(&mut self, style: StrokeStyle) {
+        self.stroke_style = style;
+    }
+
     fn max_point(&self) -> Point2D{
         Point2D::new(self.center.x + self.radius_x, self.center.y + self.radius_y)
     }
@@ -101,13 +136,16 @@ impl Shape for Ellipse{
         let index = self.get_control_point(x, y, scale);
         if index != -1{return true;}
 
-        let min_pt = self.min_point();
-        let max_pt = self.max_point();
+        let (rx, ry) = (self.radius_x.abs(), self.radius_y.abs());
+        if rx <= 0.0 || ry <= 0.0 {return false;}
 
-        if x < min_pt.x || x > max_pt.x {return false;}
-        if y < min_pt.y || y > max_pt.y {return false;}
+        // 회전을 되돌린 좌표에서 중심으로부터의 (정규화된) 거리로 확인한다.
+        let mut dir = Vector2D::new(x - self.center.x, y - self.center.y);
+        dir.rotate_by(-self.rotation);
+        let distance = ((dir.x / rx).powi(2) + (dir.y / ry).powi(2)).sqrt();
+        let tolerance = (5.0 + self.line_width * 0.5) / scale / rx.min(ry);
 
-        true
+        distance <= 1.0 + tolerance
     }
 
     fn get_control_point(&self, x: f64, y: f64, scale: f64) -> i32{
@@ -119,8 +157,9 @@ impl Shape for Ellipse{
             pt.y = self.center.y + dir.y;
         }
 
+        // 나중에 추가된 조절점(그라디언트)이 겹친 조절점보다 우선한다.
         let adjusted_width = (10.0 / scale).powi(2);
-        control_pts.iter().position(|p| (x - p.x).powi(2) + (y - p.y).powi(2) < adjusted_width).map_or(-1, |i| i as i32)
+        control_pts.iter().rposition(|p| (x - p.x).powi(2) + (y - p.y).powi(2) < adjusted_width).map_or(-1, |i| i as i32)
     }
 
     fn get_selected_control_point(&self) -> i32 {
@@ -157,7 +196,15 @@ impl Shape for Ellipse{
             pt.y = self.center.y + dir.y;
         }
 
-        if index == 8{
+        if index == 9 || index == 10{
+            let mut local = Vector2D::new(dx, dy);
+            local.rotate_by(-self.rotation);
+            let bounds = self.local_bounds();
+            if let Some(gradient) = self.fill.as_mut().and_then(|fill| fill.gradient.as_mut()) {
+                gradient.move_handle((index - 9) as usize, local.x, local.y, bounds);
+            }
+        }
+        else if index == 8{
             if let Some(pt) = control_pts.get_mut(index as usize) {
                 let mut clone = pt.clone();
                 clone.x += dx;
@@ -222,8 +269,10 @@ impl Shape for Ellipse{
         context.rotate(self.rotation).unwrap();
         context.translate(-self.center.x, -self.center.y).unwrap();
 
+        context.save();
+        self.appearance.apply(context);
         if self.hovered{
-            context.set_stroke_style(&JsValue::from_str("#ff0000"));
+            context.set_stroke_style(&JsValue::from_str(&Theme::current().hover_color));
         }
         else{
             context.set_stroke_style(&JsValue::from_str(&self.color));
@@ -232,8 +281,16 @@ impl Shape for Ellipse{
         let adjusted_width = self.line_width / scale;
         context.set_line_width(adjusted_width);
         context.begin_path();
-        let _ = context.ellipse(self.center.x, self.center.y, self.radius_x, self.radius_y, 0.0, self.start_angle, self.end_angle);
+        let _ = context.ellipse(self.center.x, self.center.y, self.radius_x.abs(), self.radius_y.abs(), 0.0, self.start_angle, self.end_angle);
+        if let Some(fill) = &self.fill{
+            fill.apply(context, self.local_bounds());
+            context.fill();
+            // 외곽선 그림자가 채우기 위에 겹쳐 보이지 않도록 한다.
+            context.set_shadow_color("rgba(0, 0, 0, 0)");
+        }
+        self.stroke_style.apply(context, adjusted_width);
         context.stroke();
+        context.restore();
         
         if self.selected{ self.draw_control_points(context, scale);}
 
@@ -246,7 +303,7 @@ impl Shape for Ellipse{
         context.set_global_composite_operation("xor").unwrap();
 
         context.begin_path();
-        let _ = context.ellipse(self.center.x, self.center.y, self.radius_x, self.radius_y, self.rotation, self.start_angle, self.end_angle);
+        let _ = context.ellipse(self.center.x, self.center.y, self.radius_x.abs(), self.radius_y.abs(), self.rotation, self.start_angle, self.end_angle);
         context.set_stroke_style(&JsValue::from_str(&self.color));
         let adjusted_width = self.line_width / scale;
         context.set_line_width(adjusted_width);
@@ -256,31 +313,100 @@ impl Shape for Ellipse{
     }
 
     fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
-        let adjusted_width = 5.0 / scale;
+        let theme = Theme::current();
 
         context.save();
+        theme.apply_selection_outline(context, scale);
+        context.begin_path();
+        context.rect(self.center.x - self.radius_x, self.center.y - self.radius_y, self.radius_x * 2.0, self.radius_y * 2.0);
+        context.stroke();
+        context.restore();
 
         let control_pts = self.control_points();
-        context.set_fill_style(&"#29B6F2".into()); // Red control points
-        for point in control_pts{
-            context.fill_rect(point.x - adjusted_width, point.y - adjusted_width, adjusted_width * 2.0, adjusted_width * 2.0);
+        theme.draw_handles(context, &control_pts[..9], scale);
+
+        if let Some(gradient) = self.fill.as_ref().and_then(|fill| fill.gradient.as_ref()) {
+            gradient.draw_handles(context, scale, self.local_bounds());
         }
+    }
 
-        context.set_stroke_style(&"#29B6F2".into()); // blue line
-        let adjusted_width = 0.5 / scale;
-        context.set_line_width(adjusted_width);
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
 
-        // ✅ Set dash pattern: [Dash length, Gap length]
-        let dash_pattern = js_sys::Array::new();
-        dash_pattern.push(&(adjusted_width * 3.0).into());  // dash
-        dash_pattern.push(&(adjusted_width * 3.0).into());  // gap
-        context.set_line_dash(&dash_pattern).unwrap();
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
 
-        context.begin_path();
-        context.rect(self.center.x - self.radius_x, self.center.y - self.radius_y, self.radius_x * 2.0, self.radius_y * 2.0);
-        context.stroke();
+    fn apply_style(&mut self, style: &ShapeStyle) {
+        self.color = style.color.clone();
+        self.line_width = style.line_width;
+        self.fill = style.fill.clone();
+        self.stroke_style = style.stroke_style.clone();
+    }
 
-        context.restore();
+    fn style(&self) -> ShapeStyle {
+        ShapeStyle{ color: self.color.clone(), line_width: self.line_width, fill: self.fill.clone(), stroke_style: self.stroke_style.clone(), font: None }
+    }
+
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property::number("center_x", "중심 X", self.center.x),
+            Property::number("center_y", "중심 Y", self.center.y),
+            Property::number("radius_x", "반지름 X", self.radius_x.abs()),
+            Property::number("radius_y", "반지름 Y", self.radius_y.abs()),
+            Property::number("rotation", "회전 (°)", self.rotation.to_degrees()),
+            Property::number("start_angle", "시작 각도 (°)", self.start_angle.to_degrees()),
+            Property::number("end_angle", "끝 각도 (°)", self.end_angle.to_degrees()),
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: &PropertyValue) {
+        let Some(value) = value.as_number() else { return; };
+        match name {
+            "center_x" => self.center.x = value,
+            "center_y" => self.center.y = value,
+            "radius_x" => self.radius_x = value.abs() * self.radius_x.signum(),
+            "radius_y" => self.radius_y = value.abs() * self.radius_y.signum(),
+            "rotation" => self.rotation = value.to_radians(),
+            "start_angle" => self.start_angle = value.to_radians(),
+            "end_angle" => self.end_angle = value.to_radians(),
+            _ => {}
+        }
+    }
+
+    /// 시작 각도부터 끝 각도까지 캔버스와 같은 방향(시계 방향)으로 호를 따라간다.
+    fn guide_path(&self) -> Vec<Point2D> {
+        const SEGMENTS: usize = 72;
+        let full = std::f64::consts::PI * 2.0;
+        let sweep = self.end_angle - self.start_angle;
+        let sweep = if sweep.abs() >= full { full } else { sweep.rem_euclid(full) };
+        let (rx, ry) = (self.radius_x.abs(), self.radius_y.abs());
+        let count = ((SEGMENTS as f64 * sweep / full).ceil() as usize).max(1);
+        (0..=count).map(|i| {
+            let angle = self.start_angle + sweep * i as f64 / count as f64;
+            let mut offset = Vector2D::new(rx * angle.cos(), ry * angle.sin());
+            offset.rotate_by(self.rotation);
+            Point2D::new(self.center.x + offset.x, self.center.y + offset.y)
+        }).collect()
+    }
+
+    fn to_svg(&self) -> String {
+        let transform = format!("rotate({} {} {})", self.rotation.to_degrees(), self.center.x, self.center.y);
+        let (rx, ry) = (self.radius_x.abs(), self.radius_y.abs());
+        let sweep = self.end_angle - self.start_angle;
+        let (defs, fill) = svg_fill_attributes(self.fill.as_ref(), self.local_bounds());
+        if sweep.abs() >= std::f64::consts::PI * 2.0 {
+            return format!(r#"{}<ellipse cx="{}" cy="{}" rx="{}" ry="{}" transform="{}" {} stroke="{}" stroke-width="{}"{}/>"#,
+                defs, self.center.x, self.center.y, rx, ry, transform, fill, self.color, self.line_width, self.stroke_style.svg_attributes(self.line_width));
+        }
+
+        // 호(arc)는 path로 내보낸다.
+        let start = Point2D::new(self.center.x + rx * self.start_angle.cos(), self.center.y + ry * self.start_angle.sin());
+        let end = Point2D::new(self.center.x + rx * self.end_angle.cos(), self.center.y + ry * self.end_angle.sin());
+        let large_arc = if sweep.rem_euclid(std::f64::consts::PI * 2.0) > std::f64::consts::PI { 1 } else { 0 };
+        format!(r#"{}<path d="M {} {} A {} {} 0 {} 1 {} {}" transform="{}" {} stroke="{}" stroke-width="{}"{}/>"#,
+            defs, start.x, start.y, rx, ry, large_arc, end.x, end.y, transform, fill, self.color, self.line_width, self.stroke_style.svg_attributes(self.line_width))
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -1,6 +1,7 @@
 use std::ops::{self, Add, AddAssign, Mul};
+use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Point2D{
     pub x: f64,
     pub y: f64,
@@ -17,6 +18,14 @@ impl Point2D{
     pub fn set_y(&mut self, value: f64){
         self.y = value;
     }
+
+    /// 선분(start ~ end)까지의 최단 거리
+    pub fn distance_to_segment(&self, start: Point2D, end: Point2D) -> f64{
+        let dir = Vector2D::from_points(start, end);
+        let d = dir.dot(dir);
+        let t = if d > 0.0 { (Vector2D::from_points(start, *self).dot(dir) / d).clamp(0.0, 1.0) } else { 0.0 };
+        Vector2D::from_points(Point2D::new(start.x + dir.x * t, start.y + dir.y * t), *self).length()
+    }
 }
 impl Add<Point2D> for Point2D{
     type Output = Self;
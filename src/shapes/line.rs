@@ -16,7 +16,8 @@ use web_sys::{window, CanvasRenderingContext2d, Element, DomParser, CanvasGradie
 
 use super::geometry::Vector2D;
 use super::geometry::{Point2D};
-use super::shape::{Shape};
+use super::theme::Theme;
+use super::shape::{Shape, StrokeStyle, Appearance, ShapeStyle, Property, PropertyValue};
 
 #[derive(Debug, Clone)]
 pub struct Line{
@@ -26,6 +27,8 @@ pub struct Line{
     line_width: f64,
     start: Point2D,
     end: Point2D,
+    stroke_style: StrokeStyle,
+    appearance: Appearance,
     selected_control_point: i32,
 }
 impl Line {
@@ -37,6 +40,8 @@ impl Line {
             line_width, 
             start, 
             end,
+            stroke_style: StrokeStyle::default(),
+            appearance: Appearance::default(),
             selected_control_point: -1,}
     }
 }
@@ -68,6 +73,18 @@ impl Shape for Line{
         dx * dx + dy * dy < 25.0
     }
 
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.line_width = width;
+    }
+
+    fn set_stroke_style(&mut self, style: StrokeStyle) {
+        self.stroke_style = style;
+    }
+
     fn max_point(&self) -> Point2D{
         Point2D::new(self.start.x.max(self.end.x), self.start.y.max(self.end.y))
     }
@@ -132,19 +149,22 @@ impl Shape for Line{
         라인을 캔버스에 그린다.
      */
     fn draw(&mut self, context: &CanvasRenderingContext2d, scale: f64){
+        context.save();
+        self.appearance.apply(context);
         if self.hovered{
-            context.set_stroke_style(&JsValue::from_str("#ff0000"));
+            context.set_stroke_style(&JsValue::from_str(&Theme::current().hover_color));
         }
         else{
             context.set_stroke_style(&JsValue::from_str(&self.color));
         }
         let adjusted_width = self.line_width / scale;
         context.set_line_width(adjusted_width);
+        self.stroke_style.apply(context, adjusted_width);
         context.begin_path();
         context.move_to(self.start.x, self.start.y);
         context.line_to(self.end.x, self.end.y);
-        context.close_path();
         context.stroke();
+        context.restore();
 
         if self.selected{ self.draw_control_points(context, scale);}
     }   
@@ -167,13 +187,54 @@ impl Shape for Line{
     }
 
     fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
-        context.set_fill_style(&"#FF0000".into()); // Red control points
+        Theme::current().draw_handles(context, &[self.start, self.end], scale);
+    }
 
-        let adjusted_width = 1.0 / scale * 5.0;
-        context.begin_path();
-        context.rect(self.start.x - adjusted_width, self.start.y - adjusted_width, adjusted_width * 2.0, adjusted_width * 2.0);
-        context.rect(self.end.x - adjusted_width, self.end.y - adjusted_width, adjusted_width * 2.0, adjusted_width * 2.0);
-        context.fill();
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
+
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
+
+    fn apply_style(&mut self, style: &ShapeStyle) {
+        self.color = style.color.clone();
+        self.line_width = style.line_width;
+        self.stroke_style = style.stroke_style.clone();
+    }
+
+    fn style(&self) -> ShapeStyle {
+        ShapeStyle{ color: self.color.clone(), line_width: self.line_width, fill: None, stroke_style: self.stroke_style.clone(), font: None }
+    }
+
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property::number("start_x", "시작점 X", self.start.x),
+            Property::number("start_y", "시작점 Y", self.start.y),
+            Property::number("end_x", "끝점 X", self.end.x),
+            Property::number("end_y", "끝점 Y", self.end.y),
+        ]
+    }
+
+    fn set_property(&mut self, name: &str, value: &PropertyValue) {
+        let Some(value) = value.as_number() else { return; };
+        match name {
+            "start_x" => self.start.x = value,
+            "start_y" => self.start.y = value,
+            "end_x" => self.end.x = value,
+            "end_y" => self.end.y = value,
+            _ => {}
+        }
+    }
+
+    fn guide_path(&self) -> Vec<Point2D> {
+        vec![self.start, self.end]
+    }
+
+    fn to_svg(&self) -> String {
+        format!(r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}"{}/>"#,
+            self.start.x, self.start.y, self.end.x, self.end.y, self.color, self.line_width, self.stroke_style.svg_attributes(self.line_width))
     }
 
     fn as_any(&self) -> &dyn Any {
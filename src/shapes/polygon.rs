@@ -0,0 +1,375 @@
+use std::any::Any;
+use std::f64::consts::PI;
+use wasm_bindgen::prelude::*;
+
+use web_sys::{CanvasRenderingContext2d};
+
+use super::geometry::Vector2D;
+use super::geometry::{Point2D};
+use super::theme::Theme;
+use super::shape::{Shape, Fill, StrokeStyle, Appearance, ShapeStyle, svg_fill_attributes};
+
+/// 정다각형 또는 별 모양
+#[derive(Debug, Clone)]
+pub struct RegularPolygon{
+    selected: bool,
+    hovered: bool,
+    color: String,
+    line_width: f64,
+    center: Point2D,
+    radius: f64,
+    rotation: f64,  // in radian
+    sides: u32,     // 정다각형의 변 개수 또는 별의 꼭짓점 개수
+    is_star: bool,
+    inner_ratio: f64,   // 별의 안쪽 반지름 비율 (0 ~ 1)
+    fill: Option<Fill>,
+    stroke_style: StrokeStyle,
+    appearance: Appearance,
+    selected_control_point: i32,
+}
+impl RegularPolygon{
+    pub const MIN_SIDES: u32 = 3;
+    pub const MAX_SIDES: u32 = 64;
+
+    pub fn new(color: String, line_width: f64, center: Point2D, radius: f64, sides: u32) -> Self {
+        RegularPolygon{
+            selected: false,
+            hovered: false,
+            color,
+            line_width,
+            center,
+            radius,
+            rotation: 0.0,
+            sides: sides.clamp(Self::MIN_SIDES, Self::MAX_SIDES),
+            is_star: false,
+            inner_ratio: 0.5,
+            fill: None,
+            stroke_style: StrokeStyle::default(),
+            appearance: Appearance::default(),
+            selected_control_point: -1}
+    }
+
+    pub fn new_star(color: String, line_width: f64, center: Point2D, radius: f64, points: u32, inner_ratio: f64) -> Self {
+        let mut star = RegularPolygon::new(color, line_width, center, radius, points);
+        star.is_star = true;
+        star.set_inner_ratio(inner_ratio);
+        star
+    }
+
+    pub fn sides(&self) -> u32{
+        self.sides
+    }
+
+    pub fn set_sides(&mut self, value: u32){
+        self.sides = value.clamp(Self::MIN_SIDES, Self::MAX_SIDES);
+    }
+
+    pub fn is_star(&self) -> bool{
+        self.is_star
+    }
+
+    pub fn inner_ratio(&self) -> f64{
+        self.inner_ratio
+    }
+
+    pub fn set_inner_ratio(&mut self, value: f64){
+        self.inner_ratio = value.clamp(0.05, 1.0);
+    }
+
+    /// 첫 번째 꼭짓점이 위쪽을 향하도록 한 기준 각도
+    fn start_angle(&self) -> f64{
+        self.rotation - PI * 0.5
+    }
+
+    fn point_at(&self, angle: f64, radius: f64) -> Point2D{
+        Point2D::new(self.center.x + radius * angle.cos(), self.center.y + radius * angle.sin())
+    }
+
+    /// 외곽선의 꼭짓점 목록을 반환한다.
+    pub fn vertices(&self) -> Vec<Point2D>{
+        let start = self.start_angle();
+        if self.is_star{
+            let step = PI / self.sides as f64;
+            (0..self.sides * 2).map(|i| {
+                let radius = if i % 2 == 0 { self.radius } else { self.radius * self.inner_ratio };
+                self.point_at(start + step * i as f64, radius)
+            }).collect()
+        }
+        else{
+            let step = PI * 2.0 / self.sides as f64;
+            (0..self.sides).map(|i| self.point_at(start + step * i as f64, self.radius)).collect()
+        }
+    }
+
+    /// 그라디언트 좌표의 기준이 되는 외접원의 (left, top, right, bottom)
+    fn fill_bounds(&self) -> (f64, f64, f64, f64){
+        (self.center.x - self.radius, self.center.y - self.radius, self.center.x + self.radius, self.center.y + self.radius)
+    }
+
+    /// 그라디언트 채우기 조절점 (채우기가 그라디언트일 때만)
+    fn gradient_control_points(&self) -> Vec<Point2D>{
+        self.fill.as_ref().and_then(|fill| fill.gradient.as_ref())
+            .map_or(Vec::new(), |gradient| gradient.handle_points(self.fill_bounds()).to_vec())
+    }
+
+    /// 0: 반지름, 1: 회전, 2: 중심, 3: 별의 안쪽 반지름, 그 다음: 그라디언트
+    fn control_points(&self) -> Vec<Point2D>{
+        let start = self.start_angle();
+        let mut control_pts = vec![
+            self.point_at(start, self.radius),
+            self.point_at(start, self.radius + 30.0),
+            self.center,
+            ];
+        if self.is_star{
+            control_pts.push(self.point_at(start + PI / self.sides as f64, self.radius * self.inner_ratio));
+        }
+        control_pts.extend(self.gradient_control_points());
+
+        control_pts
+    }
+
+    fn build_path(&self, context: &CanvasRenderingContext2d){
+        let vertices = self.vertices();
+        if let Some(first) = vertices.first(){
+            context.move_to(first.x, first.y);
+            for point in vertices.iter().skip(1) {
+                context.line_to(point.x, point.y);
+            }
+            context.close_path();
+        }
+    }
+}
+
+/// 점이 다각형 내부에 있는지 확인한다. (ray casting)
+fn is_inside_polygon(vertices: &[Point2D], x: f64, y: f64) -> bool{
+    let mut inside = false;
+    let mut j = vertices.len().wrapping_sub(1);
+    for i in 0..vertices.len(){
+        let (pi, pj) = (vertices[i], vertices[j]);
+        if (pi.y > y) != (pj.y > y) && x < (pj.x - pi.x) * (y - pi.y) / (pj.y - pi.y) + pi.x {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+impl Shape for RegularPolygon{
+    fn color(&self) -> &str {
+        &self.color
+    }
+
+    fn line_width(&self) -> f64 {
+        self.line_width
+    }
+
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.line_width = width;
+    }
+
+    fn set_fill(&mut self, fill: Option<Fill>) {
+        self.fill = fill;
+    }
+
+    fn set_stroke_style(&mut self, style: StrokeStyle) {
+        self.stroke_style = style;
+    }
+
+    fn max_point(&self) -> Point2D{
+        self.vertices().iter().fold(Point2D::new(f64::MIN, f64::MIN), |acc, point|
+            Point2D::new(acc.x.max(point.x), acc.y.max(point.y))
+        )
+    }
+
+    fn min_point(&self) -> Point2D{
+        self.vertices().iter().fold(Point2D::new(f64::MAX, f64::MAX), |acc, point|
+            Point2D::new(acc.x.min(point.x), acc.y.min(point.y))
+        )
+    }
+
+    fn is_hit(&self, x: f64, y: f64, scale: f64) -> bool {
+        let index = self.get_control_point(x, y, scale);
+        if index != -1{return true;}
+
+        let vertices = self.vertices();
+        if is_inside_polygon(&vertices, x, y) {return true;}
+
+        // 외곽선 근처
+        let tolerance = (5.0 + self.line_width * 0.5) / scale;
+        let point = Point2D::new(x, y);
+        (0..vertices.len()).any(|i| point.distance_to_segment(vertices[i], vertices[(i + 1) % vertices.len()]) <= tolerance)
+    }
+
+    fn get_control_point(&self, x: f64, y: f64, scale: f64) -> i32{
+        let control_pts = self.control_points();
+        // 나중에 추가된 조절점(그라디언트)이 겹친 조절점보다 우선한다.
+        let adjusted_width = (10.0 / scale).powi(2);
+        control_pts.iter().rposition(|p| (x - p.x).powi(2) + (y - p.y).powi(2) < adjusted_width).map_or(-1, |i| i as i32)
+    }
+
+    fn get_selected_control_point(&self) -> i32 {
+        self.selected_control_point
+    }
+
+    fn set_selected_control_point(&mut self, index: i32) {
+        self.selected_control_point = index;
+    }
+
+    fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, selected: bool){
+        self.selected = selected;
+    }
+
+    fn set_hovered(&mut self, value: bool) {
+        self.hovered = value;
+    }
+
+    fn move_by(&mut self, dx: f64, dy: f64) {
+        self.center.x += dx;
+        self.center.y += dy;
+    }
+
+    fn move_control_point_by(&mut self, index: i32, dx: f64, dy: f64) {
+        let control_pts = self.control_points();
+        let Some(pt) = control_pts.get(index as usize) else { return; };
+        let moved = Point2D::new(pt.x + dx, pt.y + dy);
+        let gradient_index = if self.is_star { 4 } else { 3 };
+
+        if index >= gradient_index{
+            let bounds = self.fill_bounds();
+            if let Some(gradient) = self.fill.as_mut().and_then(|fill| fill.gradient.as_mut()) {
+                gradient.move_handle((index - gradient_index) as usize, dx, dy, bounds);
+            }
+        }
+        else if index == 0{
+            let mut dir = Vector2D::from_points(self.center, *pt);
+            dir.normalize();
+            self.radius = dir.dot(Vector2D::from_points(self.center, moved)).max(1.0);
+        }
+        else if index == 1{
+            let pt_dir = Vector2D::from_points(self.center, *pt);
+            let moved_dir = Vector2D::from_points(self.center, moved);
+            self.rotation += pt_dir.angle_to(moved_dir);
+        }
+        else if index == 2{
+            self.move_by(dx, dy);
+        }
+        else if index == 3 && self.radius > 0.0{
+            let mut dir = Vector2D::from_points(self.center, *pt);
+            dir.normalize();
+            let inner_radius = dir.dot(Vector2D::from_points(self.center, moved));
+            self.set_inner_ratio(inner_radius / self.radius);
+        }
+    }
+
+    fn draw(&mut self, context: &CanvasRenderingContext2d, scale: f64){
+        context.save();
+        self.appearance.apply(context);
+
+        if self.hovered{
+            context.set_stroke_style(&JsValue::from_str(&Theme::current().hover_color));
+        }
+        else{
+            context.set_stroke_style(&JsValue::from_str(&self.color));
+        }
+        let adjusted_width = self.line_width / scale;
+        context.set_line_width(adjusted_width);
+        self.stroke_style.apply(context, adjusted_width);
+        context.begin_path();
+        self.build_path(context);
+        if let Some(fill) = &self.fill{
+            fill.apply(context, self.fill_bounds());
+            context.fill();
+            // 외곽선 그림자가 채우기 위에 겹쳐 보이지 않도록 한다.
+            context.set_shadow_color("rgba(0, 0, 0, 0)");
+        }
+        context.stroke();
+
+        context.restore();
+
+        if self.selected{ self.draw_control_points(context, scale);}
+    }
+
+    fn draw_xor(&self, context: &CanvasRenderingContext2d, scale: f64){
+        context.save();
+
+        context.set_global_composite_operation("xor").unwrap();
+
+        context.begin_path();
+        self.build_path(context);
+
+        context.set_stroke_style(&JsValue::from_str(&self.color));
+        let adjusted_width = self.line_width / scale;
+        context.set_line_width(adjusted_width);
+        context.stroke();
+
+        context.restore();
+    }
+
+    fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
+        let theme = Theme::current();
+
+        context.save();
+        theme.apply_selection_outline(context, scale);
+        context.begin_path();
+        context.arc(self.center.x, self.center.y, self.radius, 0.0, PI * 2.0).unwrap();
+        context.stroke();
+        context.restore();
+
+        let control_pts = self.control_points();
+        let gradient_handles = self.gradient_control_points().len();
+        for (index, point) in control_pts.iter().take(control_pts.len() - gradient_handles).enumerate(){
+            if index == 2{
+                theme.draw_round_handle(context, *point, scale);
+            }
+            else{
+                theme.draw_handle(context, *point, scale);
+            }
+        }
+
+        if let Some(gradient) = self.fill.as_ref().and_then(|fill| fill.gradient.as_ref()) {
+            gradient.draw_handles(context, scale, self.fill_bounds());
+        }
+    }
+
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
+
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
+
+    fn apply_style(&mut self, style: &ShapeStyle) {
+        self.color = style.color.clone();
+        self.line_width = style.line_width;
+        self.fill = style.fill.clone();
+        self.stroke_style = style.stroke_style.clone();
+    }
+
+    fn style(&self) -> ShapeStyle {
+        ShapeStyle{ color: self.color.clone(), line_width: self.line_width, fill: self.fill.clone(), stroke_style: self.stroke_style.clone(), font: None }
+    }
+
+    fn to_svg(&self) -> String {
+        let points = self.vertices().iter().map(|point| format!("{},{}", point.x, point.y)).collect::<Vec<_>>().join(" ");
+        let (defs, fill) = svg_fill_attributes(self.fill.as_ref(), self.fill_bounds());
+        format!(r#"{}<polygon points="{}" {} stroke="{}" stroke-width="{}"{}/>"#,
+            defs, points, fill, self.color, self.line_width, self.stroke_style.svg_attributes(self.line_width))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -16,7 +16,8 @@ use web_sys::{CanvasRenderingContext2d};
 
 use super::geometry::Vector2D;
 use super::geometry::{Point2D};
-use super::shape::{Shape};
+use super::theme::Theme;
+use super::shape::{Shape, Fill, StrokeStyle, Appearance, ShapeStyle, Property, PropertyValue, svg_fill_attributes};
 
 #[derive(Debug, Clone)]
 pub struct Rectangle{
@@ -28,8 +29,16 @@ pub struct Rectangle{
     width: f64,
     height: f64,
     rotation: f64,  // in radian,
+    corner_radii: [f64; 4], // top-left, top-right, bottom-right, bottom-left
+    independent_corners: bool,
+    fill: Option<Fill>,
+    stroke_style: StrokeStyle,
+    appearance: Appearance,
     selected_control_point: i32,
 }
+
+/// 모서리 반지름 조절점이 모서리에서 최소한 떨어져 있어야 하는 거리
+const RADIUS_HANDLE_OFFSET: f64 = 15.0;
 impl Rectangle{
     pub fn new(color: String, line_width: f64, start: Point2D, w: f64, h: f64) -> Self {
         Rectangle{
@@ -41,10 +50,133 @@ impl Rectangle{
             width: w, 
             height: h, 
             rotation: 0.0,
+            corner_radii: [0.0; 4],
+            independent_corners: false,
+            fill: None,
+            stroke_style: StrokeStyle::default(),
+            appearance: Appearance::default(),
             selected_control_point: -1}
     }
 
-    fn control_points(&self) -> Vec<Point2D>{
+    /// 네 모서리에 같은 반지름을 적용한다.
+    pub fn set_corner_radius(&mut self, radius: f64){
+        self.corner_radii = [radius.max(0.0); 4];
+        self.independent_corners = false;
+    }
+
+    /// 모서리별 반지름을 적용한다. (top-left, top-right, bottom-right, bottom-left)
+    pub fn set_corner_radii(&mut self, radii: [f64; 4]){
+        self.corner_radii = radii.map(|r| r.max(0.0));
+        self.independent_corners = true;
+    }
+
+    pub fn corner_radii(&self) -> [f64; 4]{
+        self.corner_radii
+    }
+
+    pub fn independent_corners(&self) -> bool{
+        self.independent_corners
+    }
+
+    pub fn set_independent_corners(&mut self, value: bool){
+        self.independent_corners = value;
+        if !value{
+            self.corner_radii = [self.corner_radii[0]; 4];
+        }
+    }
+
+    /// 짧은 변의 절반으로 제한된 모서리 반지름을 반환한다.
+    fn clamped_corner_radii(&self) -> [f64; 4]{
+        let max_radius = self.width.abs().min(self.height.abs()) * 0.5;
+        self.corner_radii.map(|r| r.clamp(0.0, max_radius))
+    }
+
+    fn is_rounded(&self) -> bool{
+        self.clamped_corner_radii().iter().any(|r| *r > 0.0)
+    }
+
+    /// 회전하지 않은 좌표계에서의 (left, top, right, bottom)
+    fn local_bounds(&self) -> (f64, f64, f64, f64){
+        let half_width = self.width.abs() * 0.5;
+        let half_height = self.height.abs() * 0.5;
+        (self.center.x - half_width, self.center.y - half_height, self.center.x + half_width, self.center.y + half_height)
+    }
+
+    /// 모서리 반지름 조절점(회전하지 않은 좌표계)
+    /// 최소 간격은 화면 기준이므로 배율로 나눈다.
+    fn radius_control_points(&self, scale: f64) -> Vec<Point2D>{
+        let (left, top, right, bottom) = self.local_bounds();
+        let radii = self.clamped_corner_radii();
+        let corners = [(left, top, 1.0, 1.0), (right, top, -1.0, 1.0), (right, bottom, -1.0, -1.0), (left, bottom, 1.0, -1.0)];
+
+        corners.iter().zip(radii.iter()).map(|(&(x, y, sx, sy), r)| {
+            let offset = r.max(RADIUS_HANDLE_OFFSET / scale);
+            Point2D::new(x + sx * offset, y + sy * offset)
+        }).collect()
+    }
+
+    /// 둥근 모서리를 포함한 사각형 경로를 만든다.
+    fn build_path(&self, context: &CanvasRenderingContext2d){
+        if !self.is_rounded(){
+            context.rect(self.center.x - self.width * 0.5, self.center.y - self.height * 0.5, self.width, self.height);
+            return;
+        }
+
+        let (left, top, right, bottom) = self.local_bounds();
+        let [top_left, top_right, bottom_right, bottom_left] = self.clamped_corner_radii();
+        context.move_to(left + top_left, top);
+        context.line_to(right - top_right, top);
+        context.arc_to(right, top, right, top + top_right, top_right).unwrap();
+        context.line_to(right, bottom - bottom_right);
+        context.arc_to(right, bottom, right - bottom_right, bottom, bottom_right).unwrap();
+        context.line_to(left + bottom_left, bottom);
+        context.arc_to(left, bottom, left, bottom - bottom_left, bottom_left).unwrap();
+        context.line_to(left, top + top_left);
+        context.arc_to(left, top, left + top_left, top, top_left).unwrap();
+        context.close_path();
+    }
+
+    /// SVG path 데이터로 둥근 사각형을 표현한다.
+    fn svg_path_data(&self) -> String{
+        let (left, top, right, bottom) = self.local_bounds();
+        let [top_left, top_right, bottom_right, bottom_left] = self.clamped_corner_radii();
+        format!("M {} {} H {} A {} {} 0 0 1 {} {} V {} A {} {} 0 0 1 {} {} H {} A {} {} 0 0 1 {} {} V {} A {} {} 0 0 1 {} {} Z",
+            left + top_left, top,
+            right - top_right, top_right, top_right, right, top + top_right,
+            bottom - bottom_right, bottom_right, bottom_right, right - bottom_right, bottom,
+            left + bottom_left, bottom_left, bottom_left, left, bottom - bottom_left,
+            top + top_left, top_left, top_left, left + top_left, top)
+    }
+
+    /// 회전하지 않은 좌표계에서 점이 (둥근) 사각형 내부에 있는지 확인한다.
+    /// inset만큼 안쪽(음수이면 바깥쪽)으로 줄인 사각형을 기준으로 한다.
+    fn contains_local_point(&self, x: f64, y: f64, inset: f64) -> bool{
+        let (left, top, right, bottom) = self.local_bounds();
+        let (left, top, right, bottom) = (left + inset, top + inset, right - inset, bottom - inset);
+        if x < left || x > right || y < top || y > bottom {return false;}
+
+        let radii = self.clamped_corner_radii().map(|r| (r - inset).max(0.0));
+        let arc_centers = [
+            (left + radii[0], top + radii[0]),
+            (right - radii[1], top + radii[1]),
+            (right - radii[2], bottom - radii[2]),
+            (left + radii[3], bottom - radii[3])];
+        let outside_corner = arc_centers.iter().zip(radii.iter()).enumerate().any(|(index, (&(cx, cy), r))| {
+            let in_corner_x = if index == 0 || index == 3 { x < cx } else { x > cx };
+            let in_corner_y = if index == 0 || index == 1 { y < cy } else { y > cy };
+            in_corner_x && in_corner_y && (x - cx).powi(2) + (y - cy).powi(2) > r * r
+        });
+
+        !outside_corner
+    }
+
+    /// 그라디언트 채우기 조절점 (채우기가 그라디언트일 때만)
+    fn gradient_control_points(&self) -> Vec<Point2D>{
+        self.fill.as_ref().and_then(|fill| fill.gradient.as_ref())
+            .map_or(Vec::new(), |gradient| gradient.handle_points(self.local_bounds()).to_vec())
+    }
+
+    fn control_points(&self, scale: f64) -> Vec<Point2D>{
         let control_pts = vec![
             Point2D::new(self.center.x - self.width * 0.5, self.center.y - self.height * 0.5), 
             Point2D::new(self.center.x - self.width * 0.5, self.center.y),
@@ -58,11 +190,11 @@ impl Rectangle{
             Point2D::new(self.center.x, self.center.y - self.height * 0.5 - 30.0)
             ];
         
-        control_pts
+        control_pts.into_iter().chain(self.radius_control_points(scale)).chain(self.gradient_control_points()).collect()
     }
 
     fn center_point(&self) -> Point2D{
-        let control_points = self.control_points();
+        let control_points = self.control_points(1.0);
         let start = control_points.get(0).unwrap();
         let end = control_points.get(4).unwrap();
         Point2D::new((start.x + end.x) * 0.5, (start.y + end.y) * 0.5)
@@ -90,6 +222,22 @@ impl Shape for Rectangle{
         self.line_width
     }
 
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.line_width = width;
+    }
+
+    fn set_fill(&mut self, fill: Option<Fill>) {
+        self.fill = fill;
+    }
+
+    fn set_stroke_style(&mut self, style: StrokeStyle) {
+        self.stroke_style = style;
+    }
+
     fn max_point(&self) -> Point2D{
         Point2D::new(self.center.x + self.width * 0.5, self.center.y + self.height * 0.5)
     }
@@ -102,19 +250,19 @@ impl Shape for Rectangle{
         let index = self.get_control_point(x, y, scale);
         if index != -1{return true;}
 
-        let min_pt = self.min_point();
-        let max_pt = self.max_point();
-        if x < min_pt.x {return false;}
-        if x > max_pt.x {return false;}
-        if y < min_pt.y {return false;}
-        if y > max_pt.y {return false;}
+        // 회전을 되돌린 좌표에서 확인한다.
+        let mut dir = Vector2D::new(x - self.center.x, y - self.center.y);
+        dir.rotate_by(-self.rotation);
+        let (local_x, local_y) = (self.center.x + dir.x, self.center.y + dir.y);
 
-        true
+        // 외곽선 두께만큼 바깥쪽까지 선택된다.
+        let tolerance = (5.0 + self.line_width * 0.5) / scale;
+        self.contains_local_point(local_x, local_y, -tolerance)
     }
 
     /// Get the index of the control point that is hit by the mouse cursor.
     fn get_control_point(&self, x: f64, y: f64, scale: f64) -> i32{
-        let mut control_pts = self.control_points();
+        let mut control_pts = self.control_points(scale);
         for pt in &mut control_pts{
             let mut dir = Vector2D::from_points(self.center, *pt);
             dir.rotate_by(self.rotation);
@@ -123,7 +271,13 @@ impl Shape for Rectangle{
         }
 
         let adjusted_width = (10.0 / scale).powi(2);
-        control_pts.iter().position(|p| (x - p.x).powi(2) + (y - p.y).powi(2) < adjusted_width).map_or(-1, |i| i as i32)
+        let is_near = |p: &Point2D| (x - p.x).powi(2) + (y - p.y).powi(2) < adjusted_width;
+
+        // 그라디언트 조절점(14, 15)은 겹친 다른 조절점보다 우선한다.
+        if let Some(index) = control_pts.iter().skip(14).position(is_near) {
+            return 14 + index as i32;
+        }
+        control_pts.iter().position(is_near).map_or(-1, |i| i as i32)
     }
 
     fn get_selected_control_point(&self) -> i32 {
@@ -152,7 +306,8 @@ impl Shape for Rectangle{
     }
 
     fn move_control_point_by(&mut self, index: i32, dx: f64, dy: f64) {
-        let mut control_pts = self.control_points();
+        // 외곽 조절점(0~9)만 위치를 쓰므로 배율과 무관하다.
+        let mut control_pts = self.control_points(1.0);
         for pt in &mut control_pts{
             let mut dir = Vector2D::from_points(self.center, *pt);
             dir.rotate_by(self.rotation);
@@ -164,6 +319,29 @@ impl Shape for Rectangle{
             self.center.x += dx;
             self.center.y += dy;
         }
+        else if (10..=13).contains(&index){
+            let corner = (index - 10) as usize;
+            let (sx, sy) = [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)][corner];
+            let mut local = Vector2D::new(dx, dy);
+            local.rotate_by(-self.rotation);
+
+            let max_radius = self.width.abs().min(self.height.abs()) * 0.5;
+            let radius = (self.clamped_corner_radii()[corner] + (local.x * sx + local.y * sy) * 0.5).clamp(0.0, max_radius);
+            if self.independent_corners{
+                self.corner_radii[corner] = radius;
+            }
+            else{
+                self.corner_radii = [radius; 4];
+            }
+        }
+        else if index == 14 || index == 15{
+            let mut local = Vector2D::new(dx, dy);
+            local.rotate_by(-self.rotation);
+            let bounds = self.local_bounds();
+            if let Some(gradient) = self.fill.as_mut().and_then(|fill| fill.gradient.as_mut()) {
+                gradient.move_handle((index - 14) as usize, local.x, local.y, bounds);
+            }
+        }
         else if index == 9{
             if let Some(pt) = control_pts.get_mut(index as usize) {
                 let mut clone = pt.clone();
@@ -229,8 +407,10 @@ impl Shape for Rectangle{
         context.rotate(self.rotation).unwrap();
         context.translate(-self.center.x, -self.center.y).unwrap();
 
+        context.save();
+        self.appearance.apply(context);
         if self.hovered{
-            context.set_stroke_style(&JsValue::from_str("#ff0000"));
+            context.set_stroke_style(&JsValue::from_str(&Theme::current().hover_color));
         }
         else{
             context.set_stroke_style(&JsValue::from_str(&self.color));
@@ -238,8 +418,16 @@ impl Shape for Rectangle{
         let adjusted_width = self.line_width / scale;
         context.set_line_width(adjusted_width);
         context.begin_path();
-        context.rect(self.center.x - self.width * 0.5, self.center.y - self.height * 0.5, self.width, self.height);
+        self.build_path(context);
+        if let Some(fill) = &self.fill{
+            fill.apply(context, self.local_bounds());
+            context.fill();
+            // 외곽선 그림자가 채우기 위에 겹쳐 보이지 않도록 한다.
+            context.set_shadow_color("rgba(0, 0, 0, 0)");
+        }
+        self.stroke_style.apply(context, adjusted_width);
         context.stroke();
+        context.restore();
         
         if self.selected{ self.draw_control_points(context, scale);}
 
@@ -252,7 +440,7 @@ impl Shape for Rectangle{
         context.set_global_composite_operation("xor").unwrap();
 
         context.begin_path();
-        context.rect(self.center.x - self.width * 0.5, self.center.y - self.height * 0.5, self.width, self.height);
+        self.build_path(context);
 
         context.set_stroke_style(&JsValue::from_str(&self.color));
         let adjusted_width = self.line_width / scale;
@@ -264,36 +452,113 @@ impl Shape for Rectangle{
 
     // draw control points and rotation point
     fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
-        let adjusted_width = 5.0 / scale;
+        let theme = Theme::current();
 
         context.save();
+        theme.apply_selection_outline(context, scale);
+        context.begin_path();
+        context.rect(self.center.x - self.width * 0.5, self.center.y - self.height * 0.5, self.width, self.height);
+        context.stroke();
+        context.restore();
+
+        let control_pts = self.control_points(scale);
+        theme.draw_handles(context, &control_pts[..10], scale);
 
-        let control_pts = self.control_points();
-        context.set_fill_style(&"#29B6F2".into()); // color of control points
-        for point in control_pts{
-            context.fill_rect(point.x - adjusted_width, point.y - adjusted_width, adjusted_width * 2.0, adjusted_width * 2.0);
+        // 모서리 반지름 조절점, 중심점
+        for point in control_pts.iter().skip(10).take(4){
+            theme.draw_round_handle(context, *point, scale);
         }
+        theme.draw_round_handle(context, self.center, scale);
 
-        context.set_stroke_style(&"#29B6F2".into()); // color of line
-        let adjusted_width = 0.5 / scale;
-        context.set_line_width(adjusted_width);
+        if let Some(gradient) = self.fill.as_ref().and_then(|fill| fill.gradient.as_ref()) {
+            gradient.draw_handles(context, scale, self.local_bounds());
+        }
+    }
 
-        // ✅ Set dash pattern: [Dash length, Gap length]
-        let dash_pattern = js_sys::Array::new();
-        dash_pattern.push(&(adjusted_width * 3.0).into());  // dash
-        dash_pattern.push(&(adjusted_width * 3.0).into());  // gap
-        context.set_line_dash(&dash_pattern).unwrap();
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
 
-        context.begin_path();
-        context.rect(self.center.x - self.width * 0.5, self.center.y - self.height * 0.5, self.width, self.height);
-        context.stroke();
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
 
-        let adjusted_width = 5.0 / scale;
-        context.begin_path();
-        context.arc(self.center.x, self.center.y, adjusted_width, 0.0, std::f64::consts::PI * 2.0).unwrap();
-        context.fill();
+    fn apply_style(&mut self, style: &ShapeStyle) {
+        self.color = style.color.clone();
+        self.line_width = style.line_width;
+        self.fill = style.fill.clone();
+        self.stroke_style = style.stroke_style.clone();
+    }
 
-        context.restore();
+    fn style(&self) -> ShapeStyle {
+        ShapeStyle{ color: self.color.clone(), line_width: self.line_width, fill: self.fill.clone(), stroke_style: self.stroke_style.clone(), font: None }
+    }
+
+    fn properties(&self) -> Vec<Property> {
+        let mut properties = vec![
+            Property::number("center_x", "중심 X", self.center.x),
+            Property::number("center_y", "중심 Y", self.center.y),
+            Property::number("width", "너비", self.width.abs()),
+            Property::number("height", "높이", self.height.abs()),
+            Property::number("rotation", "회전 (°)", self.rotation.to_degrees()),
+        ];
+
+        // 모서리 반지름: 같은 값 하나 또는 모서리별 값
+        let radii = self.corner_radii();
+        if self.independent_corners() {
+            properties.extend([
+                Property::number("radius_top_left", "왼쪽 위 반지름", radii[0]),
+                Property::number("radius_top_right", "오른쪽 위 반지름", radii[1]),
+                Property::number("radius_bottom_right", "오른쪽 아래 반지름", radii[2]),
+                Property::number("radius_bottom_left", "왼쪽 아래 반지름", radii[3]),
+            ]);
+        }
+        else {
+            properties.push(Property::number("corner_radius", "모서리 반지름", radii[0]));
+        }
+        properties.push(Property::flag("independent_corners", "모서리별 반지름", self.independent_corners()));
+        properties
+    }
+
+    fn set_property(&mut self, name: &str, value: &PropertyValue) {
+        if let (Some(flag), "independent_corners") = (value.as_flag(), name) {
+            self.set_independent_corners(flag);
+            return;
+        }
+
+        let Some(value) = value.as_number() else { return; };
+        let corner = ["radius_top_left", "radius_top_right", "radius_bottom_right", "radius_bottom_left"].iter().position(|corner| *corner == name);
+        match name {
+            "center_x" => self.center.x = value,
+            "center_y" => self.center.y = value,
+            // 그린 방향(부호)은 유지한다.
+            "width" => self.width = value.abs() * self.width.signum(),
+            "height" => self.height = value.abs() * self.height.signum(),
+            "rotation" => self.rotation = value.to_radians(),
+            "corner_radius" => self.set_corner_radius(value),
+            _ => {
+                if let Some(corner) = corner {
+                    let mut radii = self.corner_radii();
+                    radii[corner] = value;
+                    self.set_corner_radii(radii);
+                }
+            }
+        }
+    }
+
+    fn to_svg(&self) -> String {
+        let transform = format!("rotate({} {} {})", self.rotation.to_degrees(), self.center.x, self.center.y);
+        let radii = self.clamped_corner_radii();
+        let (defs, fill) = svg_fill_attributes(self.fill.as_ref(), self.local_bounds());
+        if radii.iter().all(|r| *r == radii[0]) {
+            let (left, top, right, bottom) = self.local_bounds();
+            format!(r#"{}<rect x="{}" y="{}" width="{}" height="{}" rx="{}" ry="{}" transform="{}" {} stroke="{}" stroke-width="{}"{}/>"#,
+                defs, left, top, right - left, bottom - top, radii[0], radii[0], transform, fill, self.color, self.line_width, self.stroke_style.svg_attributes(self.line_width))
+        }
+        else{
+            format!(r#"{}<path d="{}" transform="{}" {} stroke="{}" stroke-width="{}"{}/>"#,
+                defs, self.svg_path_data(), transform, fill, self.color, self.line_width, self.stroke_style.svg_attributes(self.line_width))
+        }
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -1,8 +1,10 @@
 use std::any::Any;
 use std::collections::HashMap;
 use std::f64::MAX;
+use std::f64::consts::PI;
 use std::iter::Scan;
 use std::str;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::task::Context;
 use std::thread::panicking;
 use log::info;
@@ -14,13 +16,19 @@ use web_sys::console::group;
 use web_sys::console::info;
 use web_sys::{window, CanvasRenderingContext2d, Element, DomParser, CanvasGradient, HtmlCanvasElement, Path2d, CssStyleDeclaration};
 use svgtypes::Transform;
+use serde::{Serialize, Deserialize};
 
 use super::geometry::{Point2D, Vector2D};
+use super::theme::Theme;
 
 // Shape 트레이트 정의
 pub trait Shape : Send + Sync + Any{
     fn color(&self) -> &str;
     fn line_width(&self) -> f64 { 2.0 }
+    fn set_color(&mut self, color: &str);
+    fn set_line_width(&mut self, width: f64);
+    fn set_fill(&mut self, _fill: Option<Fill>) {}             // 채우기를 지원하는 닫힌 도형만 구현
+    fn set_stroke_style(&mut self, _style: StrokeStyle) {}     // 선 스타일을 지원하는 도형만 구현
     fn max_point(&self) -> Point2D;
     fn min_point(&self) -> Point2D;
     fn is_hit(&self, x: f64, y: f64, scale: f64) -> bool;
@@ -35,10 +43,694 @@ pub trait Shape : Send + Sync + Any{
     fn draw(&mut self, context: &CanvasRenderingContext2d, scale: f64);
     fn draw_xor(&self, context: &CanvasRenderingContext2d, scale: f64);
     fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64);
+    fn appearance(&self) -> &Appearance;
+    fn appearance_mut(&mut self) -> &mut Appearance;
+    fn opacity(&self) -> f64 { self.appearance().opacity }
+    fn set_opacity(&mut self, value: f64) { self.appearance_mut().opacity = value.clamp(0.0, 1.0); }
+    fn blend_mode(&self) -> BlendMode { self.appearance().blend_mode }
+    fn set_blend_mode(&mut self, value: BlendMode) { self.appearance_mut().blend_mode = value; }
+    fn effects(&self) -> &Effects { &self.appearance().effects }
+    fn set_effects(&mut self, effects: Effects) { self.appearance_mut().effects = effects; }
+    fn style_name(&self) -> Option<&str> { self.appearance().style_name.as_deref() }     // 참조하는 이름 있는 스타일
+    fn set_style_name(&mut self, name: Option<String>) { self.appearance_mut().style_name = name; }
+    fn apply_style(&mut self, style: &ShapeStyle);  // 스타일 속성 중 지원하는 것만 적용
+    /// 도형의 현재 스타일 속성
+    fn style(&self) -> ShapeStyle {
+        ShapeStyle{ color: self.color().to_string(), line_width: self.line_width(), fill: None, stroke_style: StrokeStyle::default(), font: None }
+    }
+    /// 효과(그림자, 흐림)까지 포함한 영역 (min, max)
+    fn bounds(&self) -> (Point2D, Point2D) {
+        let (left, top, right, bottom) = self.effects().margins();
+        let (min_pt, max_pt) = (self.min_point(), self.max_point());
+        (Point2D::new(min_pt.x - left, min_pt.y - top), Point2D::new(max_pt.x + right, max_pt.y + bottom))
+    }
+    /// 속성 창에 표시할 기하 속성 (각도는 도 단위)
+    fn properties(&self) -> Vec<Property> { Vec::new() }
+    /// 속성 창에서 편집한 값을 적용한다. (name은 properties()가 반환한 속성의 이름)
+    fn set_property(&mut self, _name: &str, _value: &PropertyValue) {}
+    /// 텍스트를 얹을 수 있는 경로 (문서 좌표의 꺾은선, 지원하지 않으면 빈 목록)
+    fn guide_path(&self) -> Vec<Point2D> { Vec::new() }
+    fn to_svg(&self) -> String;     // SVG 요소 문자열로 변환
     fn as_any(&self) -> &dyn Any;   // ✅ Needed for downcasting
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
+/// SVG 텍스트에 들어갈 특수 문자를 escape한다.
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 덮어쓸 SVG 속성을 하위 요소에 적용한다.
+/// 선 속성(stroke, stroke-width 등)은 선을 그리는 요소에만, fill은 채우는 요소에만 적용하고 `<defs>` 안(그레이디언트의 `<stop>` 등)은 건너뛴다.
+pub fn apply_svg_overrides(svg_element: &Element, overrides: &HashMap<String, String>) {
+    if overrides.is_empty() {
+        return;
+    }
+
+    let elements = svg_element.query_selector_all("*").unwrap();
+    for i in 0..elements.length() {
+        let Some(element) = elements.item(i).and_then(|node| node.dyn_into::<Element>().ok()) else { continue; };
+        if element.closest("defs").ok().flatten().is_some() {
+            continue;
+        }
+        for (key, value) in overrides {
+            let paint = if key.starts_with("stroke") { "stroke" } else if key.starts_with("fill") { "fill" } else { key.as_str() };
+            if element.get_attribute(paint).is_some_and(|current| current != "none") {
+                let _ = element.set_attribute(key, value);
+            }
+        }
+    }
+}
+
+/// SVG 문서에 덮어쓸 속성을 적용한 `<svg>` 요소 문자열 (파싱할 수 없으면 원래 문자열)
+pub fn svg_with_overrides(svg_text: &str, overrides: &HashMap<String, String>) -> String {
+    let parser = DomParser::new().unwrap();
+    let Ok(doc) = parser.parse_from_string(svg_text, web_sys::SupportedType::ImageSvgXml) else { return svg_text.to_string(); };
+    let Some(svg_element) = doc.query_selector("svg").ok().flatten() else { return svg_text.to_string(); };
+    apply_svg_overrides(&svg_element, overrides);
+    svg_element.outer_html()
+}
+
+/// #rrggbb 색상과 불투명도를 rgba() 문자열로 바꾼다.
+fn rgba_css(color: &str, alpha: f64) -> String {
+    let hex = color.trim_start_matches('#');
+    let channel = |index: usize| hex.get(index..index + 2).and_then(|value| u8::from_str_radix(value, 16).ok());
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => format!("rgba({}, {}, {}, {})", r, g, b, alpha),
+        _ => color.to_string(),
+    }
+}
+
+/// 닫힌 도형의 채우기 (단색 + 불투명도, 또는 그라디언트)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fill{
+    pub color: String,  // #rrggbb
+    pub alpha: f64,     // 0 ~ 1
+    pub gradient: Option<Gradient>, // 있으면 color 대신 사용
+}
+impl Fill{
+    pub fn new(color: &str, alpha: f64) -> Self {
+        Fill{ color: color.to_string(), alpha: alpha.clamp(0.0, 1.0), gradient: None }
+    }
+
+    pub fn with_gradient(gradient: Gradient, alpha: f64) -> Self {
+        let color = gradient.stops.first().map_or("#000000".to_string(), |stop| stop.color.clone());
+        Fill{ color, alpha: alpha.clamp(0.0, 1.0), gradient: Some(gradient) }
+    }
+
+    /// 캔버스의 fill style로 사용할 rgba() 문자열
+    pub fn to_css(&self) -> String {
+        rgba_css(&self.color, self.alpha)
+    }
+
+    /// 캔버스에 채우기 스타일을 적용한다. bounds는 도형의 (left, top, right, bottom)
+    pub fn apply(&self, context: &CanvasRenderingContext2d, bounds: (f64, f64, f64, f64)) {
+        match self.gradient.as_ref().and_then(|gradient| gradient.create(context, bounds, self.alpha)) {
+            Some(gradient) => context.set_fill_style(&gradient.into()),
+            None => context.set_fill_style(&JsValue::from_str(&self.to_css())),
+        }
+    }
+}
+
+/// 그라디언트 종류
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GradientKind{
+    Linear,
+    Radial,
+}
+impl GradientKind{
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(GradientKind::Linear),
+            "radial" => Some(GradientKind::Radial),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str{
+        match self {
+            GradientKind::Linear => "linear",
+            GradientKind::Radial => "radial",
+        }
+    }
+}
+
+/// 그라디언트의 색 정지점
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop{
+    pub offset: f64,    // 0 ~ 1
+    pub color: String,  // #rrggbb
+    pub alpha: f64,     // 0 ~ 1
+}
+impl GradientStop{
+    pub fn new(offset: f64, color: &str, alpha: f64) -> Self {
+        GradientStop{ offset: offset.clamp(0.0, 1.0), color: color.to_string(), alpha: alpha.clamp(0.0, 1.0) }
+    }
+}
+
+/// 선형/원형 그라디언트
+/// start, end는 도형 영역에 대한 비율(0 ~ 1)로 저장해서 도형을 옮기거나 크기를 바꿔도 따라간다.
+/// 선형은 start에서 end 방향으로, 원형은 start가 중심이고 end까지의 거리가 반지름이다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gradient{
+    pub kind: GradientKind,
+    pub start: Point2D,
+    pub end: Point2D,
+    pub stops: Vec<GradientStop>,
+}
+
+static GRADIENT_ID: AtomicUsize = AtomicUsize::new(0);
+
+impl Gradient{
+    pub fn new(kind: GradientKind, stops: Vec<GradientStop>) -> Self {
+        let (start, end) = match kind {
+            GradientKind::Linear => (Point2D::new(0.0, 0.5), Point2D::new(1.0, 0.5)),
+            GradientKind::Radial => (Point2D::new(0.5, 0.5), Point2D::new(1.0, 0.5)),
+        };
+        let mut gradient = Gradient{ kind, start, end, stops };
+        gradient.sort_stops();
+        gradient
+    }
+
+    pub fn sort_stops(&mut self) {
+        self.stops.sort_by(|a, b| a.offset.partial_cmp(&b.offset).unwrap_or(std::cmp::Ordering::Equal));
+    }
+
+    fn to_absolute(point: Point2D, bounds: (f64, f64, f64, f64)) -> Point2D {
+        let (left, top, right, bottom) = bounds;
+        Point2D::new(left + (right - left) * point.x, top + (bottom - top) * point.y)
+    }
+
+    /// 시작점(또는 중심)과 끝점(또는 반지름) 조절점의 위치
+    pub fn handle_points(&self, bounds: (f64, f64, f64, f64)) -> [Point2D; 2] {
+        [Self::to_absolute(self.start, bounds), Self::to_absolute(self.end, bounds)]
+    }
+
+    /// 조절점을 옮긴다. 원형의 중심을 옮기면 반지름 조절점도 함께 움직인다.
+    pub fn move_handle(&mut self, index: usize, dx: f64, dy: f64, bounds: (f64, f64, f64, f64)) {
+        let (left, top, right, bottom) = bounds;
+        let (width, height) = (right - left, bottom - top);
+        if width.abs() < f64::EPSILON || height.abs() < f64::EPSILON {return;}
+
+        let delta = Point2D::new(dx / width, dy / height);
+        match index {
+            0 => {
+                self.start = Point2D::new(self.start.x + delta.x, self.start.y + delta.y);
+                if self.kind == GradientKind::Radial {
+                    self.end = Point2D::new(self.end.x + delta.x, self.end.y + delta.y);
+                }
+            }
+            1 => self.end = Point2D::new(self.end.x + delta.x, self.end.y + delta.y),
+            _ => {}
+        }
+    }
+
+    fn radius(&self, bounds: (f64, f64, f64, f64)) -> f64 {
+        let [start, end] = self.handle_points(bounds);
+        Vector2D::from_points(start, end).length()
+    }
+
+    /// 캔버스 그라디언트를 만든다. alpha는 채우기 전체의 불투명도
+    pub fn create(&self, context: &CanvasRenderingContext2d, bounds: (f64, f64, f64, f64), alpha: f64) -> Option<CanvasGradient> {
+        let [start, end] = self.handle_points(bounds);
+        let gradient = match self.kind {
+            GradientKind::Linear => context.create_linear_gradient(start.x, start.y, end.x, end.y),
+            GradientKind::Radial => context.create_radial_gradient(start.x, start.y, 0.0, start.x, start.y, self.radius(bounds)).ok()?,
+        };
+        for stop in &self.stops {
+            gradient.add_color_stop(stop.offset as f32, &rgba_css(&stop.color, stop.alpha * alpha)).ok()?;
+        }
+        Some(gradient)
+    }
+
+    /// 그라디언트 조절점과 방향선을 그린다.
+    pub fn draw_handles(&self, context: &CanvasRenderingContext2d, scale: f64, bounds: (f64, f64, f64, f64)) {
+        let [start, end] = self.handle_points(bounds);
+        let adjusted_width = 5.0 / scale;
+
+        context.save();
+        context.set_stroke_style(&JsValue::from_str(&Theme::current().handle_stroke));
+        context.set_line_width(1.0 / scale);
+        context.begin_path();
+        if self.kind == GradientKind::Radial {
+            context.arc(start.x, start.y, self.radius(bounds), 0.0, PI * 2.0).unwrap();
+        }
+        context.move_to(start.x, start.y);
+        context.line_to(end.x, end.y);
+        context.stroke();
+
+        // 정지점 색을 가진 마름모
+        for (point, stop) in [start, end].iter().zip([self.stops.first(), self.stops.last()]) {
+            context.begin_path();
+            context.move_to(point.x, point.y - adjusted_width);
+            context.line_to(point.x + adjusted_width, point.y);
+            context.line_to(point.x, point.y + adjusted_width);
+            context.line_to(point.x - adjusted_width, point.y);
+            context.close_path();
+            context.set_fill_style(&JsValue::from_str(stop.map_or("#FFFFFF", |stop| stop.color.as_str())));
+            context.fill();
+            context.stroke();
+        }
+        context.restore();
+    }
+
+    /// `<linearGradient>` 또는 `<radialGradient>` 요소. 좌표는 도형의 사용자 좌표계 기준이다.
+    pub fn to_svg_defs(&self, id: &str, bounds: (f64, f64, f64, f64), alpha: f64) -> String {
+        let [start, end] = self.handle_points(bounds);
+        let stops = self.stops.iter().map(|stop| format!(r#"<stop offset="{}" stop-color="{}" stop-opacity="{}"/>"#,
+            stop.offset, escape_xml(&stop.color), stop.alpha * alpha)).collect::<String>();
+        match self.kind {
+            GradientKind::Linear => format!(r#"<defs><linearGradient id="{}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}">{}</linearGradient></defs>"#,
+                id, start.x, start.y, end.x, end.y, stops),
+            GradientKind::Radial => format!(r#"<defs><radialGradient id="{}" gradientUnits="userSpaceOnUse" cx="{}" cy="{}" r="{}">{}</radialGradient></defs>"#,
+                id, start.x, start.y, self.radius(bounds), stops),
+        }
+    }
+}
+/// 도형을 아래 내용과 합성하는 방법
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendMode{
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+impl BlendMode{
+    pub const ALL: [BlendMode; 16] = [BlendMode::Normal, BlendMode::Multiply, BlendMode::Screen, BlendMode::Overlay, BlendMode::Darken, BlendMode::Lighten
+        , BlendMode::ColorDodge, BlendMode::ColorBurn, BlendMode::HardLight, BlendMode::SoftLight, BlendMode::Difference, BlendMode::Exclusion
+        , BlendMode::Hue, BlendMode::Saturation, BlendMode::Color, BlendMode::Luminosity];
+
+    /// CSS mix-blend-mode 이름
+    pub fn as_str(&self) -> &'static str{
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BlendMode>{
+        BlendMode::ALL.iter().copied().find(|mode| mode.as_str() == name)
+    }
+
+    /// 캔버스 globalCompositeOperation 값
+    pub fn composite_operation(&self) -> &'static str{
+        match self {
+            BlendMode::Normal => "source-over",
+            mode => mode.as_str(),
+        }
+    }
+}
+
+/// 도형의 불투명도와 혼합 모드를 캔버스에 적용한다. 호출하는 쪽에서 save/restore로 감싼다.
+pub fn apply_compositing(context: &CanvasRenderingContext2d, opacity: f64, blend_mode: BlendMode) {
+    context.set_global_alpha(context.global_alpha() * opacity);
+    context.set_global_composite_operation(blend_mode.composite_operation()).unwrap();
+}
+
+/// 불투명도/혼합 모드, 효과가 있으면 도형의 SVG 요소를 `<g>`로 감싼다.
+pub fn svg_with_compositing(shape: &dyn Shape) -> String {
+    let mut svg = shape.to_svg();
+    if !shape.effects().is_empty() {
+        let id = format!("effect-{}", EFFECT_ID.fetch_add(1, Ordering::Relaxed));
+        svg = format!(r#"{}<g filter="url(#{})">{}</g>"#, shape.effects().to_svg_filter(&id, shape), id, svg);
+    }
+    if shape.opacity() >= 1.0 && shape.blend_mode() == BlendMode::Normal {
+        return svg;
+    }
+
+    format!(r#"<g opacity="{}" style="mix-blend-mode:{}">{}</g>"#, shape.opacity(), shape.blend_mode().as_str(), svg)
+}
+
+/// 그림자 (색상, 오프셋, 흐림 정도)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shadow{
+    pub color: String,  // #rrggbb
+    pub alpha: f64,     // 0 ~ 1
+    pub offset_x: f64,
+    pub offset_y: f64,
+    pub blur: f64,      // 캔버스 shadowBlur 값 (표준 편차의 2배)
+}
+impl Shadow{
+    pub fn new(color: &str, alpha: f64, offset_x: f64, offset_y: f64, blur: f64) -> Self {
+        Shadow{ color: color.to_string(), alpha: alpha.clamp(0.0, 1.0), offset_x, offset_y, blur: blur.max(0.0) }
+    }
+}
+
+/// 도형 효과: 그림자와 가우시안 흐림
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Effects{
+    pub shadow: Option<Shadow>,
+    pub blur: f64,  // 가우시안 흐림의 표준 편차 (0이면 없음)
+}
+
+static EFFECT_ID: AtomicUsize = AtomicUsize::new(0);
+
+impl Effects{
+    pub fn is_empty(&self) -> bool {
+        self.shadow.is_none() && self.blur <= 0.0
+    }
+
+    /// 효과 때문에 도형 밖으로 번지는 거리 (left, top, right, bottom)
+    /// 가우시안은 표준 편차의 3배까지만 계산한다.
+    pub fn margins(&self) -> (f64, f64, f64, f64) {
+        let blur = self.blur.max(0.0) * 3.0;
+        match &self.shadow {
+            Some(shadow) => {
+                let spread = shadow.blur * 1.5;
+                (blur + (spread - shadow.offset_x).max(0.0), blur + (spread - shadow.offset_y).max(0.0),
+                 blur + (spread + shadow.offset_x).max(0.0), blur + (spread + shadow.offset_y).max(0.0))
+            }
+            None => (blur, blur, blur, blur),
+        }
+    }
+
+    /// 캔버스에 그림자와 흐림을 적용한다. 호출하는 쪽에서 save/restore로 감싼다.
+    pub fn apply(&self, context: &CanvasRenderingContext2d) {
+        if let Some(shadow) = &self.shadow {
+            context.set_shadow_color(&rgba_css(&shadow.color, shadow.alpha));
+            context.set_shadow_offset_x(shadow.offset_x);
+            context.set_shadow_offset_y(shadow.offset_y);
+            context.set_shadow_blur(shadow.blur);
+        }
+        if self.blur > 0.0 {
+            context.set_filter(&format!("blur({}px)", self.blur));
+        }
+    }
+
+    /// 조절점처럼 효과 없이 그려야 하는 것들을 위해 효과를 끈다.
+    pub fn clear(context: &CanvasRenderingContext2d) {
+        context.set_shadow_color("rgba(0, 0, 0, 0)");
+        context.set_filter("none");
+    }
+
+    /// `<filter>` 요소. 회전된 도형도 잘리지 않도록 중심에서 대각선 길이만큼의 영역을 사용한다.
+    pub fn to_svg_filter(&self, id: &str, shape: &dyn Shape) -> String {
+        let (min_pt, max_pt) = (shape.min_point(), shape.max_point());
+        let center = Point2D::new((min_pt.x + max_pt.x) * 0.5, (min_pt.y + max_pt.y) * 0.5);
+        let (left, top, right, bottom) = self.margins();
+        let half = Vector2D::from_points(min_pt, max_pt).length() * 0.5 + left.max(top).max(right).max(bottom);
+
+        let mut primitives = String::new();
+        let mut input = "SourceGraphic";
+        if self.blur > 0.0 {
+            primitives += &format!(r#"<feGaussianBlur in="SourceGraphic" stdDeviation="{}" result="blurred"/>"#, self.blur);
+            input = "blurred";
+        }
+        if let Some(shadow) = &self.shadow {
+            primitives += &format!(r#"<feDropShadow in="{}" dx="{}" dy="{}" stdDeviation="{}" flood-color="{}" flood-opacity="{}"/>"#,
+                input, shadow.offset_x, shadow.offset_y, shadow.blur * 0.5, escape_xml(&shadow.color), shadow.alpha);
+        }
+
+        format!(r#"<defs><filter id="{}" filterUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}">{}</filter></defs>"#,
+            id, center.x - half, center.y - half, half * 2.0, half * 2.0, primitives)
+    }
+}
+
+/// 모든 도형이 공통으로 가지는 합성 속성 (불투명도, 혼합 모드, 효과, 이름 있는 스타일)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Appearance{
+    pub opacity: f64,
+    pub blend_mode: BlendMode,
+    pub effects: Effects,
+    pub style_name: Option<String>,
+}
+impl Default for Appearance{
+    fn default() -> Self {
+        Appearance{ opacity: 1.0, blend_mode: BlendMode::Normal, effects: Effects::default(), style_name: None }
+    }
+}
+impl Appearance{
+    /// 불투명도, 혼합 모드, 효과를 캔버스에 적용한다. 호출하는 쪽에서 save/restore로 감싼다.
+    pub fn apply(&self, context: &CanvasRenderingContext2d){
+        apply_compositing(context, self.opacity, self.blend_mode);
+        self.effects.apply(context);
+    }
+}
+
+/// 속성 창에서 편집하는 값
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue{
+    Number(f64),
+    Text(String),
+    Flag(bool),
+}
+impl PropertyValue{
+    pub fn as_number(&self) -> Option<f64>{
+        match self {
+            PropertyValue::Number(value) if value.is_finite() => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str>{
+        match self {
+            PropertyValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_flag(&self) -> Option<bool>{
+        match self {
+            PropertyValue::Flag(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// 속성 창에 표시할 도형의 속성
+#[derive(Debug, Clone)]
+pub struct Property{
+    pub name: &'static str,     // set_property에 넘기는 이름
+    pub label: &'static str,    // 속성 창에 표시할 이름
+    pub value: PropertyValue,
+}
+impl Property{
+    pub fn number(name: &'static str, label: &'static str, value: f64) -> Self {
+        Property{ name, label, value: PropertyValue::Number(value) }
+    }
+
+    pub fn text(name: &'static str, label: &'static str, value: &str) -> Self {
+        Property{ name, label, value: PropertyValue::Text(value.to_string()) }
+    }
+
+    pub fn flag(name: &'static str, label: &'static str, value: bool) -> Self {
+        Property{ name, label, value: PropertyValue::Flag(value) }
+    }
+}
+
+/// 텍스트 글꼴 (글꼴 이름, 크기, 굵기, 기울임)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextFont{
+    pub family: String,     // CSS font-family 값 (예: sans-serif, "Malgun Gothic")
+    pub size: f64,          // px
+    pub weight: u16,        // 100 ~ 900 (400: 보통, 700: 굵게)
+    pub italic: bool,
+}
+impl Default for TextFont{
+    fn default() -> Self {
+        TextFont{ family: "sans-serif".to_string(), size: 20.0, weight: 400, italic: false }
+    }
+}
+impl TextFont{
+    /// 캔버스 font 속성에 사용할 CSS font 문자열
+    pub fn to_css(&self) -> String {
+        format!("{}{} {}px {}", if self.italic { "italic " } else { "" }, self.weight, self.size, self.family)
+    }
+
+    /// SVG `<text>` 요소의 글꼴 속성
+    pub fn svg_attributes(&self) -> String {
+        format!(r#"font-family="{}" font-size="{}" font-weight="{}" font-style="{}""#,
+            escape_xml(&self.family), self.size, self.weight, if self.italic { "italic" } else { "normal" })
+    }
+}
+
+/// 이름 있는 스타일에 저장되는 속성. 도형은 자신이 지원하는 속성만 적용한다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShapeStyle{
+    pub color: String,
+    pub line_width: f64,
+    pub fill: Option<Fill>,
+    pub stroke_style: StrokeStyle,
+    #[serde(default)]
+    pub font: Option<TextFont>,     // None이면 글꼴은 바꾸지 않는다
+}
+
+/// 도형의 서식 전체 (스타일, 불투명도, 혼합 모드, 효과). 서식 복사와 되돌리기에 사용한다.
+#[derive(Debug, Clone)]
+pub struct ShapeFormat{
+    pub style: ShapeStyle,
+    pub appearance: Appearance,
+}
+
+impl ShapeFormat{
+    pub fn from_shape(shape: &dyn Shape) -> Self {
+        ShapeFormat{ style: shape.style(), appearance: shape.appearance().clone() }
+    }
+
+    pub fn apply_to(&self, shape: &mut dyn Shape) {
+        shape.apply_style(&self.style);
+        *shape.appearance_mut() = self.appearance.clone();
+    }
+}
+
+/// 선 끝 모양
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LineCap{
+    Butt,
+    Round,
+    Square,
+}
+impl LineCap{
+    pub fn from_name(name: &str) -> Option<LineCap>{
+        match name {
+            "butt" => Some(LineCap::Butt),
+            "round" => Some(LineCap::Round),
+            "square" => Some(LineCap::Square),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str{
+        match self {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        }
+    }
+}
+
+/// 선 연결 모양
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LineJoin{
+    Miter,
+    Round,
+    Bevel,
+}
+impl LineJoin{
+    pub fn from_name(name: &str) -> Option<LineJoin>{
+        match name {
+            "miter" => Some(LineJoin::Miter),
+            "round" => Some(LineJoin::Round),
+            "bevel" => Some(LineJoin::Bevel),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str{
+        match self {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        }
+    }
+}
+
+/// 선 스타일 (점선, 끝 모양, 연결 모양, 마이터 한계)
+/// 점선 길이와 오프셋은 선 굵기의 배수로 저장하여 굵기와 확대 비율을 따라간다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrokeStyle{
+    pub dash: Vec<f64>,
+    pub dash_offset: f64,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub miter_limit: f64,
+}
+impl Default for StrokeStyle{
+    fn default() -> Self {
+        StrokeStyle{ dash: Vec::new(), dash_offset: 0.0, cap: LineCap::Butt, join: LineJoin::Miter, miter_limit: 10.0 }
+    }
+}
+impl StrokeStyle{
+    /// 점선 프리셋 (solid, dashed, dotted, dash-dot)
+    pub fn dash_preset(name: &str) -> Option<Vec<f64>>{
+        match name {
+            "solid" => Some(Vec::new()),
+            "dashed" => Some(vec![4.0, 2.0]),
+            "dotted" => Some(vec![1.0, 2.0]),
+            "dash-dot" => Some(vec![4.0, 2.0, 1.0, 2.0]),
+            _ => None,
+        }
+    }
+
+    /// 캔버스에 선 스타일을 적용한다. line_width는 화면에 그릴 선 굵기(line_width / scale)이다.
+    pub fn apply(&self, context: &CanvasRenderingContext2d, line_width: f64){
+        context.set_line_cap(self.cap.as_str());
+        context.set_line_join(self.join.as_str());
+        context.set_miter_limit(self.miter_limit);
+
+        let dash_pattern = js_sys::Array::new();
+        for length in self.dash.iter(){
+            dash_pattern.push(&(length * line_width).into());
+        }
+        context.set_line_dash(&dash_pattern).unwrap();
+        context.set_line_dash_offset(self.dash_offset * line_width);
+    }
+
+    /// SVG stroke-* 속성 문자열 (기본값은 생략, 앞에 공백 포함)
+    pub fn svg_attributes(&self, line_width: f64) -> String{
+        let mut attributes = String::new();
+        if self.cap != LineCap::Butt{
+            attributes += &format!(r#" stroke-linecap="{}""#, self.cap.as_str());
+        }
+        if self.join != LineJoin::Miter{
+            attributes += &format!(r#" stroke-linejoin="{}""#, self.join.as_str());
+        }
+        if self.miter_limit != 10.0{
+            attributes += &format!(r#" stroke-miterlimit="{}""#, self.miter_limit);
+        }
+        if !self.dash.is_empty(){
+            let dash = self.dash.iter().map(|length| (length * line_width).to_string()).collect::<Vec<_>>().join(" ");
+            attributes += &format!(r#" stroke-dasharray="{}""#, dash);
+            if self.dash_offset != 0.0{
+                attributes += &format!(r#" stroke-dashoffset="{}""#, self.dash_offset * line_width);
+            }
+        }
+        attributes
+    }
+}
+
+/// SVG 요소의 fill 속성 문자열과 그 앞에 둘 `<defs>` (그라디언트인 경우)
+/// bounds는 그라디언트 좌표 계산에 사용할 도형의 (left, top, right, bottom)
+pub fn svg_fill_attributes(fill: Option<&Fill>, bounds: (f64, f64, f64, f64)) -> (String, String) {
+    match fill {
+        Some(Fill{ gradient: Some(gradient), alpha, .. }) => {
+            let id = format!("gradient-{}", GRADIENT_ID.fetch_add(1, Ordering::Relaxed));
+            (gradient.to_svg_defs(&id, bounds, *alpha), format!(r#"fill="url(#{})""#, id))
+        }
+        Some(fill) => (String::new(), format!(r#"fill="{}" fill-opacity="{}""#, escape_xml(&fill.color), fill.alpha)),
+        None => (String::new(), r#"fill="none""#.to_string()),
+    }
+}
+
 // ✅ Implement PartialEq for dyn Shape (by type downcasting)
 impl PartialEq for dyn Shape {
     fn eq(&self, other: &Self) -> bool {
@@ -46,13 +738,84 @@ impl PartialEq for dyn Shape {
     }
 }
 
+/// 펜 입력 한 점의 필압/기울기/시간 정보
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokeSample{
+    pub pressure: Option<f64>,  // 0 ~ 1, 필압을 지원하지 않는 장치(마우스)는 None
+    pub tilt_x: f64,            // -90 ~ 90 (도)
+    pub tilt_y: f64,            // -90 ~ 90 (도)
+    pub time: f64,              // ms
+}
+
+/// 펜슬 브러시 프리셋
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Brush{
+    Pen,
+    Marker,
+    Highlighter,
+}
+impl Brush{
+    pub fn from_name(name: &str) -> Option<Brush>{
+        match name {
+            "pen" => Some(Brush::Pen),
+            "marker" => Some(Brush::Marker),
+            "highlighter" => Some(Brush::Highlighter),
+            _ => None,
+        }
+    }
+
+    /// 선 굵기에 대한 브러시 굵기 배율
+    fn width_scale(&self) -> f64{
+        match self {
+            Brush::Pen => 1.0,
+            Brush::Marker => 2.5,
+            Brush::Highlighter => 6.0,
+        }
+    }
+
+    fn alpha(&self) -> f64{
+        match self {
+            Brush::Highlighter => 0.35,
+            _ => 1.0,
+        }
+    }
+
+    fn composite_operation(&self) -> &'static str{
+        match self {
+            Brush::Highlighter => "multiply",
+            _ => "source-over",
+        }
+    }
+
+    /// 필압(0 ~ 1)에 따른 굵기 비율
+    fn pressure_factor(&self, pressure: f64) -> f64{
+        match self {
+            Brush::Pen => 0.15 + 0.85 * pressure,
+            Brush::Marker => 0.6 + 0.4 * pressure,
+            Brush::Highlighter => 1.0,
+        }
+    }
+
+    /// 속도(px/ms)에 따른 굵기 비율 (필압이 없을 때 사용)
+    fn speed_factor(&self, speed: f64) -> f64{
+        match self {
+            Brush::Pen => (1.2 - speed * 0.35).clamp(0.35, 1.0),
+            Brush::Marker => (1.1 - speed * 0.15).clamp(0.6, 1.0),
+            Brush::Highlighter => 1.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Pencil{
     selected: bool,
     hovered: bool,
     color: String,
     line_width: f64,
+    brush: Brush,
     points: Vec<Point2D>,
+    samples: Vec<StrokeSample>,     // points와 같은 개수, 비어 있으면 일정한 굵기
+    appearance: Appearance,
     selected_control_point: i32,
 }
 impl Pencil{
@@ -62,12 +825,126 @@ impl Pencil{
             hovered: false, 
             color, 
             line_width, 
+            brush: Brush::Pen,
             points,
+            samples: Vec::new(),
+            appearance: Appearance::default(),
             selected_control_point: -1,}
     }
 
+    /// 포인터 이벤트에서 얻은 필압/기울기/시간 정보로 펜슬을 생성한다.
+    pub fn with_samples(color: String, line_width: f64, brush: Brush, points: Vec<Point2D>, samples: Vec<StrokeSample>) -> Self {
+        let mut pencil = Pencil::new(color, line_width, points);
+        pencil.brush = brush;
+        if samples.len() == pencil.points.len(){
+            pencil.samples = samples;
+        }
+        pencil
+    }
+
     pub fn add_point(&mut self, point: Point2D){
         self.points.push(point);
+        self.samples.clear();
+    }
+
+    /// 각 점의 선 굵기를 계산한다. 필압이 있으면 필압을, 없으면 속도를 따른다.
+    fn stroke_widths(&self, scale: f64) -> Vec<f64>{
+        let base = self.line_width * self.brush.width_scale() / scale;
+        if self.samples.len() != self.points.len(){
+            return vec![base; self.points.len()];
+        }
+
+        let factors: Vec<f64> = self.samples.iter().enumerate().map(|(i, sample)| {
+            let mut factor = match sample.pressure {
+                Some(pressure) => self.brush.pressure_factor(pressure),
+                None if i > 0 => {
+                    let distance = Vector2D::from_points(self.points[i - 1], self.points[i]).length();
+                    let elapsed = (sample.time - self.samples[i - 1].time).max(1.0);
+                    self.brush.speed_factor(distance / elapsed)
+                }
+                None => self.brush.speed_factor(0.0),
+            };
+            // 마커는 기울일수록 넓게 칠해진다.
+            if self.brush == Brush::Marker{
+                let tilt = sample.tilt_x.hypot(sample.tilt_y).min(90.0);
+                factor *= 1.0 + tilt / 90.0 * 0.5;
+            }
+            factor
+        }).collect();
+
+        // 굵기가 튀지 않도록 이웃한 점과 평균을 낸다.
+        let smoothed: Vec<f64> = (0..factors.len()).map(|i| {
+            let from = i.saturating_sub(1);
+            let to = (i + 2).min(factors.len());
+            factors[from..to].iter().sum::<f64>() / (to - from) as f64
+        }).collect();
+
+        // 펜은 시작과 끝을 가늘게 한다.
+        let mut lengths = vec![0.0; self.points.len()];
+        for i in 1..self.points.len(){
+            lengths[i] = lengths[i - 1] + Vector2D::from_points(self.points[i - 1], self.points[i]).length();
+        }
+        let total = lengths.last().copied().unwrap_or(0.0);
+        let taper_length = self.line_width * self.brush.width_scale() * 6.0;
+
+        smoothed.iter().zip(lengths.iter()).map(|(factor, length)| {
+            let taper = if self.brush == Brush::Pen && taper_length > 0.0 {
+                (length.min(total - length) / taper_length).clamp(0.25, 1.0)
+            } else { 1.0 };
+            base * factor * taper
+        }).collect()
+    }
+
+    /// 굵기를 반영한 외곽선 (양쪽 가장자리와 둥근 끝)
+    fn outline(&self, scale: f64) -> Vec<Point2D>{
+        const CAP_STEPS: usize = 8;
+
+        let widths = self.stroke_widths(scale);
+        let count = self.points.len();
+        if count == 0{
+            return Vec::new();
+        }
+
+        let mut normals: Vec<Vector2D> = Vec::with_capacity(count);
+        for i in 0..count{
+            let mut dir = Vector2D::from_points(self.points[i.saturating_sub(1)], self.points[(i + 1).min(count - 1)]);
+            if dir.length() > 0.0{
+                dir.normalize();
+                normals.push(Vector2D::new(-dir.y, dir.x));
+            }
+            else{
+                normals.push(normals.last().copied().unwrap_or(Vector2D::AXIS_Y));
+            }
+        }
+
+        let offset = |i: usize, sign: f64| -> Point2D {
+            let half = widths[i] * 0.5 * sign;
+            Point2D::new(self.points[i].x + normals[i].x * half, self.points[i].y + normals[i].y * half)
+        };
+        let cap = |i: usize, from: f64| -> Vec<Point2D> {
+            let half = widths[i] * 0.5;
+            (1..CAP_STEPS).map(|step| {
+                let angle = from - PI * step as f64 / CAP_STEPS as f64;
+                Point2D::new(self.points[i].x + half * angle.cos(), self.points[i].y + half * angle.sin())
+            }).collect()
+        };
+
+        let mut outline: Vec<Point2D> = (0..count).map(|i| offset(i, 1.0)).collect();
+        outline.extend(cap(count - 1, normals[count - 1].y.atan2(normals[count - 1].x)));
+        outline.extend((0..count).rev().map(|i| offset(i, -1.0)));
+        outline.extend(cap(0, normals[0].y.atan2(normals[0].x) + PI));
+        outline
+    }
+
+    fn build_path(&self, context: &CanvasRenderingContext2d, scale: f64){
+        let outline = self.outline(scale);
+        if let Some(first) = outline.first(){
+            context.move_to(first.x, first.y);
+            for point in outline.iter().skip(1) {
+                context.line_to(point.x, point.y);
+            }
+            context.close_path();
+        }
     }
 }
 impl Shape for Pencil{
@@ -79,6 +956,14 @@ impl Shape for Pencil{
         self.line_width
     }
 
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.line_width = width;
+    }
+
     fn max_point(&self) -> Point2D{
         self.points.iter().fold(Point2D::new(f64::MIN, f64::MIN), |acc, point| 
             Point2D::new(acc.x.max(point.x), acc.y.max(point.y))
@@ -92,12 +977,12 @@ impl Shape for Pencil{
     }
 
     fn is_hit(&self, x: f64, y: f64, scale: f64) -> bool {
-        let adjusted_width = (5.0 / scale).powf(2.0);
+        let widths = self.stroke_widths(scale);
 
-        for point in self.points.iter() {
+        for (point, width) in self.points.iter().zip(widths.iter()) {
             let dx = x - point.x;
             let dy = y - point.y;
-            if dx * dx + dy * dy < adjusted_width {
+            if dx * dx + dy * dy < (5.0 / scale + width * 0.5).powf(2.0) {
                 return true;
             }
         }
@@ -156,78 +1041,82 @@ impl Shape for Pencil{
     fn draw(&mut self, context: &CanvasRenderingContext2d, scale: f64){
         context.save();
         if self.hovered{
-            context.set_stroke_style(&JsValue::from_str("#ff0000"));
+            context.set_fill_style(&JsValue::from_str(&Theme::current().hover_color));
         }
         else{
-            context.set_stroke_style(&JsValue::from_str(&self.color));
+            context.set_fill_style(&JsValue::from_str(&self.color));
         }
-        let adjusted_width = self.line_width / scale;
-        context.set_line_width(adjusted_width);
-        context.begin_path();
-        
-        if let Some(start) = self.points.first(){
-            context.move_to(start.x, start.y);
-            for point in self.points.iter().skip(1) {
-                context.line_to(point.x, point.y);
+        // 혼합 모드를 지정하지 않으면 브러시의 혼합 모드를 사용한다.
+        match self.appearance.blend_mode {
+            BlendMode::Normal => {
+                apply_compositing(context, self.appearance.opacity * self.brush.alpha(), BlendMode::Normal);
+                context.set_global_composite_operation(self.brush.composite_operation()).unwrap();
             }
-            context.stroke();
+            blend_mode => apply_compositing(context, self.appearance.opacity * self.brush.alpha(), blend_mode),
         }
+        self.appearance.effects.apply(context);
+
+        // ✅ 굵기가 변하는 선은 외곽선을 채워서 그린다.
+        context.begin_path();
+        self.build_path(context, scale);
+        context.fill();
         context.restore();
 
         if self.selected{ self.draw_control_points(context, scale);}
     }   
 
     fn draw_xor(&self, context: &CanvasRenderingContext2d, scale: f64){
-        if let Some(start) = self.points.first(){
+        if !self.points.is_empty(){
             context.set_global_composite_operation("xor").unwrap();
 
             context.begin_path();
-            context.move_to(start.x, start.y);
-            for point in self.points.iter().skip(1) {
-                context.line_to(point.x, point.y);
-            }
+            self.build_path(context, scale);
 
-            context.set_stroke_style(&JsValue::from_str(&self.color));
-            let adjusted_width = self.line_width / scale;
-            context.set_line_width(adjusted_width);
-            context.stroke();
+            context.set_fill_style(&JsValue::from_str(&self.color));
+            context.fill();
 
             context.set_global_composite_operation("source-over").unwrap(); // 기본 모드로 복원
         }
     }
 
     fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
-        let adjusted_width = 1.0 / scale * 5.0;
+        let theme = Theme::current();
 
         context.save();
-        context.set_fill_style(&"#FF0000".into()); // Red control points
-        for point in self.points.clone(){
-            context.fill_rect(point.x - adjusted_width, point.y - adjusted_width, adjusted_width * 2.0, adjusted_width * 2.0);
-        }
+        theme.apply_selection_outline(context, scale);
+        let (min_pt, max_pt) = self.bounds();
+        context.begin_path();
+        context.rect(min_pt.x, min_pt.y, max_pt.x - min_pt.x, max_pt.y - min_pt.y);
+        context.stroke();
+        context.restore();
 
-        context.set_fill_style(&"#0000FF".into()); // Red control points
-        context.set_stroke_style(&"0000FF".into());
-        let adjusted_width = 0.5 / scale;
-        context.set_line_width(adjusted_width);
+        theme.draw_handles(context, &self.points, scale);
+    }
 
-        // ✅ Set dash pattern: [Dash length, Gap length]
-        let dash_pattern = js_sys::Array::new();
-        dash_pattern.push(&(adjusted_width * 3.0).into()); // 2px dash
-        dash_pattern.push(&(adjusted_width * 3.0).into());  // 2px gap
-        context.set_line_dash(&dash_pattern).unwrap();
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
 
-        let min_pt = self.min_point();
-        let max_pt = self.max_point();
-        context.begin_path();
-        context.move_to(min_pt.x, min_pt.y);
-        context.line_to(max_pt.x, min_pt.y);
-        context.line_to(max_pt.x, max_pt.y);
-        context.line_to(min_pt.x, max_pt.y);
-        context.line_to(min_pt.x, min_pt.y);
-        context.close_path();
-        context.stroke();
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
 
-        context.restore();
+    fn apply_style(&mut self, style: &ShapeStyle) {
+        self.color = style.color.clone();
+        self.line_width = style.line_width;
+    }
+
+    fn guide_path(&self) -> Vec<Point2D> {
+        self.points.clone()
+    }
+
+    fn to_svg(&self) -> String {
+        let points = self.outline(1.0).iter().map(|point| format!("{},{}", point.x, point.y)).collect::<Vec<_>>().join(" ");
+        let blend = match self.brush.composite_operation() {
+            "source-over" => String::new(),
+            operation => format!(r#" style="mix-blend-mode:{}""#, operation),
+        };
+        format!(r#"<polygon points="{}" fill="{}" fill-opacity="{}" stroke="none"{}/>"#, points, self.color, self.brush.alpha(), blend)
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -239,6 +1128,17 @@ impl Shape for Pencil{
     }
 }
 
+/// 가져온 SVG 안의 `<text>` 요소 (찾기에 사용)
+#[derive(Debug, Clone)]
+pub struct SvgText{
+    pub text: String,
+    pub x: f64,
+    pub y: f64,             // 기준선
+    pub font: String,       // 캔버스 font 문자열
+    pub font_size: f64,
+    pub anchor: String,     // text-anchor (start, middle, end)
+}
+
 #[derive(Debug, Clone)]
 pub struct Svg{
     selected: bool,
@@ -247,6 +1147,8 @@ pub struct Svg{
     selected_control_point: i32,
 
     styles: Option<HashMap<String, HashMap<String, String>>>,
+    overrides: HashMap<String, String>, // 모든 요소에 덮어쓸 속성
+    appearance: Appearance,
 }
 
 impl Svg{
@@ -256,7 +1158,46 @@ impl Svg{
             location, 
             selected_control_point: -1,
             content: svg_text.to_string(), 
-            styles: None}
+            styles: None,
+            overrides: HashMap::new(),
+            appearance: Appearance::default()}
+    }
+
+    /// 문서 순서대로 `<text>` 요소의 내용과 위치를 반환한다. (render_text와 같은 속성을 읽는다)
+    pub fn text_elements(&self) -> Vec<SvgText> {
+        let parser = DomParser::new().unwrap();
+        let Ok(doc) = parser.parse_from_string(&self.content, web_sys::SupportedType::ImageSvgXml) else { return Vec::new(); };
+        let Ok(elements) = doc.query_selector_all("text") else { return Vec::new(); };
+
+        (0..elements.length()).filter_map(|i| elements.item(i).and_then(|node| node.dyn_into::<Element>().ok())).map(|element| {
+            let attribute = |name: &str, default: &str| element.get_attribute(name).unwrap_or(default.to_string());
+            let font_size = attribute("font-size", "16");
+            SvgText{
+                text: element.text_content().unwrap_or_default(),
+                x: attribute("x", "0").parse::<f64>().unwrap_or(0.0),
+                y: attribute("y", "0").parse::<f64>().unwrap_or(0.0),
+                font: format!("{}px {}", font_size, attribute("font-family", "Arial")),
+                font_size: font_size.parse::<f64>().unwrap_or(16.0),
+                anchor: attribute("text-anchor", "start"),
+            }
+        }).collect()
+    }
+
+    /// 그릴 때 모든 요소에 덮어쓸 속성을 설정한다.
+    pub fn set_overrides(&mut self, overrides: HashMap<String, String>) {
+        self.overrides = overrides;
+    }
+
+    // 🎯 덮어쓸 속성을 하위 요소에 적용
+    fn apply_overrides(&self, svg_element: &Element) {
+        apply_svg_overrides(svg_element, &self.overrides);
+    }
+
+    // 🎯 `stroke-width` 속성이 있으면 선 굵기 적용
+    fn apply_stroke_width(&self, context: &CanvasRenderingContext2d, svg_element: &Element) {
+        if let Some(width) = svg_element.get_attribute("stroke-width").and_then(|w| w.parse::<f64>().ok()) {
+            context.set_line_width(width);
+        }
     }
 
     // 🎯 SVG에서 Gradient를 추출하는 함수
@@ -289,6 +1230,8 @@ impl Svg{
                                         .get_attribute("stop-opacity")
                                         .unwrap_or_else(|| "1".to_string()); // 기본값은 '1'
 
+                                    let stop_color = rgba_css(&stop_color, stop_opacity.parse::<f64>().unwrap_or(1.0));
+
                                     if let Some(offset) = stop_element.get_attribute("offset") {
                                         let offset = offset.trim_end_matches('%').parse::<f32>().unwrap_or(0.0);
                                         gradient.add_color_stop(offset, &stop_color).unwrap();
@@ -312,14 +1255,14 @@ impl Svg{
             if let Some(gradient_element) = radial_gradients.item(i) {
                 if let Ok(gradient_element) = gradient_element.dyn_into::<Element>() {
                     if let Some(id) = gradient_element.get_attribute("id") {
-                        let cx = gradient_element.get_attribute("x1").unwrap_or("0".to_string()).parse::<f64>().unwrap_or(0.0);
-                        let cy = gradient_element.get_attribute("y1").unwrap_or("0".to_string()).parse::<f64>().unwrap_or(0.0);
+                        let cx = gradient_element.get_attribute("cx").or(gradient_element.get_attribute("x1")).unwrap_or("0".to_string()).parse::<f64>().unwrap_or(0.0);
+                        let cy = gradient_element.get_attribute("cy").or(gradient_element.get_attribute("y1")).unwrap_or("0".to_string()).parse::<f64>().unwrap_or(0.0);
                         let r = gradient_element.get_attribute("r").unwrap_or("1".to_string()).parse::<f64>().unwrap_or(1.0);
 
                         let transform = gradient_element.get_attribute("gradientTransform").unwrap_or("".to_string());
                         context.save();
                         self.apply_transform(context, &transform);
-                        let gradient = context.create_radial_gradient(0.0, 0.0, 0.0, cx, cy, r).unwrap();
+                        let gradient = context.create_radial_gradient(cx, cy, 0.0, cx, cy, r).unwrap();
                         context.restore();
 
                         let stops = gradient_element.query_selector_all("stop").unwrap();
@@ -336,6 +1279,8 @@ impl Svg{
                                         .get_attribute("stop-opacity")
                                         .unwrap_or_else(|| "1".to_string()); // 기본값은 '1'
 
+                                    let stop_color = rgba_css(&stop_color, stop_opacity.parse::<f64>().unwrap_or(1.0));
+
                                     if let Some(offset) = stop_element.get_attribute("offset") {
                                         let offset = offset.trim_end_matches('%').parse::<f32>().unwrap_or(0.0);
                                         gradient.add_color_stop(offset, &stop_color).unwrap();
@@ -460,14 +1405,15 @@ impl Svg{
                     let fill_style = self.parse_fill_attribute(element, gradients);
 
                     match tag_name.as_str() {
-                        "g" => self.render_group(&context, element, gradients, &fill_style),
-                        "rect" => self.render_rect(&context, element, gradients, &fill_style),
-                        "polygon" => self.render_polygon(&context, element, gradients),
-                        "polyline" => self.render_polyline(&context, element, gradients),
-                        "ellipse" => self.render_ellipse(&context, element, gradients),
-                        "circle" => self.render_circle(&context, element, gradients),
-                        "path" => self.render_path(&context, element, gradients, &fill_style),
-                        "text" => self.render_text(&context, element, gradients),
+                        "g" => self.render_group(context, element, gradients, &fill_style),
+                        "rect" => self.render_rect(context, element, gradients, &fill_style),
+                        "polygon" => self.render_polygon(context, element, gradients),
+                        "polyline" => self.render_polyline(context, element, gradients),
+                        "line" => self.render_line(context, element),
+                        "ellipse" => self.render_ellipse(context, element, gradients),
+                        "circle" => self.render_circle(context, element, gradients),
+                        "path" => self.render_path(context, element, gradients, &fill_style),
+                        "text" => self.render_text(context, element, gradients),
                         _ => (),
                     }
                 }
@@ -526,7 +1472,7 @@ impl Svg{
                     if let Some(opacity) = class_styles.get("opacity") {
                         // Set the global alpha to the specified opacity
                         let opacity_value = opacity.parse::<f64>().unwrap_or(1.0);
-                        context.set_global_alpha(opacity_value);
+                        context.set_global_alpha(context.global_alpha() * opacity_value);
                         info!("opacity: {:?}", opacity_value);
                     }
                 }
@@ -552,8 +1498,8 @@ impl Svg{
 
         let transform = group_element.get_attribute("transform").unwrap_or_default();
         self.apply_transform(context, &transform);
-        self.apply_class_attribute(&context, group_element);
-        self.apply_fill_attribute(&context, group_element, gradients);
+        self.apply_class_attribute(context, group_element);
+        self.apply_fill_attribute(context, group_element, gradients);
 
         // 🎨 그룹의 `fill` 속성 가져오기
         let mut group_fill = self.parse_fill_attribute(group_element, gradients);
@@ -567,14 +1513,15 @@ impl Svg{
                 if let Some(element) = node.dyn_ref::<Element>() {
                     let tag_name = element.tag_name().to_lowercase();
                     match tag_name.as_str() {
-                        "g" => self.render_group(&context, element, gradients, &group_fill),
-                        "rect" => self.render_rect(&context, element, gradients, &group_fill),
-                        "polygon" => self.render_polygon(&context, element, gradients),
-                        "polyline" => self.render_polyline(&context, element, gradients),
-                        "ellipse" => self.render_ellipse(&context, element, gradients),
-                        "circle" => self.render_circle(&context, element, gradients),
-                        "path" => self.render_path(&context, element, gradients, &group_fill),
-                        "text" => self.render_text(&context, element, gradients),
+                        "g" => self.render_group(context, element, gradients, &group_fill),
+                        "rect" => self.render_rect(context, element, gradients, &group_fill),
+                        "polygon" => self.render_polygon(context, element, gradients),
+                        "polyline" => self.render_polyline(context, element, gradients),
+                        "line" => self.render_line(context, element),
+                        "ellipse" => self.render_ellipse(context, element, gradients),
+                        "circle" => self.render_circle(context, element, gradients),
+                        "path" => self.render_path(context, element, gradients, &group_fill),
+                        "text" => self.render_text(context, element, gradients),
                         _ => (),
                     }
                 }
@@ -622,6 +1569,7 @@ impl Svg{
                 let stroke_color = polygon_element.get_attribute("stroke").unwrap_or("none".to_string());
                 if !stroke_color.is_empty() && stroke_color.to_lowercase() != "none" {
                     context.set_stroke_style(&JsValue::from_str(&stroke_color));
+                    self.apply_stroke_width(context, polygon_element);
                     context.stroke();
                 }
 
@@ -666,6 +1614,7 @@ impl Svg{
                 let stroke_color = polyline_element.get_attribute("stroke").unwrap_or("none".to_string());
                 if !stroke_color.is_empty() && stroke_color.to_lowercase() != "none" {
                     context.set_stroke_style(&JsValue::from_str(&stroke_color));
+                    self.apply_stroke_width(context, polyline_element);
                     context.stroke();
                 }
 
@@ -687,13 +1636,13 @@ impl Svg{
         context.ellipse(cx, cy, rx, ry, 0.0, 0.0, std::f64::consts::PI * 2.0).unwrap();
         context.close_path();
 
-        self.apply_class_attribute(&context, ellipse_element);
-        self.apply_fill_attribute(&context, ellipse_element, gradients);
-
-        context.fill();
+        self.apply_class_attribute(context, ellipse_element);
+        let filled = self.apply_fill_attribute(context, ellipse_element, gradients);
+        if filled{ context.fill(); }
 
         if !stroke_color.is_empty() && stroke_color.to_lowercase() != "none" {
             context.set_stroke_style(&JsValue::from_str(&stroke_color));
+            self.apply_stroke_width(context, ellipse_element);
             context.stroke();
         }
 
@@ -714,18 +1663,19 @@ impl Svg{
         context.save();
 
         self.apply_class_attribute(context, circle_element);
-        self.apply_fill_attribute(context, circle_element, gradients);
+        let filled = self.apply_fill_attribute(context, circle_element, gradients);
 
         context.begin_path();
         context.arc(cx, cy, r, 0.0, std::f64::consts::PI * 2.0).unwrap();
         context.close_path();
 
         // 🎯 Fill 적용
-        context.fill();
+        if filled{ context.fill(); }
 
         // 🎯 Stroke 적용
         if stroke_style.as_string().unwrap_or_default().to_lowercase() != "none" {
             context.set_stroke_style(&stroke_style);
+            self.apply_stroke_width(context, circle_element);
             context.stroke();
         }
 
@@ -784,12 +1734,38 @@ impl Svg{
 
         if !stroke_color.is_empty() && stroke_color.to_lowercase() != "none" {
             context.set_stroke_style(&JsValue::from_str(&stroke_color));
+            self.apply_stroke_width(context, rect_element);
             context.stroke();
         }
 
         context.restore();
     }
 
+    // 🎯 `line` 요소를 Canvas에 그리는 함수
+    fn render_line(&self, context: &CanvasRenderingContext2d, line_element: &Element) {
+        let x1 = line_element.get_attribute("x1").unwrap_or("0".to_string()).parse::<f64>().unwrap_or(0.0);
+        let y1 = line_element.get_attribute("y1").unwrap_or("0".to_string()).parse::<f64>().unwrap_or(0.0);
+        let x2 = line_element.get_attribute("x2").unwrap_or("0".to_string()).parse::<f64>().unwrap_or(0.0);
+        let y2 = line_element.get_attribute("y2").unwrap_or("0".to_string()).parse::<f64>().unwrap_or(0.0);
+        let stroke_color = line_element.get_attribute("stroke").unwrap_or("none".to_string());
+
+        if stroke_color.is_empty() || stroke_color.to_lowercase() == "none" {
+            return;
+        }
+
+        context.save();
+        self.apply_class_attribute(context, line_element);
+        context.set_stroke_style(&JsValue::from_str(&stroke_color));
+        self.apply_stroke_width(context, line_element);
+
+        context.begin_path();
+        context.move_to(x1, y1);
+        context.line_to(x2, y2);
+        context.stroke();
+
+        context.restore();
+    }
+
     // 🎯 `path` 요소를 Canvas에 그리는 함수
     fn render_path(&self, context: &CanvasRenderingContext2d, path_element: &Element, gradients: &HashMap<String, CanvasGradient>, group_fill: &JsValue) {
         if let Some(d_attr) = path_element.get_attribute("d") {
@@ -805,11 +1781,11 @@ impl Svg{
                 // Set the global alpha to the specified opacity
                 if let Some(opacity_attr) = path_element.get_attribute("opacity") {
                     let opacity_value = opacity_attr.parse::<f64>().unwrap_or(1.0);
-                    context.set_global_alpha(opacity_value);
+                    context.set_global_alpha(context.global_alpha() * opacity_value);
                 }
 
-                self.apply_class_attribute(&context, path_element);
-                let filled = self.apply_fill_attribute(&context, path_element, gradients);
+                self.apply_class_attribute(context, path_element);
+                let filled = self.apply_fill_attribute(context, path_element, gradients);
                 if filled{
                     context.fill_with_path_2d(&path);
                 }
@@ -823,6 +1799,7 @@ impl Svg{
                 let stroke_style= JsValue::from_str(path_element.get_attribute("stroke").unwrap_or("none".to_string()).as_str());
                 if stroke_style.as_string().unwrap_or_default().to_lowercase() != "none" {
                     context.set_stroke_style(&stroke_style);
+                    self.apply_stroke_width(context, path_element);
                     context.stroke_with_path(&path);
                 }
 
@@ -938,6 +1915,13 @@ impl Shape for Svg{
         false        
     }
 
+    fn set_color(&mut self, _color: &str) {
+        // 가져온 SVG는 파일 안의 스타일을 유지한다.
+    }
+
+    fn set_line_width(&mut self, _width: f64) {
+    }
+
     fn max_point(&self) -> Point2D{
         Point2D::new(f64::MAX, f64::MAX)
     }
@@ -984,9 +1968,13 @@ impl Shape for Svg{
         let doc = parser.parse_from_string(&self.content, web_sys::SupportedType::ImageSvgXml).unwrap();
 
         if let Some(svg_element) = doc.query_selector("svg").ok().flatten() {
+            context.save();
+            self.appearance.apply(context);
+            self.apply_overrides(&svg_element);
             let gradients = self.extract_gradients(context, &svg_element);
             self.extract_styles(&svg_element);
             self.render_svg_to_canvas(context, &svg_element, &gradients);
+            context.restore();
         } else {
             web_sys::console::log_1(&"⚠️ SVG 파싱 실패".into());
         }
@@ -998,6 +1986,27 @@ impl Shape for Svg{
     fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
     }
 
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
+
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
+
+    fn apply_style(&mut self, _style: &ShapeStyle) {
+        // 가져온 SVG는 파일 안의 스타일을 유지한다.
+    }
+
+    fn to_svg(&self) -> String {
+        // XML 선언과 DOCTYPE은 문서 중간에 올 수 없으므로 제거한다.
+        let content = match self.content.find("<svg") {
+            Some(index) => &self.content[index..],
+            None => self.content.as_str(),
+        };
+        format!(r#"<g transform="translate({} {})">{}</g>"#, self.location.x, self.location.y, content)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -0,0 +1,320 @@
+use std::any::Any;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+use web_sys::{CanvasRenderingContext2d};
+
+use super::geometry::Vector2D;
+use super::geometry::{Point2D};
+use super::theme::Theme;
+use super::shape::{Shape, Appearance, ShapeStyle, svg_with_overrides};
+use crate::symbol_library::SymbolLibrary;
+
+/// 심볼 라이브러리의 정의를 참조하는 인스턴스
+#[derive(Debug, Clone)]
+pub struct SymbolInstance{
+    selected: bool,
+    hovered: bool,
+    symbol_name: String,
+    center: Point2D,
+    rotation: f64,  // in radian
+    scale_x: f64,
+    scale_y: f64,
+    size: (f64, f64),   // 정의를 찾을 수 없을 때 사용할 크기
+    overrides: HashMap<String, String>, // SVG 속성 덮어쓰기 (예: stroke, fill)
+    appearance: Appearance,
+    selected_control_point: i32,
+}
+
+/// 회전 조절점이 위쪽 변의 가운데에서 떨어진 거리 (화면 픽셀)
+const ROTATE_HANDLE_OFFSET: f64 = 30.0;
+impl SymbolInstance{
+    pub fn new(symbol_name: &str, center: Point2D) -> Self {
+        let size = {
+            let library = SymbolLibrary::instance();
+            let library = library.lock().unwrap();
+            library.get(symbol_name).map_or((100.0, 100.0), |definition| (definition.width(), definition.height()))
+        };
+
+        SymbolInstance{
+            selected: false,
+            hovered: false,
+            symbol_name: symbol_name.to_string(),
+            center,
+            rotation: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            size,
+            overrides: HashMap::new(),
+            appearance: Appearance::default(),
+            selected_control_point: -1}
+    }
+
+    pub fn symbol_name(&self) -> &str{
+        &self.symbol_name
+    }
+
+    pub fn set_override(&mut self, attribute: &str, value: &str){
+        self.overrides.insert(attribute.to_string(), value.to_string());
+    }
+
+    /// 정의가 바뀌면 크기도 따라가도록 라이브러리에서 크기를 가져온다.
+    fn definition_size(&self) -> (f64, f64){
+        let library = SymbolLibrary::instance();
+        let library = library.lock().unwrap();
+        library.get(&self.symbol_name).map_or(self.size, |definition| (definition.width(), definition.height()))
+    }
+
+    /// 인스턴스 좌표계(중심 기준, 회전/축척 전)의 점을 캔버스 좌표로 변환한다.
+    fn to_world(&self, x: f64, y: f64) -> Point2D{
+        let mut dir = Vector2D::new(x * self.scale_x, y * self.scale_y);
+        dir.rotate_by(self.rotation);
+        Point2D::new(self.center.x + dir.x, self.center.y + dir.y)
+    }
+
+    fn corners(&self) -> Vec<Point2D>{
+        let (width, height) = self.definition_size();
+        let (hw, hh) = (width * 0.5, height * 0.5);
+        vec![self.to_world(-hw, -hh), self.to_world(hw, -hh), self.to_world(hw, hh), self.to_world(-hw, hh)]
+    }
+
+    /// 0: 축척, 1: 회전, 2: 중심
+    /// 회전 조절점은 확대/축소와 관계없이 화면에서 같은 거리에 보이도록 scale로 나눈다.
+    fn control_points(&self, scale: f64) -> Vec<Point2D>{
+        let corners = self.corners();
+        let mut up = Vector2D::AXIS_Y * -1.0;
+        up.rotate_by(self.rotation);
+        let top_center = Point2D::new((corners[0].x + corners[1].x) * 0.5, (corners[0].y + corners[1].y) * 0.5);
+
+        vec![
+            corners[2],
+            Point2D::new(top_center.x + up.x * ROTATE_HANDLE_OFFSET / scale, top_center.y + up.y * ROTATE_HANDLE_OFFSET / scale),
+            self.center,
+            ]
+    }
+
+    fn trace_outline(&self, context: &CanvasRenderingContext2d){
+        let corners = self.corners();
+        context.begin_path();
+        context.move_to(corners[0].x, corners[0].y);
+        for point in corners.iter().skip(1){
+            context.line_to(point.x, point.y);
+        }
+        context.close_path();
+    }
+}
+
+impl Shape for SymbolInstance{
+    fn color(&self) -> &str {
+        self.overrides.get("stroke").map_or("#000000", |value| value.as_str())
+    }
+
+    fn line_width(&self) -> f64 {
+        self.overrides.get("stroke-width").and_then(|value| value.parse::<f64>().ok()).unwrap_or(2.0)
+    }
+
+    // 색상과 굵기는 마스터를 바꾸지 않고 인스턴스의 SVG 속성 덮어쓰기로 적용한다.
+    fn set_color(&mut self, color: &str) {
+        self.set_override("stroke", color);
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.set_override("stroke-width", &width.to_string());
+    }
+
+    fn max_point(&self) -> Point2D{
+        self.corners().iter().fold(Point2D::new(f64::MIN, f64::MIN), |acc, point|
+            Point2D::new(acc.x.max(point.x), acc.y.max(point.y))
+        )
+    }
+
+    fn min_point(&self) -> Point2D{
+        self.corners().iter().fold(Point2D::new(f64::MAX, f64::MAX), |acc, point|
+            Point2D::new(acc.x.min(point.x), acc.y.min(point.y))
+        )
+    }
+
+    fn is_hit(&self, x: f64, y: f64, scale: f64) -> bool {
+        let index = self.get_control_point(x, y, scale);
+        if index != -1{return true;}
+
+        let (width, height) = self.definition_size();
+        let mut local = Vector2D::new(x - self.center.x, y - self.center.y);
+        local.rotate_by(-self.rotation);
+        (local.x / self.scale_x).abs() <= width * 0.5 && (local.y / self.scale_y).abs() <= height * 0.5
+    }
+
+    fn get_control_point(&self, x: f64, y: f64, scale: f64) -> i32{
+        let control_pts = self.control_points(scale);
+        let adjusted_width = (10.0 / scale).powi(2);
+        control_pts.iter().position(|p| (x - p.x).powi(2) + (y - p.y).powi(2) < adjusted_width).map_or(-1, |i| i as i32)
+    }
+
+    fn get_selected_control_point(&self) -> i32 {
+        self.selected_control_point
+    }
+
+    fn set_selected_control_point(&mut self, index: i32) {
+        self.selected_control_point = index;
+    }
+
+    fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, selected: bool){
+        self.selected = selected;
+    }
+
+    fn set_hovered(&mut self, value: bool) {
+        self.hovered = value;
+    }
+
+    fn move_by(&mut self, dx: f64, dy: f64) {
+        self.center.x += dx;
+        self.center.y += dy;
+    }
+
+    fn move_control_point_by(&mut self, index: i32, dx: f64, dy: f64) {
+        // 회전은 중심에서 조절점으로 향하는 방향만 쓰므로 배율과 무관하다.
+        let control_pts = self.control_points(1.0);
+        let Some(pt) = control_pts.get(index as usize) else { return; };
+        let moved = Point2D::new(pt.x + dx, pt.y + dy);
+
+        if index == 0{
+            // 대각선 방향으로 균일하게 축척을 바꾼다.
+            let pt_dir = Vector2D::from_points(self.center, *pt);
+            let length = pt_dir.length();
+            if length > 0.0 {
+                let ratio = pt_dir.dot(Vector2D::from_points(self.center, moved)) / (length * length);
+                if ratio > 0.01 {
+                    self.scale_x *= ratio;
+                    self.scale_y *= ratio;
+                }
+            }
+        }
+        else if index == 1{
+            let pt_dir = Vector2D::from_points(self.center, *pt);
+            let moved_dir = Vector2D::from_points(self.center, moved);
+            self.rotation += pt_dir.angle_to(moved_dir);
+        }
+        else if index == 2{
+            self.move_by(dx, dy);
+        }
+    }
+
+    fn draw(&mut self, context: &CanvasRenderingContext2d, scale: f64){
+        let (width, height) = self.definition_size();
+
+        context.save();
+        self.appearance.apply(context);
+        context.translate(self.center.x, self.center.y).unwrap();
+        context.rotate(self.rotation).unwrap();
+        context.scale(self.scale_x, self.scale_y).unwrap();
+        context.translate(-width * 0.5, -height * 0.5).unwrap();
+
+        // 마스터 안의 인스턴스도 라이브러리를 잠그므로 정의를 복사하고 잠금을 푼 뒤 그린다.
+        let definition = SymbolLibrary::instance().lock().unwrap().get(&self.symbol_name).cloned();
+        match definition {
+            Some(definition) => definition.draw(context, scale * self.scale_x.abs().max(self.scale_y.abs()), &self.overrides),
+            None => {
+                // 정의를 찾을 수 없으면 자리 표시 상자를 그린다.
+                context.set_stroke_style(&JsValue::from_str("#999999"));
+                context.set_line_width(1.0 / scale);
+                context.stroke_rect(0.0, 0.0, width, height);
+                context.begin_path();
+                context.move_to(0.0, 0.0);
+                context.line_to(width, height);
+                context.move_to(width, 0.0);
+                context.line_to(0.0, height);
+                context.stroke();
+            }
+        }
+
+        context.restore();
+
+        if self.hovered{
+            context.save();
+            context.set_stroke_style(&JsValue::from_str(&Theme::current().hover_color));
+            context.set_line_width(1.0 / scale);
+            self.trace_outline(context);
+            context.stroke();
+            context.restore();
+        }
+
+        if self.selected{ self.draw_control_points(context, scale);}
+    }
+
+    fn draw_xor(&self, context: &CanvasRenderingContext2d, scale: f64){
+        context.save();
+
+        context.set_global_composite_operation("xor").unwrap();
+        context.set_stroke_style(&JsValue::from_str("#000000"));
+        context.set_line_width(1.0 / scale);
+        self.trace_outline(context);
+        context.stroke();
+
+        context.restore();
+    }
+
+    fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
+        let theme = Theme::current();
+
+        context.save();
+        theme.apply_selection_outline(context, scale);
+        self.trace_outline(context);
+        context.stroke();
+        context.restore();
+
+        let control_pts = self.control_points(scale);
+        for (index, point) in control_pts.iter().enumerate(){
+            if index == 2{
+                theme.draw_round_handle(context, *point, scale);
+            }
+            else{
+                theme.draw_handle(context, *point, scale);
+            }
+        }
+    }
+
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
+
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
+
+    fn apply_style(&mut self, _style: &ShapeStyle) {
+        // 인스턴스는 마스터 심볼의 스타일을 따른다.
+    }
+
+    fn to_svg(&self) -> String {
+        let (width, height) = self.definition_size();
+        let definition = SymbolLibrary::instance().lock().unwrap().get(&self.symbol_name).cloned();
+        let transform = format!("translate({} {}) rotate({}) scale({} {}) translate({} {})",
+            self.center.x, self.center.y, self.rotation.to_degrees(), self.scale_x, self.scale_y, -width * 0.5, -height * 0.5);
+
+        // 마스터의 요소마다 색상이 지정되어 있어 <use>의 속성은 상속되지 않으므로,
+        // 덮어쓴 속성이 있으면 인스턴스의 내용을 따로 만들어 내보낸다.
+        match definition {
+            Some(definition) if !self.overrides.is_empty() => {
+                let content = svg_with_overrides(&definition.to_svg_file(), &self.overrides);
+                format!(r#"<g transform="{}"><svg x="0" y="0" width="{}" height="{}" viewBox="0 0 {} {}">{}</svg></g>"#,
+                    transform, width, height, width, height, content)
+            }
+            definition => {
+                let symbol_id = definition.map_or(String::new(), |definition| definition.symbol_id());
+                format!(r##"<use href="#{}" width="{}" height="{}" transform="{}"/>"##, symbol_id, width, height, transform)
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
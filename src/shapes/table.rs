@@ -0,0 +1,636 @@
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+use wasm_bindgen::prelude::*;
+
+use web_sys::{CanvasRenderingContext2d};
+
+use super::geometry::{Point2D};
+use super::theme::Theme;
+use super::shape::{Shape, Fill, StrokeStyle, Appearance, ShapeStyle, escape_xml, svg_fill_attributes};
+use super::text_box::TextBox;
+
+const MIN_CELL_SIZE: f64 = 20.0;
+
+/// 표의 셀. 텍스트는 TextBoxManager로 편집할 수 있도록 TextBox로 보관한다.
+pub struct TableCell{
+    text: Arc<Mutex<Box<dyn Shape>>>,
+    fill: Option<String>,
+    border_color: String,
+    border_width: f64,
+}
+impl TableCell{
+    fn new(border_color: &str, border_width: f64) -> Self {
+        let mut text_box = TextBox::new(0.0, 0.0);
+        text_box.set_framed(false);
+        TableCell{
+            text: Arc::new(Mutex::new(Box::new(text_box))),
+            fill: None,
+            border_color: border_color.to_string(),
+            border_width,
+        }
+    }
+
+    fn text(&self) -> String{
+        let shape = self.text.lock().unwrap();
+        shape.as_any().downcast_ref::<TextBox>().map_or(String::new(), |tb| tb.text.clone())
+    }
+}
+
+/// 병합된 셀 영역 (왼쪽 위 셀이 내용을 가진다)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CellRange{
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+}
+impl CellRange{
+    fn contains(&self, row: usize, col: usize) -> bool{
+        row >= self.row && row < self.row + self.rows && col >= self.col && col < self.col + self.cols
+    }
+}
+
+/// 행과 열로 이루어진 표
+pub struct Table{
+    selected: bool,
+    hovered: bool,
+    color: String,
+    line_width: f64,
+    position: Point2D,  // 왼쪽 위
+    column_widths: Vec<f64>,
+    row_heights: Vec<f64>,
+    cells: Vec<Vec<TableCell>>,
+    merges: Vec<CellRange>,
+    cell_anchor: Option<(usize, usize)>,    // 셀 선택 시작 (row, col)
+    cell_focus: Option<(usize, usize)>,     // 셀 선택 끝 (row, col)
+    fill: Option<Fill>,         // 표 전체 배경 (셀 채우기는 그 위에 그린다)
+    stroke_style: StrokeStyle,  // 셀 테두리의 선 스타일
+    appearance: Appearance,
+    selected_control_point: i32,
+}
+impl Table{
+    pub const DEFAULT_ROWS: usize = 3;
+    pub const DEFAULT_COLUMNS: usize = 3;
+
+    pub fn new(color: String, line_width: f64, start: Point2D, width: f64, height: f64, rows: usize, cols: usize) -> Self {
+        let rows = rows.max(1);
+        let cols = cols.max(1);
+        let position = Point2D::new(start.x.min(start.x + width), start.y.min(start.y + height));
+        let column_width = (width.abs() / cols as f64).max(MIN_CELL_SIZE);
+        let row_height = (height.abs() / rows as f64).max(MIN_CELL_SIZE);
+
+        let cells = (0..rows).map(|_| (0..cols).map(|_| TableCell::new(&color, line_width)).collect()).collect();
+        let mut table = Table{
+            selected: false,
+            hovered: false,
+            color,
+            line_width,
+            position,
+            column_widths: vec![column_width; cols],
+            row_heights: vec![row_height; rows],
+            cells,
+            merges: Vec::new(),
+            cell_anchor: None,
+            cell_focus: None,
+            fill: None,
+            stroke_style: StrokeStyle::default(),
+            appearance: Appearance::default(),
+            selected_control_point: -1};
+        table.layout();
+        table
+    }
+
+    pub fn row_count(&self) -> usize{
+        self.row_heights.len()
+    }
+
+    pub fn column_count(&self) -> usize{
+        self.column_widths.len()
+    }
+
+    /// 모든 셀의 텍스트 박스 (병합으로 가려진 셀 포함)
+    pub fn cell_text_boxes(&self) -> Vec<Arc<Mutex<Box<dyn Shape>>>>{
+        self.cells.iter().flatten().map(|cell| Arc::clone(&cell.text)).collect()
+    }
+
+    /// (left, top, right, bottom)
+    fn table_bounds(&self) -> (f64, f64, f64, f64){
+        (self.position.x, self.position.y, self.position.x + self.width(), self.position.y + self.height())
+    }
+
+    fn width(&self) -> f64{
+        self.column_widths.iter().sum()
+    }
+
+    fn height(&self) -> f64{
+        self.row_heights.iter().sum()
+    }
+
+    fn column_x(&self, col: usize) -> f64{
+        self.position.x + self.column_widths.iter().take(col).sum::<f64>()
+    }
+
+    fn row_y(&self, row: usize) -> f64{
+        self.position.y + self.row_heights.iter().take(row).sum::<f64>()
+    }
+
+    fn merge_at(&self, row: usize, col: usize) -> Option<&CellRange>{
+        self.merges.iter().find(|merge| merge.contains(row, col))
+    }
+
+    /// 다른 셀에 병합되어 가려진 셀인지 확인한다.
+    fn is_covered(&self, row: usize, col: usize) -> bool{
+        self.merge_at(row, col).is_some_and(|merge| merge.row != row || merge.col != col)
+    }
+
+    /// 병합을 고려한 셀 영역 (x, y, width, height)
+    fn cell_rect(&self, row: usize, col: usize) -> (f64, f64, f64, f64){
+        let (rows, cols) = self.merge_at(row, col).map_or((1, 1), |merge| (merge.rows, merge.cols));
+        let width = self.column_widths.iter().skip(col).take(cols).sum();
+        let height = self.row_heights.iter().skip(row).take(rows).sum();
+        (self.column_x(col), self.row_y(row), width, height)
+    }
+
+    /// 주어진 좌표의 셀을 반환한다. 병합된 셀이면 왼쪽 위 셀을 반환한다.
+    pub fn cell_at(&self, x: f64, y: f64) -> Option<(usize, usize)>{
+        if x < self.position.x || y < self.position.y {return None;}
+
+        let col = (0..self.column_count()).find(|&c| x < self.column_x(c) + self.column_widths[c])?;
+        let row = (0..self.row_count()).find(|&r| y < self.row_y(r) + self.row_heights[r])?;
+        Some(self.merge_at(row, col).map_or((row, col), |merge| (merge.row, merge.col)))
+    }
+
+    /// 셀의 텍스트 박스를 반환한다. TextBoxManager에 연결하여 편집한다.
+    pub fn cell_text_box(&self, row: usize, col: usize) -> Option<Arc<Mutex<Box<dyn Shape>>>>{
+        self.cells.get(row).and_then(|cells| cells.get(col)).map(|cell| Arc::clone(&cell.text))
+    }
+
+    /// 셀을 선택한다. extend가 true이면 기존 선택 영역을 확장한다.
+    pub fn select_cell_at(&mut self, x: f64, y: f64, extend: bool){
+        match self.cell_at(x, y) {
+            Some(cell) => {
+                if !extend || self.cell_anchor.is_none(){
+                    self.cell_anchor = Some(cell);
+                }
+                self.cell_focus = Some(cell);
+            }
+            None => {
+                self.cell_anchor = None;
+                self.cell_focus = None;
+            }
+        }
+    }
+
+    /// 선택된 셀 영역 (병합된 셀을 모두 포함하도록 확장)
+    fn selected_range(&self) -> Option<CellRange>{
+        let (anchor, focus) = (self.cell_anchor?, self.cell_focus?);
+        let mut range = CellRange{
+            row: anchor.0.min(focus.0),
+            col: anchor.1.min(focus.1),
+            rows: anchor.0.abs_diff(focus.0) + 1,
+            cols: anchor.1.abs_diff(focus.1) + 1};
+
+        loop {
+            let expanded = self.merges.iter().fold(range, |acc, merge| {
+                let overlaps = merge.row < acc.row + acc.rows && acc.row < merge.row + merge.rows
+                    && merge.col < acc.col + acc.cols && acc.col < merge.col + merge.cols;
+                if !overlaps { return acc; }
+
+                let row = acc.row.min(merge.row);
+                let col = acc.col.min(merge.col);
+                CellRange{
+                    row,
+                    col,
+                    rows: (acc.row + acc.rows).max(merge.row + merge.rows) - row,
+                    cols: (acc.col + acc.cols).max(merge.col + merge.cols) - col}
+            });
+            if expanded == range { break; }
+            range = expanded;
+        }
+
+        Some(range)
+    }
+
+    fn clamp_cell_selection(&mut self){
+        let (rows, cols) = (self.row_count(), self.column_count());
+        let clamp = |cell: (usize, usize)| (cell.0.min(rows - 1), cell.1.min(cols - 1));
+        self.cell_anchor = self.cell_anchor.map(clamp);
+        self.cell_focus = self.cell_focus.map(clamp);
+    }
+
+    /// 선택된 셀 아래(선택이 없으면 맨 아래)에 행을 삽입한다.
+    pub fn insert_row(&mut self){
+        let index = self.selected_range().map_or(self.row_count(), |range| range.row + range.rows);
+        let height = self.row_heights.get(index.saturating_sub(1)).copied().unwrap_or(MIN_CELL_SIZE);
+        let cols = self.column_count();
+        let (color, line_width) = (self.color.clone(), self.line_width);
+
+        self.row_heights.insert(index, height);
+        self.cells.insert(index, (0..cols).map(|_| TableCell::new(&color, line_width)).collect());
+        for merge in self.merges.iter_mut(){
+            if merge.row >= index { merge.row += 1; }
+            else if merge.row + merge.rows > index { merge.rows += 1; }
+        }
+        self.layout();
+    }
+
+    /// 선택된 셀의 행을 삭제한다.
+    pub fn delete_row(&mut self){
+        let Some(range) = self.selected_range() else { return; };
+        if range.rows >= self.row_count() { return; }
+
+        for _ in 0..range.rows{
+            self.row_heights.remove(range.row);
+            self.cells.remove(range.row);
+        }
+        self.merges.retain_mut(|merge| {
+            let start = merge.row.max(range.row);
+            let end = (merge.row + merge.rows).min(range.row + range.rows);
+            if end > start { merge.rows -= end - start; }
+            if merge.row >= range.row + range.rows { merge.row -= range.rows; }
+            else if merge.row > range.row { merge.row = range.row; }
+            merge.rows > 0 && merge.rows * merge.cols > 1
+        });
+        self.clamp_cell_selection();
+        self.layout();
+    }
+
+    /// 선택된 셀 오른쪽(선택이 없으면 맨 오른쪽)에 열을 삽입한다.
+    pub fn insert_column(&mut self){
+        let index = self.selected_range().map_or(self.column_count(), |range| range.col + range.cols);
+        let width = self.column_widths.get(index.saturating_sub(1)).copied().unwrap_or(MIN_CELL_SIZE);
+        let (color, line_width) = (self.color.clone(), self.line_width);
+
+        self.column_widths.insert(index, width);
+        for row in self.cells.iter_mut(){
+            row.insert(index, TableCell::new(&color, line_width));
+        }
+        for merge in self.merges.iter_mut(){
+            if merge.col >= index { merge.col += 1; }
+            else if merge.col + merge.cols > index { merge.cols += 1; }
+        }
+        self.layout();
+    }
+
+    /// 선택된 셀의 열을 삭제한다.
+    pub fn delete_column(&mut self){
+        let Some(range) = self.selected_range() else { return; };
+        if range.cols >= self.column_count() { return; }
+
+        for _ in 0..range.cols{
+            self.column_widths.remove(range.col);
+            for row in self.cells.iter_mut(){
+                row.remove(range.col);
+            }
+        }
+        self.merges.retain_mut(|merge| {
+            let start = merge.col.max(range.col);
+            let end = (merge.col + merge.cols).min(range.col + range.cols);
+            if end > start { merge.cols -= end - start; }
+            if merge.col >= range.col + range.cols { merge.col -= range.cols; }
+            else if merge.col > range.col { merge.col = range.col; }
+            merge.cols > 0 && merge.rows * merge.cols > 1
+        });
+        self.clamp_cell_selection();
+        self.layout();
+    }
+
+    /// 선택된 셀을 하나로 병합한다. 각 셀의 텍스트는 줄을 바꿔 이어 붙인다.
+    pub fn merge_selected_cells(&mut self){
+        let Some(range) = self.selected_range() else { return; };
+        if range.rows * range.cols < 2 { return; }
+
+        let texts: Vec<String> = (range.row..range.row + range.rows)
+            .flat_map(|row| (range.col..range.col + range.cols).map(move |col| (row, col)))
+            .filter(|&(row, col)| !self.is_covered(row, col))
+            .map(|(row, col)| self.cells[row][col].text())
+            .filter(|text| !text.is_empty())
+            .collect();
+        for row in range.row..range.row + range.rows{
+            for col in range.col..range.col + range.cols{
+                if let Some(tb) = self.cells[row][col].text.lock().unwrap().as_any_mut().downcast_mut::<TextBox>(){
+                    tb.text = if row == range.row && col == range.col { texts.join("\n") } else { String::new() };
+                    tb.cursor_position = 0;
+                }
+            }
+        }
+
+        self.merges.retain(|merge| !(merge.row >= range.row && merge.row < range.row + range.rows && merge.col >= range.col && merge.col < range.col + range.cols));
+        self.merges.push(range);
+        self.cell_anchor = Some((range.row, range.col));
+        self.cell_focus = Some((range.row, range.col));
+        self.layout();
+    }
+
+    /// 선택 영역의 병합을 해제한다.
+    pub fn unmerge_selected_cells(&mut self){
+        let Some(range) = self.selected_range() else { return; };
+        self.merges.retain(|merge| !(merge.row >= range.row && merge.row < range.row + range.rows && merge.col >= range.col && merge.col < range.col + range.cols));
+        self.layout();
+    }
+
+    fn for_each_selected_cell(&mut self, f: impl Fn(&mut TableCell)){
+        let Some(range) = self.selected_range() else { return; };
+        for row in range.row..range.row + range.rows{
+            for col in range.col..range.col + range.cols{
+                f(&mut self.cells[row][col]);
+            }
+        }
+    }
+
+    /// 선택된 셀의 채우기 색을 설정한다. None이면 채우지 않는다.
+    pub fn set_selected_cells_fill(&mut self, fill: Option<String>){
+        self.for_each_selected_cell(|cell| cell.fill = fill.clone());
+        self.layout();
+    }
+
+    /// 선택된 셀의 테두리 색과 굵기를 설정한다.
+    pub fn set_selected_cells_border(&mut self, color: &str, width: f64){
+        self.for_each_selected_cell(|cell| {
+            cell.border_color = color.to_string();
+            cell.border_width = width;
+        });
+    }
+
+    /// 셀 텍스트에 맞게 행/열 크기를 늘리고 각 셀의 텍스트 박스 위치를 맞춘다.
+    fn layout(&mut self){
+        for row in 0..self.row_count(){
+            for col in 0..self.column_count(){
+                if self.merge_at(row, col).is_some(){ continue; }
+
+                let shape = self.cells[row][col].text.lock().unwrap();
+                if let Some(tb) = shape.as_any().downcast_ref::<TextBox>(){
+                    if !tb.text.is_empty(){
+                        let (width, height) = tb.get_size();
+                        self.column_widths[col] = self.column_widths[col].max(width);
+                        self.row_heights[row] = self.row_heights[row].max(height + 5.0);
+                    }
+                }
+            }
+        }
+
+        for row in 0..self.row_count(){
+            for col in 0..self.column_count(){
+                let (x, y, _, _) = self.cell_rect(row, col);
+                // 채우기가 없는 셀은 투명하게 두어 표 배경이 보이게 한다.
+                let background = self.cells[row][col].fill.clone().unwrap_or("transparent".to_string());
+                let mut shape = self.cells[row][col].text.lock().unwrap();
+                if let Some(tb) = shape.as_any_mut().downcast_mut::<TextBox>(){
+                    tb.position = Point2D::new(x, y);
+                    tb.set_background_color(&background);
+                }
+            }
+        }
+    }
+
+    /// 0..열 개수: 각 열의 오른쪽 경계, 열 개수..: 각 행의 아래쪽 경계
+    fn control_points(&self) -> Vec<Point2D>{
+        let column_handles = (0..self.column_count()).map(|c| Point2D::new(self.column_x(c) + self.column_widths[c], self.position.y));
+        let row_handles = (0..self.row_count()).map(|r| Point2D::new(self.position.x, self.row_y(r) + self.row_heights[r]));
+        column_handles.chain(row_handles).collect()
+    }
+}
+
+impl Shape for Table{
+    fn color(&self) -> &str {
+        &self.color
+    }
+
+    fn line_width(&self) -> f64 {
+        self.line_width
+    }
+
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+        self.cells.iter_mut().flatten().for_each(|cell| cell.border_color = color.to_string());
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.line_width = width;
+        self.cells.iter_mut().flatten().for_each(|cell| cell.border_width = width);
+    }
+
+    fn max_point(&self) -> Point2D{
+        Point2D::new(self.position.x + self.width(), self.position.y + self.height())
+    }
+
+    fn min_point(&self) -> Point2D{
+        self.position
+    }
+
+    fn is_hit(&self, x: f64, y: f64, scale: f64) -> bool {
+        let index = self.get_control_point(x, y, scale);
+        if index != -1{return true;}
+
+        let min_pt = self.min_point();
+        let max_pt = self.max_point();
+        x >= min_pt.x && x <= max_pt.x && y >= min_pt.y && y <= max_pt.y
+    }
+
+    fn get_control_point(&self, x: f64, y: f64, scale: f64) -> i32{
+        let control_pts = self.control_points();
+        let adjusted_width = (10.0 / scale).powi(2);
+        control_pts.iter().position(|p| (x - p.x).powi(2) + (y - p.y).powi(2) < adjusted_width).map_or(-1, |i| i as i32)
+    }
+
+    fn get_selected_control_point(&self) -> i32 {
+        self.selected_control_point
+    }
+
+    fn set_selected_control_point(&mut self, index: i32) {
+        self.selected_control_point = index;
+    }
+
+    fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    fn set_selected(&mut self, selected: bool){
+        self.selected = selected;
+        if !selected{
+            self.cell_anchor = None;
+            self.cell_focus = None;
+        }
+    }
+
+    fn set_hovered(&mut self, value: bool) {
+        self.hovered = value;
+    }
+
+    fn move_by(&mut self, dx: f64, dy: f64) {
+        self.position.x += dx;
+        self.position.y += dy;
+        self.layout();
+    }
+
+    fn move_control_point_by(&mut self, index: i32, dx: f64, dy: f64) {
+        if index < 0 { return; }
+
+        let index = index as usize;
+        let cols = self.column_count();
+        if index < cols{
+            self.column_widths[index] = (self.column_widths[index] + dx).max(MIN_CELL_SIZE);
+        }
+        else if index - cols < self.row_count(){
+            self.row_heights[index - cols] = (self.row_heights[index - cols] + dy).max(MIN_CELL_SIZE);
+        }
+        self.layout();
+    }
+
+    fn draw(&mut self, context: &CanvasRenderingContext2d, scale: f64){
+        self.layout();
+
+        context.save();
+        self.appearance.apply(context);
+
+        if let Some(fill) = &self.fill{
+            fill.apply(context, self.table_bounds());
+            context.fill_rect(self.position.x, self.position.y, self.width(), self.height());
+        }
+
+        for row in 0..self.row_count(){
+            for col in 0..self.column_count(){
+                if self.is_covered(row, col){ continue; }
+
+                let (x, y, width, height) = self.cell_rect(row, col);
+                let cell = &self.cells[row][col];
+                if let Some(fill) = &cell.fill{
+                    context.set_fill_style(&JsValue::from_str(fill));
+                    context.fill_rect(x, y, width, height);
+                }
+
+                // 텍스트 박스가 셀 밖으로 나가지 않도록 자른다.
+                context.save();
+                context.begin_path();
+                context.rect(x, y, width, height);
+                context.clip();
+                cell.text.lock().unwrap().draw(context, scale);
+                context.restore();
+
+                if cell.border_width > 0.0{
+                    if self.hovered{
+                        context.set_stroke_style(&JsValue::from_str(&Theme::current().hover_color));
+                    }
+                    else{
+                        context.set_stroke_style(&JsValue::from_str(&cell.border_color));
+                    }
+                    context.set_line_width(cell.border_width / scale);
+                    context.save();
+                    self.stroke_style.apply(context, cell.border_width / scale);
+                    context.stroke_rect(x, y, width, height);
+                    context.restore();
+                }
+            }
+        }
+
+        // 선택된 셀 강조
+        if let Some(range) = self.selected_range(){
+            let (x, y, _, _) = self.cell_rect(range.row, range.col);
+            let width: f64 = self.column_widths.iter().skip(range.col).take(range.cols).sum();
+            let height: f64 = self.row_heights.iter().skip(range.row).take(range.rows).sum();
+            // 테마의 선택 색상을 옅게 칠한다.
+            context.set_global_alpha(context.global_alpha() * 0.2);
+            context.set_fill_style(&JsValue::from_str(&Theme::current().selection_color));
+            context.fill_rect(x, y, width, height);
+        }
+
+        context.restore();
+
+        if self.selected{ self.draw_control_points(context, scale);}
+    }
+
+    fn draw_xor(&self, context: &CanvasRenderingContext2d, scale: f64){
+        context.save();
+
+        context.set_global_composite_operation("xor").unwrap();
+        context.set_stroke_style(&JsValue::from_str(&self.color));
+        context.set_line_width(self.line_width / scale);
+
+        context.begin_path();
+        for col in 0..=self.column_count(){
+            let x = self.column_x(col);
+            context.move_to(x, self.position.y);
+            context.line_to(x, self.position.y + self.height());
+        }
+        for row in 0..=self.row_count(){
+            let y = self.row_y(row);
+            context.move_to(self.position.x, y);
+            context.line_to(self.position.x + self.width(), y);
+        }
+        context.stroke();
+
+        context.restore();
+    }
+
+    fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
+        let theme = Theme::current();
+
+        context.save();
+        theme.apply_selection_outline(context, scale);
+        context.begin_path();
+        context.rect(self.position.x, self.position.y, self.width(), self.height());
+        context.stroke();
+        context.restore();
+
+        theme.draw_handles(context, &self.control_points(), scale);
+    }
+
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
+
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
+
+    fn set_fill(&mut self, fill: Option<Fill>) {
+        self.fill = fill;
+    }
+
+    fn set_stroke_style(&mut self, style: StrokeStyle) {
+        self.stroke_style = style;
+    }
+
+    fn style(&self) -> ShapeStyle {
+        ShapeStyle{ color: self.color.clone(), line_width: self.line_width, fill: self.fill.clone(), stroke_style: self.stroke_style.clone(), font: None }
+    }
+
+    fn apply_style(&mut self, style: &ShapeStyle) {
+        self.set_color(&style.color);
+        self.set_line_width(style.line_width);
+        self.fill = style.fill.clone();
+        self.stroke_style = style.stroke_style.clone();
+    }
+
+    fn to_svg(&self) -> String {
+        let (defs, background) = svg_fill_attributes(self.fill.as_ref(), self.table_bounds());
+        let mut elements = match self.fill {
+            Some(_) => format!(r#"{}<rect x="{}" y="{}" width="{}" height="{}" {}/>"#, defs, self.position.x, self.position.y, self.width(), self.height(), background),
+            None => String::new(),
+        };
+        for row in 0..self.row_count(){
+            for col in 0..self.column_count(){
+                if self.is_covered(row, col){ continue; }
+
+                let (x, y, width, height) = self.cell_rect(row, col);
+                let cell = &self.cells[row][col];
+                let fill = cell.fill.as_deref().map_or("none".to_string(), escape_xml);
+                elements += &format!(r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="{}" stroke-width="{}"{}/>"#,
+                    x, y, width, height, fill, escape_xml(&cell.border_color), cell.border_width, self.stroke_style.svg_attributes(cell.border_width));
+                if !cell.text().is_empty(){
+                    elements += &cell.text.lock().unwrap().to_svg();
+                }
+            }
+        }
+
+        format!("<g>{}</g>", elements)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -15,36 +15,55 @@ use wasm_bindgen::JsCast;
 
 use web_sys::console::group;
 use web_sys::console::info;
-use web_sys::{window, Document, CanvasRenderingContext2d, MouseEvent, CompositionEvent, InputEvent, KeyboardEvent};
+use web_sys::{window, Document, CanvasRenderingContext2d, MouseEvent, CompositionEvent, InputEvent, KeyboardEvent, HtmlElement};
 
 use std::sync::{Arc, Mutex};
 use once_cell::sync::Lazy;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_bidi::{BidiInfo, Level};
 use std::thread;
 
 use super::geometry::Vector2D;
 use super::geometry::{Point2D};
+use super::theme::Theme;
+use crate::vec_draw_doc::VecDrawDoc;
+use super::text_run::{RunStyle, TextRuns};
+use super::text_path::{TextPath, polyline_length, point_at_distance, project_to_polyline, svg_path_data, next_text_path_id};
 use super::line;
-use super::shape::{Shape};
+use super::shape::{Shape, Appearance, Effects, ShapeStyle, StrokeStyle, TextFont, Property, PropertyValue, escape_xml};
 
 pub struct TextBoxManager {
     document: Document,
     context: CanvasRenderingContext2d,
     attached: Option<Arc<Mutex<Box<dyn Shape>>>>,
+    table: Option<Arc<Mutex<Box<dyn Shape>>>>,  // 표 셀을 편집 중이면 그 셀을 가진 표
     active_index: Option<usize>,
     cursor_visible: bool,
     is_composing: bool,
     composition_text: String,
+    drag_selecting: bool,           // 마우스로 글자를 선택하는 중인지
+    last_click: (f64, Point2D, u32),// 마지막 클릭의 시각(ms), 위치, 연속 클릭 횟수 (더블/트리플 클릭 판별)
+    return_tool: Option<String>,    // 편집을 마치면 돌아갈 도구 버튼의 id
 }
+
+/// 연속 클릭으로 보는 최대 간격(ms)과 거리
+const MULTI_CLICK_INTERVAL: f64 = 400.0;
+const MULTI_CLICK_DISTANCE: f64 = 4.0;
+
 impl TextBoxManager {
     pub fn new(document: Document, context: CanvasRenderingContext2d) -> Self {
         Self {
             document,
             context,
             attached: None,
+            table: None,
             active_index: None,
             cursor_visible: true,
             is_composing: false,
             composition_text: String::new(),
+            drag_selecting: false,
+            last_click: (0.0, Point2D::new(0.0, 0.0), 0),
+            return_tool: None,
         }
     }
 
@@ -61,8 +80,15 @@ impl TextBoxManager {
         self.redraw();
     }
 
+    /// 표 셀의 텍스트 박스를 연결한다. 입력할 때마다 표의 행/열 크기를 다시 맞춘다.
+    pub fn attach_table_cell(&mut self, table: Arc<Mutex<Box<dyn Shape>>>, cell_text: Arc<Mutex<Box<dyn Shape>>>) {
+        self.table = Some(table);
+        self.attach(cell_text);
+    }
+
     pub fn detach(&mut self) {
         self.attached = None;
+        self.table = None;
     }
 
     pub fn is_active(&self) -> bool {
@@ -91,17 +117,177 @@ impl TextBoxManager {
         self.redraw();
     }
 
+    /// (x, y)가 편집 중인 텍스트 박스 안인지
+    pub fn hit_attached(&self, x: f64, y: f64) -> bool {
+        self.attached.as_ref().is_some_and(|attached| {
+            let shape = attached.lock().unwrap();
+            shape.as_any().downcast_ref::<TextBox>().is_some_and(|tb| tb.contains_point(x, y))
+        })
+    }
+
+    /// 편집 중인 텍스트 박스 안을 눌렀을 때: 캐럿을 옮긴다. (Shift: 선택 범위 확장, 더블 클릭: 단어, 트리플 클릭: 줄 선택)
+    pub fn on_pointer_down(&mut self, x: f64, y: f64, extend: bool) {
+        let now = js_sys::Date::now();
+        let (last_time, last_point, last_count) = self.last_click;
+        let count = if now - last_time < MULTI_CLICK_INTERVAL && Vector2D::from_points(last_point, Point2D::new(x, y)).length() < MULTI_CLICK_DISTANCE { last_count % 3 + 1 } else { 1 };
+        self.last_click = (now, Point2D::new(x, y), count);
+
+        if let Some(attached) = &self.attached {
+            let mut shape = attached.lock().unwrap();
+            if let Some(tb) = shape.as_any_mut().downcast_mut::<TextBox>() {
+                let index = tb.index_at_point(x, y);
+                match count {
+                    1 => tb.set_cursor(index, extend),
+                    2 => tb.select_word_at(index),
+                    _ => tb.select_line_at(index),
+                }
+            }
+            drop(shape);
+
+            self.drag_selecting = count == 1;
+            self.cursor_visible = true;
+            self.focus_hidden_input();
+            self.redraw();
+        }
+    }
+
+    /// 누른 채로 움직이면 선택 범위를 넓힌다.
+    pub fn on_pointer_drag(&mut self, x: f64, y: f64) {
+        if let Some(attached) = &self.attached {
+            let mut shape = attached.lock().unwrap();
+            if let Some(tb) = shape.as_any_mut().downcast_mut::<TextBox>() {
+                let index = tb.index_at_point(x, y);
+                tb.set_cursor(index, true);
+            }
+            drop(shape);
+            self.redraw();
+        }
+    }
+
+    pub fn end_drag(&mut self) {
+        self.drag_selecting = false;
+    }
+
+    pub fn is_drag_selecting(&self) -> bool {
+        self.drag_selecting
+    }
+
+    /// 선택된 글자를 복사한다. cut이면 선택된 글자를 지운다. (hidden-input의 copy/cut 이벤트에서 호출)
+    pub fn copy_selection(&mut self, cut: bool) -> Option<String> {
+        let attached = self.attached.as_ref()?;
+        let mut shape = attached.lock().unwrap();
+        let tb = shape.as_any_mut().downcast_mut::<TextBox>()?;
+        let text = tb.selected_text()?;
+        if cut {
+            tb.delete_selection();
+            tb.relayout(&self.context);
+        }
+        drop(shape);
+
+        if cut {
+            self.redraw();
+        }
+        Some(text)
+    }
+
+    /// 붙여 넣은 텍스트를 커서 위치에 넣는다. 선택된 글자는 바꾼다. (hidden-input의 paste 이벤트에서 호출)
+    pub fn paste(&mut self, text: &str) {
+        if self.is_composing || !self.is_active() {
+            return;
+        }
+
+        if let Some(attached) = &self.attached {
+            let mut shape = attached.lock().unwrap();
+            if let Some(tb) = shape.as_any_mut().downcast_mut::<TextBox>() {
+                // 줄바꿈은 '\n'으로 통일한다. 줄 수가 바뀌면 relayout이 높이를 다시 계산한다.
+                let text = text.replace("\r\n", "\n").replace('\r', "\n");
+                tb.insert_at_cursor(&text);
+                tb.relayout(&self.context);
+            }
+            drop(shape);
+
+            self.clear_hidden_input();
+            self.redraw();
+        }
+    }
+
+    /// 편집 중인 텍스트 박스의 글꼴, 정렬 등을 바꾼다. (툴바에서 호출)
+    pub fn update_attached(&mut self, f: impl Fn(&mut TextBox)) {
+        if let Some(attached) = &self.attached {
+            let mut shape = attached.lock().unwrap();
+            if let Some(tb) = shape.as_any_mut().downcast_mut::<TextBox>() {
+                f(tb);
+                tb.relayout(&self.context);
+            }
+            drop(shape);
+
+            // 툴바를 누르면서 잃은 포커스를 되돌린다.
+            self.focus_hidden_input();
+            self.redraw();
+        }
+    }
+
+    /// 편집을 마친 뒤 돌아갈 도구를 정한다. (선택 모드에서 더블 클릭으로 편집을 시작한 경우)
+    pub fn set_return_tool(&mut self, button_id: Option<&str>) {
+        self.return_tool = button_id.map(str::to_string);
+    }
+
+    /// 편집을 시작할 때 클릭한 위치에 캐럿을 놓는다. (끌어서 선택하지 않는다)
+    pub fn place_caret_at(&mut self, x: f64, y: f64) {
+        if let Some(attached) = &self.attached {
+            let mut shape = attached.lock().unwrap();
+            if let Some(tb) = shape.as_any_mut().downcast_mut::<TextBox>() {
+                let index = tb.index_at_point(x, y);
+                tb.set_cursor(index, false);
+            }
+            drop(shape);
+            self.redraw();
+        }
+    }
+
     pub fn finish_input(&mut self) {
+        // 선택 범위를 지우고, 글자가 없는 텍스트 박스는 문서에서 지운다.
+        if let Some(attached) = self.attached.clone() {
+            let empty = {
+                let mut shape = attached.lock().unwrap();
+                shape.as_any_mut().downcast_mut::<TextBox>().is_some_and(|tb| {
+                    tb.begin_navigation(false);
+                    tb.text.is_empty()
+                })
+            };
+            if empty {
+                remove_from_document(&attached);
+            }
+        }
+
         // 입력 완료 및 비활성화
         self.detach();
+        self.drag_selecting = false;
         self.clear_hidden_input();
         self.redraw();
+
+        // 다른 도구에서 편집을 시작했으면 그 도구로 돌아간다.
+        if let Some(button_id) = self.return_tool.take() {
+            if let Some(button) = self.document.get_element_by_id(&button_id).and_then(|element| element.dyn_into::<HtmlElement>().ok()) {
+                button.click();
+            }
+        }
     }
 
     /// 글자 조합 시작
     pub fn on_composition_start(&mut self) {
         self.is_composing = true;
         self.composition_text.clear();
+
+        // 조합을 시작하면 선택된 글자를 지운다.
+        if let Some(attached) = &self.attached {
+            let mut shape = attached.lock().unwrap();
+            if let Some(tb) = shape.as_any_mut().downcast_mut::<TextBox>() {
+                if tb.delete_selection() {
+                    tb.relayout(&self.context);
+                }
+            }
+        }
     }
 
     /// 글자 조합 중인 상태
@@ -137,12 +323,8 @@ impl TextBoxManager {
                     tb.insert_at_cursor(&data);
                     info!("on_composition_end: {}", tb.text);
 
-                    // 텍스트의 너비 계산 및 업데이트
-                    let text_width = {
-                        let text_clone = tb.text.clone();
-                        get_max_line_width(&self.context, &text_clone)
-                    };
-                    tb.update_width(text_width);
+                    // 텍스트의 줄바꿈과 크기 업데이트
+                    tb.relayout(&self.context);
                     tb.composition_text.clear();
                 }
                 drop(shape); // Release the mutable borrow before calling redraw
@@ -166,12 +348,8 @@ impl TextBoxManager {
                 info!("on_input: {}", value);
                 tb.insert_at_cursor(&value);
 
-                // 텍스트의 너비 계산 및 업데이트
-                let text_width = {
-                    let text_clone = tb.text.clone();
-                    get_max_line_width(&self.context, &text_clone)
-                };
-                tb.update_width(text_width);
+                // 텍스트의 줄바꿈과 크기 업데이트
+                tb.relayout(&self.context);
                 drop(tb); // Release the mutable borrow before calling redraw
 
                 self.clear_hidden_input();
@@ -191,49 +369,101 @@ impl TextBoxManager {
         if let Some(attached) = &self.attached {
             let mut shape = attached.lock().unwrap();
             if let Some(tb) = shape.as_any_mut().downcast_mut::<TextBox>() {
-                let text_clone = tb.text.clone();
-                match event.key().as_str() {
+                // Ctrl+B / Ctrl+I / Ctrl+U: 선택 범위의 굵게, 기울임, 밑줄을 켜고 끈다.
+                // Ctrl+A: 박스 안의 글자를 모두 선택한다. (도형 전체 선택으로 넘기지 않는다)
+                // Ctrl+C/X/V는 막지 않고 hidden-input의 copy/cut/paste 이벤트에서 처리한다.
+                if (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("a") {
+                    event.prevent_default();
+                    tb.select_all();
+                    drop(shape);
+                    self.redraw();
+                    return;
+                }
+
+                if (event.ctrl_key() || event.meta_key()) && matches!(event.key().to_lowercase().as_str(), "b" | "i" | "u") {
+                    match event.key().to_lowercase().as_str() {
+                        "b" => tb.toggle_run_style(|style| style.bold, |style, on| style.bold = on),
+                        "i" => tb.toggle_run_style(|style| style.italic, |style, on| style.italic = on),
+                        _ => tb.toggle_run_style(|style| style.underline, |style, on| style.underline = on),
+                    }
+                    event.prevent_default();
+                    tb.relayout(&self.context);
+                    drop(shape);
+                    self.redraw();
+                    return;
+                }
+
+                // 세로쓰기: 위/아래는 앞/뒤 글자, 왼쪽/오른쪽은 다음/이전 줄(열)로 간다.
+                let vertical = tb.direction() == TextDirection::Vertical;
+                let key = match (vertical, event.key().as_str()) {
+                    (true, "ArrowUp") => "ArrowLeft".to_string(),
+                    (true, "ArrowDown") => "ArrowRight".to_string(),
+                    (true, "ArrowLeft") => "ArrowDown".to_string(),
+                    (true, "ArrowRight") => "ArrowUp".to_string(),
+                    (_, key) => key.to_string(),
+                };
+                // 오른쪽에서 왼쪽 문단에서는 왼쪽이 글의 뒤쪽이다.
+                let backward = (key == "ArrowLeft") != (tb.direction() == TextDirection::Rtl);
+
+                match key.as_str() {
                     "Backspace" => {
-                        tb.delete_before_cursor();
-                        let text_width = get_max_line_width(&self.context, &text_clone);
-                        tb.update_width(text_width);
-                        let height = tb.get_height(&tb.text);
-                        tb.update_height(height);
+                        if !tb.delete_selection() {
+                            if event.ctrl_key() { tb.delete_word_before_cursor(); } else { tb.delete_before_cursor(); }
+                        }
+                        tb.relayout(&self.context);
                     }
                     "Delete" => {
-                        tb.delete_at_cursor();
-                        let text_width = get_max_line_width(&self.context, &text_clone);
-                        tb.update_width(text_width);
-                        let height = tb.get_height(&tb.text);
-                        tb.update_height(height);
+                        if !tb.delete_selection() {
+                            if event.ctrl_key() { tb.delete_word_at_cursor(); } else { tb.delete_at_cursor(); }
+                        }
+                        tb.relayout(&self.context);
                     }
                     "Enter" => {
                         tb.insert_at_cursor("\n");
                         info!("Enter: {},{}", tb.text, tb.cursor_position);
 
                         // ✅ TextBox 높이 증가 (줄 개수에 맞게)
-                        let height = tb.get_height(&tb.text);
-                        tb.update_height(height);
-                        let max_line_width = get_max_line_width(&self.context, &tb.text);
-                        tb.update_width(max_line_width);
+                        tb.relayout(&self.context);
+                    }
+                    "ArrowLeft" | "ArrowRight" if !event.shift_key() && tb.selection_range().is_some() => {
+                        // 선택 범위가 있으면 선택을 해제하고 범위의 앞/뒤로 간다.
+                        let (start, end) = tb.selection_range().unwrap();
+                        tb.set_cursor(if backward { start } else { end }, false);
                     }
-                    "ArrowLeft" => {
-                        tb.move_cursor_left();
+                    "ArrowLeft" | "ArrowRight" if event.ctrl_key() => {
+                        tb.begin_navigation(event.shift_key());
+                        if backward { tb.move_cursor_word_left(); } else { tb.move_cursor_word_right(); }
                     }
-                    "ArrowRight" => {
-                        tb.move_cursor_right();
+                    "ArrowLeft" | "ArrowRight" if vertical => {
+                        tb.begin_navigation(event.shift_key());
+                        if key == "ArrowLeft" { tb.move_cursor_left(); } else { tb.move_cursor_right(); }
+                    }
+                    "ArrowLeft" | "ArrowRight" => {
+                        // 양방향 텍스트에서도 화면에서 누른 방향으로 움직인다.
+                        tb.begin_navigation(event.shift_key());
+                        tb.move_cursor_visually(key == "ArrowRight");
                     }
                     "ArrowUp" =>{
-                        tb.move_cursor_up();
+                        tb.begin_navigation(event.shift_key());
+                        tb.move_cursor_up(&self.context);
                     }
                     "ArrowDown" =>{
-                        tb.move_cursor_down();
+                        tb.begin_navigation(event.shift_key());
+                        tb.move_cursor_down(&self.context);
+                    }
+                    "Home" if event.ctrl_key() => {
+                        tb.set_cursor(0, event.shift_key());
+                    }
+                    "End" if event.ctrl_key() => {
+                        tb.set_cursor(tb.text.chars().count(), event.shift_key());
                     }
                     "Home" =>{
-                        tb.move_cursor_to_line_start();
+                        tb.begin_navigation(event.shift_key());
+                        tb.move_cursor_to_line_start(&self.context);
                     }
                     "End" =>{
-                        tb.move_cursor_to_line_end();
+                        tb.begin_navigation(event.shift_key());
+                        tb.move_cursor_to_line_end(&self.context);
                     }
                     "Escape" => {
                         drop(tb); // Release the mutable borrow before calling finish_input
@@ -278,41 +508,59 @@ impl TextBoxManager {
             return;
         }
         //self.context.clear_rect(0.0, 0.0, 800.0, 600.0);
-        self.context.set_font("20px sans-serif");
+
+        // 표 셀이면 표를 먼저 그린다. (그리면서 셀 크기에 맞게 행/열을 다시 배치한다)
+        if let Some(table) = &self.table {
+            table.lock().unwrap().draw(&self.context, 1.0);
+        }
 
         if let Some(attached) = &self.attached {
             let mut shape = attached.lock().unwrap();
             if let Some(tb) = shape.as_any_mut().downcast_mut::<TextBox>() {
 
-                let byte_index = tb.get_byte_index_at_cursor();
-                let text_before_cursor = &tb.text[..byte_index];
-                let text_after_cursor = &tb.text[byte_index..];
-                let text_to_draw = format!("{}{}{}", text_before_cursor, tb.composition_text, text_after_cursor);
-                info!("redraw: {}", text_to_draw);
-                tb.update_width(get_max_line_width(&self.context, &text_to_draw));
-
+                tb.erase_path_area(&self.context);
                 tb.draw(&self.context, 1.0);
 
-                // 커서 및 조합 중인 글자 강조 표시
-                let cursor_x = get_text_width(&self.context, &text_to_draw[..tb.get_byte_index_at_cursor()]) + tb.position.x + 5.0;
-                let cursor_y = tb.position.y + 5.0 + (text_to_draw[..tb.get_byte_index_at_cursor()].matches('\n').count() as f64) * (tb.get_font_size() + tb.get_line_gap());
+                // 커서 및 조합 중인 글자 강조 표시 (줄 배치는 draw에서 계산한 것을 사용한다)
+                let theme = Theme::current();
+                // 선택 범위 강조 (조합 중인 글자와 같은 색)
+                if let Some((start, end)) = tb.selection_range() {
+                    self.context.set_fill_style(&JsValue::from_str(&theme.composition_color));
+                    tb.fill_glyph_spans(&self.context, start, end);
+                }
+
                 if self.is_composing && !tb.composition_text.is_empty() {
-                    // 조합 중인 글자에 파란색 박스 표시
-                    let cursor_width = get_text_width(&self.context, &tb.composition_text);
-                    let cursor_height = tb.get_font_size() + tb.get_line_gap();
-                    self.context.set_fill_style(&"rgba(0, 0, 255, 0.3)".into()); // ✅ 반투명한 파란색 (alpha = 0.3)
-                    self.context.fill_rect(cursor_x, cursor_y, cursor_width, cursor_height);
+                    // 조합 중인 글자마다 강조 박스 표시 (줄이 바뀔 수 있다)
+                    let composition_len = tb.composition_text.chars().count();
+                    self.context.set_fill_style(&JsValue::from_str(&theme.composition_color)); // ✅ 반투명한 강조색
+                    tb.fill_glyph_spans(&self.context, tb.cursor_position, tb.cursor_position + composition_len);
                 }else if self.cursor_visible {
-                    self.context.set_fill_style(&"blue".into());
-                    self.context.fill_rect(cursor_x, cursor_y, 2.0, tb.get_font_size() + tb.get_line_gap());
+                    self.context.set_fill_style(&JsValue::from_str(&theme.caret_color));
+                    tb.fill_caret(&self.context, tb.cursor_position);
                 }
 
-                self.context.set_stroke_style(&"blue".into());
+                self.context.set_stroke_style(&JsValue::from_str(&theme.caret_color));
             }
         }
     }
 }
 
+/// 문서에서 도형을 지운다. 문서에 없는 도형(표의 셀 텍스트)은 그대로 둔다.
+fn remove_from_document(target: &Arc<Mutex<Box<dyn Shape>>>) {
+    let instance = VecDrawDoc::instance();
+    let mut doc = instance.lock().unwrap();
+    if !(0..doc.count()).any(|i| doc.nth(i).is_some_and(|shape| Arc::ptr_eq(&shape, target))) {
+        return;
+    }
+
+    // 선택된 도형만 지울 수 있으므로 잠시 이 도형만 선택한다.
+    let selected = doc.get_selected_shapes();
+    selected.iter().for_each(|shape| shape.lock().unwrap().set_selected(false));
+    target.lock().unwrap().set_selected(true);
+    doc.delete_selected();
+    selected.iter().filter(|shape| !Arc::ptr_eq(shape, target)).for_each(|shape| shape.lock().unwrap().set_selected(true));
+}
+
 /// 주어진 text를 줄 단위로 분할하여 반환한다.
 /// '\n'으로 끝나는 경우 마지막 줄을 추가한다.
 fn split_lines<'a>(text: &'a str) -> Vec<&'a str> {
@@ -327,31 +575,375 @@ fn split_lines<'a>(text: &'a str) -> Vec<&'a str> {
     lines
 }
 
-fn get_max_line_width(context: &CanvasRenderingContext2d, text: &str) -> f64 {
-    text.lines()
-        .map(|line| get_text_width(context,line))
-        .max_by(|a, b| a.partial_cmp(b).unwrap())
-        .unwrap_or(50.0) // 최소 너비 50px
-}
-
 /// 문자의 높이를 반환한다.
 fn get_char_height(context: &CanvasRenderingContext2d, text: &str) -> f64 {
     let metrics = context.measure_text(text).unwrap();
     metrics.actual_bounding_box_ascent() + metrics.actual_bounding_box_descent() // ✅ 글자 높이 반환
 }
 
-/// 텍스트 마지막 줄의 길이를 반환한다.
-fn get_text_width(context: &CanvasRenderingContext2d, text: &str) -> f64 {
-    // 마지막 줄이 개행 문자로 끝나면 길이 0
-    if text.ends_with('\n'){
-        return 0.0;
+/// 텍스트 박스 안쪽 여백
+const PADDING: f64 = 5.0;
+
+/// 너비 모드
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextWrap{
+    Auto,   // 가장 긴 줄에 맞춰 너비가 늘어난다. 줄바꿈은 개행 문자에서만 한다.
+    Fixed,  // 너비가 고정되고 단어 경계에서 자동으로 줄을 바꾼다.
+}
+impl TextWrap{
+    pub fn from_name(name: &str) -> Option<TextWrap>{
+        match name {
+            "auto" => Some(TextWrap::Auto),
+            "fixed" => Some(TextWrap::Fixed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str{
+        match self {
+            TextWrap::Auto => "auto",
+            TextWrap::Fixed => "fixed",
+        }
+    }
+}
+
+/// 가로 정렬
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextAlign{
+    Left,
+    Center,
+    Right,
+    Justify,    // 자동 줄바꿈된 줄을 박스 너비에 맞춘다. (문단의 마지막 줄은 왼쪽 정렬)
+}
+impl TextAlign{
+    pub fn from_name(name: &str) -> Option<TextAlign>{
+        match name {
+            "left" => Some(TextAlign::Left),
+            "center" => Some(TextAlign::Center),
+            "right" => Some(TextAlign::Right),
+            "justify" => Some(TextAlign::Justify),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str{
+        match self {
+            TextAlign::Left => "left",
+            TextAlign::Center => "center",
+            TextAlign::Right => "right",
+            TextAlign::Justify => "justify",
+        }
+    }
+}
+
+/// 세로 정렬 (박스가 텍스트보다 높을 때)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerticalAlign{
+    Top,
+    Middle,
+    Bottom,
+}
+impl VerticalAlign{
+    pub fn from_name(name: &str) -> Option<VerticalAlign>{
+        match name {
+            "top" => Some(VerticalAlign::Top),
+            "middle" => Some(VerticalAlign::Middle),
+            "bottom" => Some(VerticalAlign::Bottom),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str{
+        match self {
+            VerticalAlign::Top => "top",
+            VerticalAlign::Middle => "middle",
+            VerticalAlign::Bottom => "bottom",
+        }
+    }
+}
+
+/// 글쓰기 방향
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextDirection{
+    Ltr,        // 왼쪽에서 오른쪽 (아랍어, 히브리어 글자는 그 부분만 오른쪽에서 왼쪽으로 쓴다)
+    Rtl,        // 오른쪽에서 왼쪽 (문단이 오른쪽에서 시작한다)
+    Vertical,   // 세로쓰기. 글자는 위에서 아래로, 줄은 오른쪽에서 왼쪽으로 쌓인다.
+}
+impl TextDirection{
+    pub fn from_name(name: &str) -> Option<TextDirection>{
+        match name {
+            "ltr" => Some(TextDirection::Ltr),
+            "rtl" => Some(TextDirection::Rtl),
+            "vertical" => Some(TextDirection::Vertical),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str{
+        match self {
+            TextDirection::Ltr => "ltr",
+            TextDirection::Rtl => "rtl",
+            TextDirection::Vertical => "vertical",
+        }
+    }
+}
+
+/// 화면에 표시되는 한 줄 (자동 줄바꿈 결과). 인덱스는 표시 텍스트(조합 중인 글자 포함)의 글자 단위이다.
+/// 세로쓰기에서는 x 대신 y(박스 위쪽 기준)가 글자 진행 방향의 좌표이고, top은 박스 오른쪽에서 줄(열)까지의 거리이다.
+#[derive(Debug, Clone)]
+pub struct VisualLine{
+    pub start: usize,
+    pub end: usize,             // 개행 문자는 포함하지 않는다. 자동 줄바꿈된 줄은 줄 끝 공백을 포함한다.
+    pub trimmed_end: usize,     // 줄 끝 공백을 뺀 끝
+    pub hard_break: bool,       // 개행 문자나 텍스트 끝에서 끝난 줄
+    pub text: String,
+    pub width: f64,             // 줄 끝 공백을 뺀 너비
+    pub offsets: Vec<f64>,      // 글자 경계마다의 캐럿 x 좌표 (박스 왼쪽 기준, 정렬 반영). 길이 = end - start + 1
+    pub glyphs: Vec<(f64, f64)>,// 글자마다의 (왼쪽 x, 오른쪽 x). 양방향 텍스트는 화면 순서로 놓인다. 길이 = end - start
+    pub levels: Vec<u8>,        // 글자마다의 양방향 수준 (홀수면 오른쪽에서 왼쪽으로 쓴다)
+    pub top: f64,               // 줄 위쪽의 y 좌표 (박스 위쪽 기준)
+    pub size: f64,              // 줄에서 가장 큰 글자 크기 (줄 위쪽에서 기준선까지)
+    pub justified: bool,        // 양쪽 정렬로 글자 간격이 늘어난 줄 (글자를 하나씩 그린다)
+}
+impl VisualLine{
+    /// [start, end) 글자가 화면에서 차지하는 (왼쪽, 오른쪽). 빈 범위는 start의 캐럿 위치
+    fn span(&self, start: usize, end: usize) -> (f64, f64) {
+        if start >= end {
+            let x = self.offsets[start.clamp(self.start, self.end) - self.start];
+            return (x, x);
+        }
+        self.glyphs[start - self.start..end - self.start].iter()
+            .fold((f64::MAX, f64::MIN), |(left, right), glyph| (left.min(glyph.0), right.max(glyph.1)))
+    }
+
+    /// [start, end)를 양방향 수준이 같은 조각으로 나눈다. (조각마다 한 방향으로 그린다)
+    fn level_runs(&self, start: usize, end: usize) -> Vec<(usize, usize)> {
+        let mut runs: Vec<(usize, usize)> = Vec::new();
+        for i in start..end {
+            match runs.last_mut() {
+                Some(run) if self.levels[i - self.start] == self.levels[run.0 - self.start] => run.1 = i + 1,
+                _ => runs.push((i, i + 1)),
+            }
+        }
+        runs
+    }
+}
+
+/// 한중일 문자인지 (글자 사이 어디서나 줄을 바꿀 수 있다)
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x11FF         // 한글 자모
+        | 0x2E80..=0x2FDF       // 한자 부수
+        | 0x3000..=0x303F       // 한중일 기호, 구두점
+        | 0x3040..=0x30FF       // 히라가나, 가타카나
+        | 0x3130..=0x318F       // 한글 호환 자모
+        | 0x3400..=0x4DBF       // 한자 확장 A
+        | 0x4E00..=0x9FFF       // 한자
+        | 0xAC00..=0xD7AF       // 한글 음절
+        | 0xF900..=0xFAFF       // 한자 호환
+        | 0xFF00..=0xFFEF       // 전각 문자
+        | 0x20000..=0x2FFFF)    // 한자 확장 B 이후
+}
+
+/// 줄 첫머리에 올 수 없는 문자 (닫는 괄호, 구두점)
+fn is_no_break_before(c: char) -> bool {
+    ")]}>,.!?;:%、。，．！？；：」』）】〕〉》ーぁぃぅぇぉっゃゅょァィゥェォッャュョ・…".contains(c)
+}
+
+/// 줄 끝에 올 수 없는 문자 (여는 괄호)
+fn is_no_break_after(c: char) -> bool {
+    "([{<「『（【〔〈《".contains(c)
+}
+
+/// chars[index] 앞에서 줄을 바꿀 수 있는지
+fn can_break_before(chars: &[char], index: usize) -> bool {
+    let (prev, c) = (chars[index - 1], chars[index]);
+    if c.is_whitespace() {
+        return false;   // 공백은 앞 줄 끝에 남긴다.
+    }
+    if prev.is_whitespace() {
+        return true;
+    }
+    if is_no_break_before(c) || is_no_break_after(prev) {
+        return false;
+    }
+    is_cjk(prev) || is_cjk(c)
+}
+
+/// 줄 끝 공백을 뺀 끝 인덱스
+fn trim_end_index(chars: &[char], start: usize, end: usize) -> usize {
+    let mut trimmed = end;
+    while trimmed > start && chars[trimmed - 1].is_whitespace() {
+        trimmed -= 1;
+    }
+    trimmed
+}
+
+/// 그리지 않고 글자 너비만 재는 데 쓰는 캔버스 컨텍스트 (SVG 내보내기처럼 그리기 컨텍스트가 없을 때 사용)
+fn measuring_context() -> Option<CanvasRenderingContext2d> {
+    let canvas = window()?.document()?.create_element("canvas").ok()?.dyn_into::<web_sys::HtmlCanvasElement>().ok()?;
+    canvas.get_context("2d").ok()??.dyn_into::<CanvasRenderingContext2d>().ok()
+}
+
+fn measure(context: &CanvasRenderingContext2d, chars: &[char]) -> f64 {
+    context.measure_text(&chars.iter().collect::<String>()).map_or(0.0, |metrics| metrics.width())
+}
+
+/// 글자 경계마다 텍스트 처음부터의 x 좌표. 서식 범위마다 그 범위의 글꼴로 잰다.
+/// 글자마다 한 번만 재서 더해 나가며, 같은 글꼴의 같은 글자는 다시 재지 않는다.
+fn advances(context: &CanvasRenderingContext2d, chars: &[char], runs: &TextRuns, base: &TextFont) -> Vec<f64> {
+    let mut xs = vec![0.0; chars.len() + 1];
+    let mut widths: HashMap<(String, char), f64> = HashMap::new();
+    for (start, end, style) in runs.segments(0, chars.len()) {
+        let font = style.font(base).to_css();
+        context.set_font(&font);
+        for i in start..end {
+            let width = *widths.entry((font.clone(), chars[i])).or_insert_with(|| measure(context, &chars[i..=i]));
+            xs[i + 1] = xs[i] + width;
+        }
+    }
+    xs
+}
+
+/// 글자마다의 양방향 수준 (유니코드 양방향 알고리즘). 문단 방향은 direction을 따른다.
+fn bidi_levels(chars: &[char], direction: TextDirection) -> Vec<u8> {
+    let text: String = chars.iter().collect();
+    let base = if direction == TextDirection::Rtl { Level::rtl() } else { Level::ltr() };
+    let info = BidiInfo::new(&text, Some(base));
+    text.char_indices().map(|(byte, _)| info.levels[byte].number()).collect()
+}
+
+/// 한 줄 글자들의 화면 순서 (가장 높은 수준부터 가장 낮은 홀수 수준까지 차례로 뒤집는다)
+fn visual_order(levels: &[u8]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..levels.len()).collect();
+    let (Some(&highest), Some(&lowest)) = (levels.iter().max(), levels.iter().min()) else { return order; };
+    for level in ((lowest | 1)..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] < level {
+                i += 1;
+                continue;
+            }
+            let run_start = i;
+            while i < order.len() && levels[order[i]] >= level {
+                i += 1;
+            }
+            order[run_start..i].reverse();
+        }
+    }
+    order
+}
+
+/// 한 줄의 글자를 화면 순서로 놓는다. widths는 글자 너비(양쪽 정렬로 늘어난 간격 포함)이다.
+/// 오른쪽에서 왼쪽 문단은 줄 끝 공백이 왼쪽으로 밀려나므로 그만큼 왼쪽으로 옮겨 글자들이 0에서 시작하게 한다.
+/// (글자마다의 (왼쪽, 오른쪽), 글자 경계마다의 캐럿 위치)를 반환한다.
+fn place_line(widths: &[f64], levels: &[u8], trimmed: usize, rtl: bool) -> (Vec<(f64, f64)>, Vec<f64>) {
+    let mut x = if rtl { -widths[trimmed..].iter().sum::<f64>() } else { 0.0 };
+    let mut glyphs = vec![(0.0, 0.0); widths.len()];
+    for i in visual_order(levels) {
+        glyphs[i] = (x, x + widths[i]);
+        x += widths[i];
+    }
+
+    // 캐럿은 앞 글자의 뒤쪽 가장자리에 놓는다. (오른쪽에서 왼쪽 글자는 왼쪽 가장자리)
+    // 줄의 처음은 첫 글자의 앞쪽 가장자리이다.
+    let offsets = (0..=widths.len()).map(|k| {
+        if widths.is_empty() {
+            0.0
+        }
+        else if k == 0 {
+            if levels[0] % 2 == 1 { glyphs[0].1 } else { glyphs[0].0 }
+        }
+        else if levels[k - 1] % 2 == 1 {
+            glyphs[k - 1].0
+        }
+        else {
+            glyphs[k - 1].1
+        }
+    }).collect();
+    (glyphs, offsets)
+}
+
+/// 세로쓰기의 글자 경계마다 텍스트 처음부터의 y 좌표. 한중일 글자는 정사각형이므로 글자 크기만큼 나아간다.
+fn vertical_advances(chars: &[char], runs: &TextRuns, base: &TextFont) -> Vec<f64> {
+    let mut ys = vec![0.0; chars.len() + 1];
+    for (start, end, style) in runs.segments(0, chars.len()) {
+        let size = style.font(base).size;
+        for i in start..end {
+            ys[i + 1] = ys[i] + size;
+        }
+    }
+    ys
+}
+
+/// 한 문단(chars[start..end])을 available 너비에 맞게 나눈다. xs는 advances의 결과이다. (start, end, hard_break) 목록을 반환한다.
+fn wrap_paragraph(xs: &[f64], chars: &[char], start: usize, end: usize, available: f64) -> Vec<(usize, usize, bool)> {
+    let measure = |from: usize, to: usize| xs[to] - xs[from];
+    let mut lines = Vec::new();
+    let mut line_start = start;
+    while line_start < end {
+        // 줄을 바꿀 수 있는 위치 중 너비 안에 들어가는 가장 먼 위치
+        let candidates = (line_start + 1..end).filter(|&i| can_break_before(chars, i)).chain(std::iter::once(end));
+        let mut line_end = None;
+        for candidate in candidates {
+            let trimmed = trim_end_index(chars, line_start, candidate);
+            if line_end.is_some() && measure(line_start, trimmed) > available {
+                break;
+            }
+            line_end = Some(candidate);
+            if measure(line_start, trimmed) > available {
+                break;  // 첫 단어가 너비보다 길다.
+            }
+        }
+        let mut line_end = line_end.unwrap_or(end);
+
+        // 한 단어가 너비보다 길면 글자 단위로 자른다.
+        let trimmed = trim_end_index(chars, line_start, line_end);
+        if trimmed - line_start > 1 && measure(line_start, trimmed) > available {
+            line_end = (line_start + 1..trimmed).rev()
+                .find(|&i| measure(line_start, i) <= available)
+                .unwrap_or(line_start + 1);
+        }
+
+        lines.push((line_start, line_end, line_end == end));
+        line_start = line_end;
+    }
+
+    if lines.is_empty() {
+        lines.push((start, end, true));
     }
+    lines
+}
+
+/// 글자 인덱스가 속한 줄. 자동 줄바꿈 경계의 인덱스는 다음 줄의 처음으로 본다.
+fn line_index_at(lines: &[VisualLine], index: usize) -> usize {
+    lines.iter()
+        .position(|line| index >= line.start && (index < line.end || (index == line.end && line.hard_break)))
+        .unwrap_or(lines.len().saturating_sub(1))
+}
+
+/// 줄에서 x 좌표(세로쓰기는 y 좌표)에 가장 가까운 글자 경계. 자동 줄바꿈된 줄의 끝은 다음 줄의 처음이므로 제외한다.
+fn nearest_index_in_line(line: &VisualLine, x: f64) -> usize {
+    let last = if line.hard_break || line.end == line.start { line.end } else { line.end - 1 };
+    (line.start..=last)
+        .min_by(|a, b| {
+            let da = (line.offsets[a - line.start] - x).abs();
+            let db = (line.offsets[b - line.start] - x).abs();
+            da.partial_cmp(&db).unwrap()
+        })
+        .unwrap_or(line.start)
+}
 
-    let last_text = text.lines().last().unwrap_or_default();
-    context
-        .measure_text(last_text)
-        .map(|metrics| metrics.width())
-        .unwrap_or_else(|_| 0.0)
+/// 텍스트 박스의 배치 설정 (너비 모드, 정렬, 글쓰기 방향, 경로). 되돌리기에 사용한다.
+#[derive(Debug, Clone)]
+pub struct TextLayout{
+    wrap: TextWrap,
+    align: TextAlign,
+    vertical_align: VerticalAlign,
+    direction: TextDirection,
+    width: f64,         // 방향을 바꾸면 고정한 너비와 높이를 맞바꾸므로 함께 저장한다.
+    box_height: f64,
+    path: Option<TextPath>,
 }
 
 #[derive(Debug, Clone)]
@@ -363,15 +955,28 @@ pub struct TextBox{
     hovered: bool,
     color: String,
     background_color: String,
+    framed: bool,   // 테두리 표시 여부 (표의 셀은 표가 테두리를 그린다)
     axis_x: Vector2D,
     axis_y: Vector2D,
-    font_size: f64,
+    font: TextFont,
     line_gap: f64,
     pub width: f64,
     height: f64,
     selected_control_point: i32,
     pub composition_text: String,
     pub cursor_position: usize,
+    appearance: Appearance,
+    wrap: TextWrap,
+    align: TextAlign,
+    vertical_align: VerticalAlign,
+    box_height: f64,                // 고정 너비 모드에서 지정한 박스 높이 (텍스트가 더 높으면 늘어난다)
+    layout_cache: Vec<VisualLine>,  // 마지막으로 그린 줄 배치 (SVG 내보내기에 사용)
+    runs: TextRuns,                 // 글자 범위별 서식 (굵게, 밑줄, 색상 등)
+    typing_style: Option<RunStyle>, // 선택 범위 없이 Ctrl+B 등을 눌렀을 때 다음에 입력할 글자의 서식
+    selection_anchor: Option<usize>,// 선택 범위의 시작 (다른 끝은 커서 위치)
+    preferred_x: Option<(usize, f64)>,  // 위/아래로 이동할 때 유지할 x 좌표와 그때의 커서 위치
+    path: Option<TextPath>,         // 글자를 얹은 경로 (있으면 박스 대신 경로를 따라 한 줄로 그린다)
+    direction: TextDirection,       // 글쓰기 방향 (경로 위에서는 왼쪽에서 오른쪽으로만 쓴다)
 }
 impl TextBox{
     pub fn new(x: f64, y: f64) -> Self {
@@ -383,21 +988,41 @@ impl TextBox{
             , hovered: false
             , color: "#000000".to_string()
             , background_color: "lightgray".to_string() //
+            , framed: true
             , axis_x: Vector2D::AXIS_X
             , axis_y: Vector2D::AXIS_Y
-            , font_size: 20.0
+            , font: TextFont::default()
             , line_gap: 5.0
             , width: 50.0
             , height: 30.0
             , selected_control_point: -1
             , composition_text: String::new()
-            , cursor_position: 0}
+            , cursor_position: 0
+            , appearance: Appearance::default()
+            , wrap: TextWrap::Auto
+            , align: TextAlign::Left
+            , vertical_align: VerticalAlign::Top
+            , box_height: 0.0
+            , layout_cache: Vec::new()
+            , runs: TextRuns::default()
+            , typing_style: None
+            , selection_anchor: None
+            , preferred_x: None
+            , path: None
+            , direction: TextDirection::Ltr}
     }
 
     fn control_points(&self) -> Vec<Point2D>{
+        // 경로 위의 글자는 첫 글자 위치의 핸들로 경로를 따라 옮긴다.
+        if let (Some(points), Some(line)) = (self.path_points(), self.layout_cache.first()) {
+            return vec![point_at_distance(&points, line.offsets[0]).0];
+        }
+
         let control_pts = vec![
             Point2D::new(self.position.x + self.width * 0.5, self.position.y + self.height * 0.5) ,
-            Point2D::new(self.position.x + self.width * 0.5, self.position.y - 30.0)
+            Point2D::new(self.position.x + self.width * 0.5, self.position.y - 30.0),
+            Point2D::new(self.position.x + self.width, self.position.y + self.height * 0.5),    // 너비 (고정 너비로 바뀐다)
+            Point2D::new(self.position.x + self.width * 0.5, self.position.y + self.height + 5.0),  // 높이
             ];
 
         control_pts
@@ -416,118 +1041,792 @@ impl TextBox{
     }
 
     pub fn get_font_size(&self) -> f64 {
-        self.font_size
+        self.font.size
     }
 
-    pub  fn get_line_gap(&self) -> f64 {
-        self.line_gap
+    pub fn font(&self) -> &TextFont {
+        &self.font
     }
 
-    // ✅ 텍스트 박스 높이 계산
-    fn get_height(&self, text: &str) -> f64 {
-        let lines = split_lines(text);
-        let line_count = lines.into_iter().count().max(1); // 최소 1줄
-        10.0 + (line_count as f64) * (self.font_size) + ((line_count - 1) as f64) * (self.line_gap)
+    pub fn set_font(&mut self, font: TextFont) {
+        self.font = font;
+        self.height = self.get_height(&self.text);
     }
 
-    /// ✅ 줄 개수에 따라 높이 자동 조정
-    fn update_height(&mut self, height: f64) {
-        self.height = height;
+    pub fn wrap(&self) -> TextWrap {
+        self.wrap
     }
 
-    /// ✅ 위쪽 줄로 이동
-    fn move_cursor_up(&mut self) {
-        if self.cursor_position == 0 {
-            return; // 🚫 첫 줄에서는 더 위로 이동할 수 없음
+    /// 너비 모드를 바꾼다. 고정 너비로 바꾸면 현재 너비와 높이를 유지한다.
+    pub fn set_wrap(&mut self, wrap: TextWrap) {
+        if wrap == TextWrap::Fixed && self.wrap == TextWrap::Auto {
+            self.box_height = self.height;
         }
+        self.wrap = wrap;
+    }
 
-        let mut pos = self.cursor_position;
-        let mut row = self.get_row_index_at_cursor();
-        let mut col = self.get_column_index_at_cursor();
-        info!("mouse move up row: {}, col: {}", row, col);
+    pub fn align(&self) -> TextAlign {
+        self.align
+    }
 
-        if row > 0{
-            row -= 1;
+    pub fn set_align(&mut self, align: TextAlign) {
+        self.align = align;
+    }
 
-            let lines = self.text.lines();
-            if let Some(line) = lines.clone().nth(row) {
-                if line.chars().count() < col {
-                    col = line.chars().count();
-                }
-            }
+    pub fn vertical_align(&self) -> VerticalAlign {
+        self.vertical_align
+    }
+
+    pub fn set_vertical_align(&mut self, vertical_align: VerticalAlign) {
+        self.vertical_align = vertical_align;
+    }
+
+    pub fn direction(&self) -> TextDirection {
+        self.direction
+    }
 
-            info!("cursor_position: {}, row: {}, col: {}", self.cursor_position, row, col);
-            pos = lines.take(row).map(|line| line.chars().count() + 1).sum::<usize>() + col;
-            info!("pos: {}", pos);
+    /// 글쓰기 방향을 바꾼다. 왼쪽에서 오른쪽과 오른쪽에서 왼쪽을 바꾸면 왼쪽/오른쪽 정렬도 맞바꾼다. (문단 시작 쪽 정렬을 유지한다)
+    /// 가로쓰기와 세로쓰기를 바꾸면 고정한 너비와 높이를 맞바꾼다.
+    pub fn set_direction(&mut self, direction: TextDirection) {
+        let is_vertical = |direction: TextDirection| direction == TextDirection::Vertical;
+        if is_vertical(self.direction) != is_vertical(direction) {
+            if self.wrap == TextWrap::Fixed {
+                std::mem::swap(&mut self.width, &mut self.box_height);
+            }
         }
+        else if self.direction != direction && self.path.is_none() {
+            // 경로 위에서는 왼쪽에서 오른쪽으로만 쓰므로 정렬을 맞바꾸지 않는다.
+            self.align = match self.align {
+                TextAlign::Left => TextAlign::Right,
+                TextAlign::Right => TextAlign::Left,
+                align => align,
+            };
+        }
+        self.direction = direction;
+    }
 
-        self.cursor_position = pos;
+    pub fn text_layout(&self) -> TextLayout {
+        TextLayout{
+            wrap: self.wrap,
+            align: self.align,
+            vertical_align: self.vertical_align,
+            direction: self.direction,
+            width: self.width,
+            box_height: self.box_height,
+            path: self.path.clone(),
+        }
     }
 
-    /// ✅ 아래쪽 줄로 이동
-    fn move_cursor_down(&mut self) {
+    pub fn set_text_layout(&mut self, layout: &TextLayout) {
+        self.wrap = layout.wrap;
+        self.align = layout.align;
+        self.vertical_align = layout.vertical_align;
+        self.direction = layout.direction;
+        self.width = layout.width;
+        self.box_height = layout.box_height;
+        self.path = layout.path.clone();
+    }
 
-        let mut pos = self.cursor_position;
-        let mut row = self.get_row_index_at_cursor();
-        let mut col = self.get_column_index_at_cursor();
+    /// 세로쓰기에서 줄(열)의 왼쪽 x 좌표 (박스 왼쪽 기준)
+    fn column_x(&self, line: &VisualLine) -> f64 {
+        self.width - line.top - line.size
+    }
 
-        let lines = split_lines(&self.text);
-        let line_count = lines.iter().count();
+    pub fn path(&self) -> Option<&TextPath> {
+        self.path.as_ref()
+    }
 
-        info!("mouse move down cursor_position: {}, row: {}, col: {}, count: {}", self.cursor_position, row, col, line_count);
+    /// 글자를 guide 도형의 경로에 얹는다. 경로 위에서는 박스를 회전하지 않는다.
+    pub fn attach_to_path(&mut self, guide: Arc<Mutex<Box<dyn Shape>>>) {
+        self.path = Some(TextPath::new(guide));
+        self.rotation = 0.0;
+    }
 
-        if row < line_count - 1{
-            row += 1;
+    /// 경로에서 떼어 내 지금 글자 영역의 박스로 되돌린다.
+    pub fn detach_from_path(&mut self) {
+        self.path = None;
+    }
 
-            let line = lines[row];
-            info!("line: {}", line);
-            if line.chars().count() < col {
-                col = line.chars().count();
-            }
+    /// shapes 중에 이 텍스트 박스가 얹힌 경로 도형이 있는지
+    pub fn follows_any(&self, shapes: &[Arc<Mutex<Box<dyn Shape>>>]) -> bool {
+        self.path.as_ref().is_some_and(|path| shapes.iter().any(|shape| Arc::ptr_eq(shape, &path.guide)))
+    }
 
-            let row_chars = lines.into_iter().take(row).map(|line| line.chars().count() + 1).sum::<usize>();
-            info!("row_chars: {}, col: {}", row_chars, col);
-            pos = row_chars + col;
+    /// 시작 위치, 경로에 대한 위치 등을 바꾼다.
+    pub fn update_path(&mut self, f: impl Fn(&mut TextPath)) {
+        if let Some(path) = self.path.as_mut() {
+            f(path);
         }
+    }
 
-        self.cursor_position = pos;
+    /// 경로 도형의 현재 꺾은선 (경로에 얹지 않았거나 경로가 비어 있으면 None)
+    fn path_points(&self) -> Option<Vec<Point2D>> {
+        let points = self.path.as_ref()?.points();
+        (points.len() >= 2).then_some(points)
     }
 
-    /// ✅ 현재 줄의 시작으로 이동
-    fn move_cursor_to_line_start(&mut self) {
-        let row = self.get_row_index_at_cursor();
-        let col = 0;
+    /// 경로 위 글자의 법선 방향 범위 (위쪽, 아래쪽). 경로가 0이고 진행 방향의 오른쪽이 +이다.
+    fn path_extent(&self, size: f64) -> (f64, f64) {
+        let baseline = self.path.as_ref().map_or(0.0, |path| path.side.baseline_offset(size));
+        (baseline - size, baseline + size * 0.25)
+    }
 
-        let lines = split_lines(&self.text);
+    /// 경로 위 index 번째 글자의 (글자 가운데의 경로 위 점, 진행 방향 각도, 글자 너비)
+    fn glyph_on_path(line: &VisualLine, points: &[Point2D], index: usize) -> (Point2D, f64, f64) {
+        let (left, right) = (line.offsets[index - line.start], line.offsets[index - line.start + 1]);
+        let (point, angle) = point_at_distance(points, (left + right) * 0.5);
+        (point, angle, right - left)
+    }
 
-        let row_chars = lines.into_iter().take(row).map(|line| line.chars().count() + 1).sum::<usize>();
-        let pos = row_chars + col;
+    /// 화면에 표시할 텍스트 (커서 위치에 조합 중인 글자를 넣는다)
+    fn display_text(&self) -> String {
+        let byte_index = self.get_byte_index_at_cursor();
+        format!("{}{}{}", &self.text[..byte_index], self.composition_text, &self.text[byte_index..])
+    }
 
-        self.cursor_position = pos;
+    /// 화면에 표시할 텍스트의 서식 범위 (조합 중인 글자는 입력할 글자의 서식을 따른다)
+    fn display_runs(&self) -> TextRuns {
+        let mut runs = self.runs.clone();
+        runs.fit(self.text.chars().count());
+        runs.insert(self.cursor_position, self.composition_text.chars().count(), self.insertion_style());
+        runs
     }
 
-    /// ✅ 현재 줄의 끝으로 이동
-    fn move_cursor_to_line_end(&mut self) {
-        let mut pos = self.cursor_position;
-        let mut row = self.get_row_index_at_cursor();
-        let mut col = self.get_column_index_at_cursor();
+    /// 커서 위치에 입력할 글자의 서식 (앞 글자의 서식을 이어받는다)
+    fn insertion_style(&self) -> RunStyle {
+        self.typing_style.clone().unwrap_or_else(|| self.runs.style_at(self.cursor_position.saturating_sub(1)))
+    }
 
-        let lines = split_lines(&self.text);
-        let line = lines[row];
-        col = line.chars().count();
+    /// 선택 범위 (시작, 끝). 선택된 글자가 없으면 None
+    pub fn selection_range(&self) -> Option<(usize, usize)> {
+        let anchor = self.selection_anchor?;
+        (anchor != self.cursor_position).then(|| (anchor.min(self.cursor_position), anchor.max(self.cursor_position)))
+    }
 
-        let row_chars = lines.into_iter().take(row).map(|line| line.chars().count() + 1).sum::<usize>();
-        pos = row_chars + col;
+    /// 커서를 옮기기 전에 호출한다. extend이면 선택 범위를 넓히고, 아니면 선택을 해제한다.
+    pub fn begin_navigation(&mut self, extend: bool) {
+        self.typing_style = None;
+        if extend {
+            self.selection_anchor.get_or_insert(self.cursor_position);
+        }
+        else {
+            self.selection_anchor = None;
+        }
+    }
 
-        self.cursor_position = pos;
+    /// 커서를 index로 옮긴다. extend이면 선택 범위를 넓힌다.
+    pub fn set_cursor(&mut self, index: usize, extend: bool) {
+        self.begin_navigation(extend);
+        self.cursor_position = self.snap_to_grapheme(index.min(self.text.chars().count()));
     }
 
-    pub fn get_char_index_at_cursor(&self) -> usize {
-        self.cursor_position
+    /// 선택된 글자 (선택 범위가 없으면 None)
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection_range()?;
+        Some(self.text.chars().skip(start).take(end - start).collect())
     }
 
-    pub fn get_byte_index_at_cursor(&self) -> usize {
-        let index = self.text
+    pub fn select_all(&mut self) {
+        self.typing_style = None;
+        self.selection_anchor = Some(0);
+        self.cursor_position = self.text.chars().count();
+    }
+
+    /// 선택된 글자를 지운다. 지운 글자가 있으면 true
+    pub fn delete_selection(&mut self) -> bool {
+        let selection = self.selection_range();
+        self.selection_anchor = None;
+        let Some((start, end)) = selection else { return false; };
+        self.delete_range(start, end);
+        true
+    }
+
+    /// index 위치의 단어를 선택한다. (글자, 공백, 문장 부호를 각각 한 덩어리로 본다)
+    pub fn select_word_at(&mut self, index: usize) {
+        let chars: Vec<char> = self.text.chars().collect();
+        if chars.is_empty() {
+            return;
+        }
+        let class = |c: char| if c.is_alphanumeric() || c == '_' { 0 } else if c.is_whitespace() { 1 } else { 2 };
+
+        // 단어 끝에서 누르면 앞 단어를 선택한다.
+        let index = if index >= chars.len() || (index > 0 && chars[index] == '\n') { index - 1 } else { index };
+        if chars[index] == '\n' {
+            self.set_cursor(index, false);
+            return;
+        }
+        let target = class(chars[index]);
+        let start = (0..index).rev().take_while(|&i| chars[i] != '\n' && class(chars[i]) == target).last().unwrap_or(index);
+        let end = (index..chars.len()).take_while(|&i| chars[i] != '\n' && class(chars[i]) == target).last().map_or(index, |i| i + 1);
+
+        self.typing_style = None;
+        self.selection_anchor = Some(start);
+        self.cursor_position = end;
+    }
+
+    /// index 위치의 줄(화면에 보이는 줄)을 선택한다.
+    pub fn select_line_at(&mut self, index: usize) {
+        let Some(line) = self.layout_cache.get(line_index_at(&self.layout_cache, index)) else { return; };
+        let (start, end) = (line.start, line.end);
+
+        self.typing_style = None;
+        self.selection_anchor = Some(start);
+        self.cursor_position = end;
+    }
+
+    /// 문서 좌표를 박스의 좌표계(왼쪽 위 기준, 회전 전)로 바꾼다.
+    fn to_local(&self, x: f64, y: f64) -> Vector2D {
+        let mut local = Vector2D::new(x - self.position.x, y - self.position.y);
+        local.rotate_by(-self.rotation);
+        local
+    }
+
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        if let (Some(points), Some(line)) = (self.path_points(), self.layout_cache.first()) {
+            let (along, distance) = project_to_polyline(&points, Point2D::new(x, y));
+            let (top, bottom) = self.path_extent(line.size);
+            return along >= line.offsets[0] - PADDING && along <= line.offsets[line.offsets.len() - 1] + PADDING
+                && distance <= top.abs().max(bottom.abs());
+        }
+
+        let local = self.to_local(x, y);
+        local.x >= 0.0 && local.x <= self.width && local.y >= 0.0 && local.y <= self.height + 5.0
+    }
+
+    /// (x, y)에 가장 가까운 글자 경계 (마지막으로 그린 줄 배치 기준)
+    pub fn index_at_point(&self, x: f64, y: f64) -> usize {
+        if let (Some(points), Some(line)) = (self.path_points(), self.layout_cache.first()) {
+            let (along, _) = project_to_polyline(&points, Point2D::new(x, y));
+            return self.snap_to_grapheme(nearest_index_in_line(line, along).min(self.text.chars().count()));
+        }
+
+        let local = self.to_local(x, y);
+        let lines = &self.layout_cache;
+        let vertical = self.direction == TextDirection::Vertical;
+        let Some(line) = lines.iter()
+            .find(|line| if vertical { local.x > self.column_x(line) - self.line_gap } else { local.y < line.top + line.size + self.line_gap })
+            .or(lines.last()) else {
+            return self.cursor_position;
+        };
+        let along = if vertical { local.y } else { local.x };
+        self.snap_to_grapheme(nearest_index_in_line(line, along).min(self.text.chars().count()))
+    }
+
+    /// 선택 범위의 서식을 켜고 끈다. 선택 범위의 모든 글자에 켜져 있으면 끈다.
+    /// 선택 범위가 없으면 다음에 입력할 글자의 서식을 바꾼다.
+    pub fn toggle_run_style(&mut self, is_on: impl Fn(&RunStyle) -> bool, set: impl Fn(&mut RunStyle, bool)) {
+        match self.selection_range() {
+            Some((start, end)) => {
+                self.runs.fit(self.text.chars().count());
+                let on = !self.runs.all_in_range(start, end, &is_on);
+                self.runs.update_range(start, end, |style| set(style, on));
+            }
+            None => {
+                let mut style = self.insertion_style();
+                let on = !is_on(&style);
+                set(&mut style, on);
+                self.typing_style = Some(style);
+            }
+        }
+    }
+
+    /// 선택 범위(없으면 다음에 입력할 글자)의 서식을 바꾼다. (글자 색상, 크기)
+    pub fn update_run_style(&mut self, f: impl Fn(&mut RunStyle)) {
+        match self.selection_range() {
+            Some((start, end)) => {
+                self.runs.fit(self.text.chars().count());
+                self.runs.update_range(start, end, f);
+            }
+            None => {
+                let mut style = self.insertion_style();
+                f(&mut style);
+                self.typing_style = Some(style);
+            }
+        }
+    }
+
+    /// 줄바꿈과 정렬을 계산한다.
+    pub fn layout(&self, context: &CanvasRenderingContext2d) -> Vec<VisualLine> {
+        let chars: Vec<char> = self.display_text().chars().collect();
+        let runs = self.display_runs();
+        let vertical = self.direction == TextDirection::Vertical;
+        let available = (if vertical { self.box_height } else { self.width } - PADDING * 2.0).max(1.0);
+
+        context.save();
+        let xs = if vertical { vertical_advances(&chars, &runs, &self.font) } else { advances(context, &chars, &runs, &self.font) };
+        context.restore();
+
+        if let Some(points) = self.path_points() {
+            return vec![self.layout_on_path(&chars, &runs, &xs, &points)];
+        }
+        let levels = if vertical { vec![0; chars.len()] } else { bidi_levels(&chars, self.direction) };
+        let rtl = self.direction == TextDirection::Rtl;
+
+        // 1) 문단(개행 문자)별로 나누고, 고정 너비이면 단어 경계에서 다시 나눈다.
+        let mut ranges = Vec::new();
+        let mut paragraph_start = 0;
+        loop {
+            let paragraph_end = chars[paragraph_start..].iter().position(|c| *c == '\n').map_or(chars.len(), |p| paragraph_start + p);
+            match self.wrap {
+                TextWrap::Auto => ranges.push((paragraph_start, paragraph_end, true)),
+                TextWrap::Fixed => ranges.extend(wrap_paragraph(&xs, &chars, paragraph_start, paragraph_end, available)),
+            }
+            if paragraph_end >= chars.len() {
+                break;
+            }
+            paragraph_start = paragraph_end + 1;
+        }
+
+        // 2) 글자 경계의 x 좌표 (정렬 전)와 줄의 글자 크기
+        let natural: Vec<(Vec<f64>, f64, usize, f64)> = ranges.iter().map(|&(start, end, _)| {
+            let offsets = (start..=end).map(|i| xs[i] - xs[start]).collect::<Vec<_>>();
+            let trimmed_end = trim_end_index(&chars, start, end);
+            let size = self.line_size(&runs, start, end);
+            (offsets.clone(), offsets[trimmed_end - start], trimmed_end, size)
+        }).collect();
+
+        // 자동 너비는 가장 긴 줄에 맞춘다. (update_width와 같은 최소 너비)
+        let available = match self.wrap {
+            TextWrap::Auto => natural.iter().map(|(_, width, _, _)| *width).fold(0.0, f64::max).max(50.0),
+            TextWrap::Fixed => available,
+        };
+
+        // 3) 정렬과 세로 위치 (세로쓰기는 오른쪽 줄부터 쌓는다)
+        let sizes = natural.iter().map(|(_, _, _, size)| *size).collect::<Vec<_>>();
+        let content_height = self.content_height(&sizes);
+        let box_height = match self.wrap {
+            TextWrap::Auto => content_height,
+            TextWrap::Fixed => self.box_height.max(content_height),
+        };
+        let mut top = PADDING + match self.vertical_align {
+            _ if vertical => 0.0,
+            VerticalAlign::Top => 0.0,
+            VerticalAlign::Middle => (box_height - content_height) * 0.5,
+            VerticalAlign::Bottom => box_height - content_height,
+        };
+
+        ranges.iter().zip(natural).map(|(&(start, end, hard_break), (offsets, width, trimmed_end, size))| {
+            let extra = available - width;
+            let line_chars = &chars[start..trimmed_end];
+            let spaces = line_chars.iter().filter(|c| c.is_whitespace()).count();
+            let justified = self.align == TextAlign::Justify && !hard_break && extra > 0.0 && line_chars.len() > 1;
+
+            // 양쪽 정렬은 공백이 있으면 공백에, 없으면(한중일 문장) 글자 사이에 간격을 나눈다.
+            let widths = (start..end).map(|i| {
+                let k = i - start;
+                let spacing = if !justified || k >= line_chars.len() {
+                    0.0
+                } else if spaces > 0 {
+                    if line_chars[k].is_whitespace() { extra / spaces as f64 } else { 0.0 }
+                } else if k < line_chars.len() - 1 {
+                    extra / (line_chars.len() - 1) as f64
+                } else {
+                    0.0
+                };
+                offsets[k + 1] - offsets[k] + spacing
+            }).collect::<Vec<_>>();
+            let shift = PADDING + match self.align {
+                _ if justified => 0.0,
+                TextAlign::Center => extra * 0.5,
+                TextAlign::Right => extra,
+                TextAlign::Justify if rtl => extra,     // 문단의 마지막 줄은 시작 쪽(오른쪽)에 붙인다.
+                _ => 0.0,
+            };
+
+            // 줄 끝 공백은 문단 방향을 따른다. (자동 줄바꿈된 줄의 공백이 줄 중간으로 가지 않는다)
+            let mut line_levels = levels[start..end].to_vec();
+            line_levels[trimmed_end - start..].iter_mut().for_each(|level| *level = rtl as u8);
+            let (glyphs, offsets) = place_line(&widths, &line_levels, trimmed_end - start, rtl);
+            let line = VisualLine{
+                start,
+                end,
+                trimmed_end,
+                hard_break,
+                text: chars[start..end].iter().collect(),
+                width,
+                offsets: offsets.iter().map(|x| shift + x).collect(),
+                glyphs: glyphs.iter().map(|(left, right)| (shift + left, shift + right)).collect(),
+                levels: line_levels,
+                top,
+                size,
+                justified,
+            };
+            top += size + self.line_gap;
+            line
+        }).collect()
+    }
+
+    /// [start, end) 글자 중 가장 큰 글자 크기 (빈 줄은 앞 글자의 크기)
+    fn line_size(&self, runs: &TextRuns, start: usize, end: usize) -> f64 {
+        runs.segments(start, end).iter()
+            .map(|(_, _, style)| style.line_size(&self.font))
+            .fold(None, |max: Option<f64>, size| Some(max.map_or(size, |max| max.max(size))))
+            .unwrap_or_else(|| runs.style_at(start.saturating_sub(1)).line_size(&self.font))
+    }
+
+    /// 경로를 따라 한 줄로 배치한다. offsets는 경로 시작점부터의 거리이다.
+    /// 정렬은 시작 위치부터 경로 끝까지의 구간을 기준으로 한다.
+    fn layout_on_path(&self, chars: &[char], runs: &TextRuns, xs: &[f64], points: &[Point2D]) -> VisualLine {
+        let start_offset = self.path.as_ref().map_or(0.0, |path| path.start_offset);
+        let end = chars.len();
+        let trimmed_end = trim_end_index(chars, 0, end);
+        let width = xs[trimmed_end];
+        let extra = polyline_length(points) - start_offset - width;
+        let justified = self.align == TextAlign::Justify && extra > 0.0 && trimmed_end > 1;
+
+        let offsets: Vec<f64> = (0..=end).map(|i| {
+            let shift = match self.align {
+                _ if justified => extra / (trimmed_end - 1) as f64 * i.min(trimmed_end - 1) as f64,
+                TextAlign::Center => extra * 0.5,
+                TextAlign::Right => extra,
+                _ => 0.0,
+            };
+            start_offset + xs[i] + shift
+        }).collect();
+
+        let glyphs = offsets.windows(2).map(|pair| (pair[0], pair[1])).collect();
+        VisualLine{
+            start: 0,
+            end,
+            trimmed_end,
+            hard_break: true,
+            text: chars.iter().collect(),
+            width,
+            offsets,
+            glyphs,
+            levels: vec![0; end],
+            top: 0.0,
+            size: self.line_size(runs, 0, end),
+            justified,
+        }
+    }
+
+    /// 경로 위 글자들을 감싸는 영역으로 위치와 크기를 맞춘다. (선택, 끌기에 사용)
+    fn fit_to_path(&mut self, line: &VisualLine, points: &[Point2D]) {
+        let (top, bottom) = self.path_extent(line.size);
+        let mut corners = Vec::new();
+        for index in line.start..line.end {
+            let (point, angle, width) = Self::glyph_on_path(line, points, index);
+            for (x, y) in [(-width * 0.5, top), (width * 0.5, top), (width * 0.5, bottom), (-width * 0.5, bottom)] {
+                let mut corner = Vector2D::new(x, y);
+                corner.rotate_by(angle);
+                corners.push(Point2D::new(point.x + corner.x, point.y + corner.y));
+            }
+        }
+        if corners.is_empty() {
+            corners.push(point_at_distance(points, line.offsets[0]).0);
+        }
+
+        let min_x = corners.iter().map(|p| p.x).fold(f64::MAX, f64::min);
+        let min_y = corners.iter().map(|p| p.y).fold(f64::MAX, f64::min);
+        let max_x = corners.iter().map(|p| p.x).fold(f64::MIN, f64::max);
+        let max_y = corners.iter().map(|p| p.y).fold(f64::MIN, f64::max);
+        self.position = Point2D::new(min_x - PADDING, min_y - PADDING);
+        self.width = max_x - min_x + PADDING * 2.0;
+        self.height = max_y - min_y + PADDING * 2.0;
+    }
+
+    /// 경로를 따라 글자를 하나씩 돌려 그린다.
+    fn draw_on_path(&self, context: &CanvasRenderingContext2d, line: &VisualLine, points: &[Point2D]) {
+        let baseline = self.path.as_ref().map_or(0.0, |path| path.side.baseline_offset(line.size));
+        for (start, end, style) in self.display_runs().segments(line.start, line.end){
+            let font = style.font(&self.font);
+            context.set_font(&font.to_css());
+            context.set_fill_style(&style.color.as_deref().unwrap_or(&self.color).into());
+
+            let y = baseline + style.baseline_shift(&self.font);
+            let thickness = (font.size / 15.0).max(1.0);
+            for (k, c) in line.text.chars().skip(start - line.start).take(end - start).enumerate(){
+                if c == '\n'{
+                    continue;
+                }
+                let (point, angle, width) = Self::glyph_on_path(line, points, start + k);
+                context.save();
+                context.translate(point.x, point.y).unwrap();
+                context.rotate(angle).unwrap();
+                context.fill_text(&c.to_string(), -width * 0.5, y).unwrap();
+                if style.underline{
+                    context.fill_rect(-width * 0.5, y + font.size * 0.1, width, thickness);
+                }
+                if style.strikethrough{
+                    context.fill_rect(-width * 0.5, y - font.size * 0.3, width, thickness);
+                }
+                context.restore();
+            }
+        }
+    }
+
+    /// 가로쓰기 줄을 그린다. 서식 범위를 다시 방향이 같은 조각으로 나누어 조각마다 그 방향으로 그린다.
+    fn draw_lines(&self, context: &CanvasRenderingContext2d, lines: &[VisualLine]) {
+        context.set_text_align("left");
+        let runs = self.display_runs();
+        for line in lines{
+            let baseline = self.position.y + line.top + line.size;
+            for (start, end, style) in runs.segments(line.start, line.end){
+                let font = style.font(&self.font);
+                context.set_font(&font.to_css());
+                context.set_fill_style(&style.color.as_deref().unwrap_or(&self.color).into());
+
+                let y = baseline + style.baseline_shift(&self.font);
+                let thickness = (font.size / 15.0).max(1.0);
+                for (run_start, run_end) in line.level_runs(start, end){
+                    let rtl = line.levels[run_start - line.start] % 2 == 1;
+                    js_sys::Reflect::set(context, &"direction".into(), &(if rtl { "rtl" } else { "ltr" }).into()).unwrap();
+
+                    let text: String = line.text.chars().skip(run_start - line.start).take(run_end - run_start).collect();
+                    if line.justified{
+                        // 양쪽 정렬된 줄은 늘어난 간격에 맞춰 글자를 하나씩 그린다.
+                        for (k, c) in text.chars().enumerate(){
+                            context.fill_text(&c.to_string(), self.position.x + line.glyphs[run_start - line.start + k].0, y).unwrap();
+                        }
+                    }
+                    else{
+                        context.fill_text(&text, self.position.x + line.span(run_start, run_end).0, y).unwrap();
+                    }
+
+                    // 밑줄, 취소선 (줄 끝 공백은 제외)
+                    let decoration_end = run_end.min(line.trimmed_end).max(run_start);
+                    if decoration_end > run_start{
+                        let (left, right) = line.span(run_start, decoration_end);
+                        if style.underline{
+                            context.fill_rect(self.position.x + left, y + font.size * 0.1, right - left, thickness);
+                        }
+                        if style.strikethrough{
+                            context.fill_rect(self.position.x + left, y - font.size * 0.3, right - left, thickness);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// 세로쓰기 줄(열)을 그린다. 글자를 세워서 하나씩 열의 가운데에 놓는다.
+    fn draw_vertical(&self, context: &CanvasRenderingContext2d, lines: &[VisualLine]) {
+        context.set_text_align("center");
+        context.set_text_baseline("middle");
+        let runs = self.display_runs();
+        for line in lines{
+            let center = self.position.x + self.column_x(line) + line.size * 0.5;
+            for (start, end, style) in runs.segments(line.start, line.end){
+                let font = style.font(&self.font);
+                context.set_font(&font.to_css());
+                context.set_fill_style(&style.color.as_deref().unwrap_or(&self.color).into());
+
+                for (k, c) in line.text.chars().skip(start - line.start).take(end - start).enumerate(){
+                    let (top, bottom) = line.glyphs[start - line.start + k];
+                    context.fill_text(&c.to_string(), center, self.position.y + (top + bottom) * 0.5).unwrap();
+                }
+
+                // 세로쓰기의 밑줄은 글자 오른쪽에, 취소선은 가운데에 긋는다. (줄 끝 공백은 제외)
+                let decoration_end = end.min(line.trimmed_end).max(start);
+                if decoration_end > start{
+                    let (top, bottom) = line.span(start, decoration_end);
+                    let thickness = (font.size / 15.0).max(1.0);
+                    if style.underline{
+                        context.fill_rect(center + font.size * 0.55, self.position.y + top, thickness, bottom - top);
+                    }
+                    if style.strikethrough{
+                        context.fill_rect(center - thickness * 0.5, self.position.y + top, thickness, bottom - top);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 편집 중 다시 그리기 전에 지난번 글자 영역을 배경색으로 덮는다. 경로 위의 글자는 박스를 그리지 않으므로 캐럿과 강조 표시가 남는다.
+    pub fn erase_path_area(&self, context: &CanvasRenderingContext2d) {
+        if self.path_points().is_some() {
+            context.set_fill_style(&JsValue::from_str(&self.background_color));
+            context.fill_rect(self.position.x, self.position.y, self.width, self.height);
+        }
+    }
+
+    /// [start, end) 글자 영역을 칠한다. (선택 범위, 조합 중인 글자 강조. 채우기 색은 호출하는 쪽에서 정한다)
+    pub fn fill_glyph_spans(&self, context: &CanvasRenderingContext2d, start: usize, end: usize) {
+        let lines = &self.layout_cache;
+        if let (Some(points), Some(line)) = (self.path_points(), lines.first()) {
+            let (top, bottom) = self.path_extent(line.size);
+            for index in start.max(line.start)..end.min(line.end) {
+                let (point, angle, width) = Self::glyph_on_path(line, &points, index);
+                context.save();
+                context.translate(point.x, point.y).unwrap();
+                context.rotate(angle).unwrap();
+                context.fill_rect(-width * 0.5, top, width, bottom - top);
+                context.restore();
+            }
+            return;
+        }
+
+        context.save();
+        context.translate(self.position.x, self.position.y).unwrap();
+        context.rotate(self.rotation).unwrap();
+        for index in start..end {
+            if let Some((x, y, width, height)) = self.glyph_rect(lines, index) {
+                context.fill_rect(x, y, width, height);
+            }
+        }
+        context.restore();
+    }
+
+    /// index 위치에 캐럿을 그린다.
+    pub fn fill_caret(&self, context: &CanvasRenderingContext2d, index: usize) {
+        let lines = &self.layout_cache;
+        if lines.is_empty() {
+            return;
+        }
+        if let (Some(points), Some(line)) = (self.path_points(), lines.first()) {
+            let (top, bottom) = self.path_extent(line.size);
+            let (point, angle) = point_at_distance(&points, line.offsets[index.clamp(line.start, line.end) - line.start]);
+            context.save();
+            context.translate(point.x, point.y).unwrap();
+            context.rotate(angle).unwrap();
+            context.fill_rect(-1.0, top, 2.0, bottom - top);
+            context.restore();
+            return;
+        }
+
+        let (x, y, width, height) = self.caret_rect(lines, index);
+        context.save();
+        context.translate(self.position.x, self.position.y).unwrap();
+        context.rotate(self.rotation).unwrap();
+        context.fill_rect(x, y, width, height);
+        context.restore();
+    }
+
+    /// 줄마다의 글자 크기에 따른 텍스트 높이
+    fn content_height(&self, sizes: &[f64]) -> f64 {
+        let line_count = sizes.len().max(1);
+        let total = if sizes.is_empty() { self.font.size } else { sizes.iter().sum() };
+        PADDING * 2.0 + total + ((line_count - 1) as f64) * self.line_gap
+    }
+
+    /// 줄 배치에 맞게 너비(자동 너비 모드)와 높이를 맞춘다.
+    fn fit_to_layout(&mut self, lines: &[VisualLine]) {
+        let content_height = self.content_height(&lines.iter().map(|line| line.size).collect::<Vec<_>>());
+        if self.direction == TextDirection::Vertical {
+            // 세로쓰기는 줄(열)을 쌓은 만큼 너비가, 가장 긴 줄만큼 높이가 된다.
+            let longest = lines.iter().map(|line| line.width).fold(0.0, f64::max).max(50.0) + PADDING * 2.0;
+            self.width = content_height;
+            self.height = match self.wrap {
+                TextWrap::Auto => longest,
+                TextWrap::Fixed => self.box_height.max(longest),
+            };
+            return;
+        }
+        match self.wrap {
+            TextWrap::Auto => {
+                self.update_width(lines.iter().map(|line| line.width).fold(0.0, f64::max));
+                self.height = content_height;
+            }
+            TextWrap::Fixed => self.height = self.box_height.max(content_height),
+        }
+    }
+
+    /// 줄바꿈을 다시 계산하고 크기를 맞춘다.
+    pub fn relayout(&mut self, context: &CanvasRenderingContext2d) {
+        let lines = self.layout(context);
+        self.fit_to_layout(&lines);
+    }
+
+    /// 글자 인덱스 위치의 캐럿 영역 (x, y, 너비, 높이). 박스 왼쪽 위 기준이다.
+    pub fn caret_rect(&self, lines: &[VisualLine], index: usize) -> (f64, f64, f64, f64) {
+        let line = &lines[line_index_at(lines, index)];
+        let offset = line.offsets[index.clamp(line.start, line.end) - line.start];
+        if self.direction == TextDirection::Vertical {
+            (self.column_x(line) - self.line_gap, offset, line.size + self.line_gap, 2.0)
+        }
+        else {
+            (offset, line.top, 2.0, line.size + self.line_gap)
+        }
+    }
+
+    /// 글자 하나가 차지하는 영역 (x, y, 너비, 높이). 박스 왼쪽 위 기준이다.
+    pub fn glyph_rect(&self, lines: &[VisualLine], index: usize) -> Option<(f64, f64, f64, f64)> {
+        let line = lines.iter().find(|line| index >= line.start && index < line.end)?;
+        let (start, end) = line.glyphs[index - line.start];
+        if self.direction == TextDirection::Vertical {
+            Some((self.column_x(line) - self.line_gap, start, line.size + self.line_gap, end - start))
+        }
+        else {
+            Some((start, line.top, end - start, line.size + self.line_gap))
+        }
+    }
+
+    pub  fn get_line_gap(&self) -> f64 {
+        self.line_gap
+    }
+
+    /// (너비, 높이)를 반환한다.
+    pub fn get_size(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+
+    pub fn set_background_color(&mut self, color: &str) {
+        self.background_color = color.to_string();
+    }
+
+    pub fn set_framed(&mut self, value: bool) {
+        self.framed = value;
+    }
+
+    // ✅ 텍스트 박스 높이 계산
+    fn get_height(&self, text: &str) -> f64 {
+        let lines = split_lines(text);
+        let line_count = lines.into_iter().count().max(1); // 최소 1줄
+        10.0 + (line_count as f64) * (self.font.size) + ((line_count - 1) as f64) * (self.line_gap)
+    }
+
+    /// ✅ 줄 개수에 따라 높이 자동 조정
+    fn update_height(&mut self, height: f64) {
+        self.height = height;
+    }
+
+    /// ✅ 위쪽 줄로 이동 (화면에 보이는 줄 기준, 같은 x 위치)
+    fn move_cursor_up(&mut self, context: &CanvasRenderingContext2d) {
+        self.move_cursor_vertically(context, -1);
+    }
+
+    /// ✅ 아래쪽 줄로 이동
+    fn move_cursor_down(&mut self, context: &CanvasRenderingContext2d) {
+        self.move_cursor_vertically(context, 1);
+    }
+
+    fn move_cursor_vertically(&mut self, context: &CanvasRenderingContext2d, delta: i32) {
+        let lines = self.layout(context);
+        let row = line_index_at(&lines, self.cursor_position);
+        let target = row as i32 + delta;
+        if target < 0 || target >= lines.len() as i32 {
+            return; // 🚫 첫 줄/마지막 줄에서는 더 이동할 수 없음
+        }
+
+        // 연속해서 위/아래로 움직이면 처음 x 좌표를 유지한다. (짧은 줄을 지나도 열이 바뀌지 않는다)
+        let x = match self.preferred_x {
+            Some((index, x)) if index == self.cursor_position => x,
+            _ => lines[row].offsets[self.cursor_position - lines[row].start],
+        };
+        self.cursor_position = self.snap_to_grapheme(nearest_index_in_line(&lines[target as usize], x));
+        self.preferred_x = Some((self.cursor_position, x));
+    }
+
+    /// ✅ 현재 줄의 시작으로 이동
+    fn move_cursor_to_line_start(&mut self, context: &CanvasRenderingContext2d) {
+        let lines = self.layout(context);
+        self.cursor_position = lines[line_index_at(&lines, self.cursor_position)].start;
+    }
+
+    /// ✅ 현재 줄의 끝으로 이동 (자동 줄바꿈된 줄은 줄 끝 공백 앞)
+    fn move_cursor_to_line_end(&mut self, context: &CanvasRenderingContext2d) {
+        let lines = self.layout(context);
+        let line = &lines[line_index_at(&lines, self.cursor_position)];
+        self.cursor_position = if line.hard_break { line.end } else { line.trimmed_end.min(line.end - 1).max(line.start) };
+    }
+
+    pub fn get_char_index_at_cursor(&self) -> usize {
+        self.cursor_position
+    }
+
+    pub fn get_byte_index_at_cursor(&self) -> usize {
+        let index = self.text
             .char_indices()
             .nth(self.cursor_position)
             .map(|(i, _)| i)
@@ -537,52 +1836,161 @@ impl TextBox{
 
     /// 커서 위치에 텍스트를 입력한다.
     pub fn insert_at_cursor(&mut self, value: &str) {
+        // 선택된 글자는 입력한 글자로 바꾼다. (지운 글자의 서식을 이어받는다)
+        let replaced_style = self.selection_range().map(|(start, _)| self.runs.style_at(start));
+        self.delete_selection();
+        if self.typing_style.is_none() {
+            self.typing_style = replaced_style;
+        }
+
+        self.runs.fit(self.text.chars().count());
+        let style = self.insertion_style();
+        self.runs.insert(self.cursor_position, value.chars().count(), style);
+        self.typing_style = None;
+
         let byte_index = self.get_byte_index_at_cursor();
         self.text.insert_str(byte_index, value);
         self.cursor_position += value.chars().count();
     }
 
+    /// [start, end) 글자를 value로 바꾼다. (지운 글자의 서식을 이어받는다)
+    pub fn replace_range(&mut self, start: usize, end: usize, value: &str) {
+        self.typing_style = None;
+        self.selection_anchor = Some(start);
+        self.cursor_position = end;
+        self.insert_at_cursor(value);
+    }
+
     /// 커서 위치의 이전 글자를 삭제한다.
     pub fn delete_before_cursor(&mut self) {
         if self.cursor_position > 0 {
-            let byte_index = self.get_byte_index_at_cursor();
-            let prev_char_index = self
-                .text
-                .char_indices()
-                .take(self.cursor_position)
-                .last()
-                .map(|(i, _)| i)
-                .unwrap_or(0);
-
-            self.text.replace_range(prev_char_index..byte_index, "");
-            self.cursor_position -= 1;
+            let start = self.previous_grapheme(self.cursor_position);
+            self.delete_range(start, self.cursor_position);
         }
     }
 
     pub fn delete_at_cursor(&mut self) {
         if self.cursor_position < self.text.chars().count() {
-            let start_index = self.get_byte_index_at_cursor();
-            let end_index = self.text[start_index..]
-                .char_indices()
-                .nth(1)
-                .map(|(i, _)| start_index + i)
-                .unwrap_or(0);
+            let end = self.next_grapheme(self.cursor_position);
+            self.delete_range(self.cursor_position, end);
+        }
+    }
+
+    /// 커서 앞 단어를 지운다. (Ctrl+Backspace)
+    pub fn delete_word_before_cursor(&mut self) {
+        let start = self.previous_word_start(self.cursor_position);
+        self.delete_range(start, self.cursor_position);
+    }
 
-            self.text.replace_range(start_index..end_index, "");
+    /// 커서 뒤 단어를 지운다. (Ctrl+Delete)
+    pub fn delete_word_at_cursor(&mut self) {
+        let end = self.next_word_end(self.cursor_position);
+        self.delete_range(self.cursor_position, end);
+    }
+
+    /// [start, end) 범위의 글자를 지우고 커서를 start로 옮긴다.
+    fn delete_range(&mut self, start: usize, end: usize) {
+        if start >= end {
+            return;
         }
+        self.runs.fit(self.text.chars().count());
+        self.runs.remove(start, end);
+
+        let byte_start = self.text.char_indices().nth(start).map_or(self.text.len(), |(i, _)| i);
+        let byte_end = self.text.char_indices().nth(end).map_or(self.text.len(), |(i, _)| i);
+        self.text.replace_range(byte_start..byte_end, "");
+        self.cursor_position = start;
     }
 
-    pub fn move_cursor_left(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
+    /// 글자 묶음(grapheme cluster) 경계의 글자 인덱스 목록 (0과 글자 수 포함)
+    fn grapheme_boundaries(&self) -> Vec<usize> {
+        let mut boundaries = vec![0];
+        let mut count = 0;
+        for grapheme in self.text.graphemes(true) {
+            count += grapheme.chars().count();
+            boundaries.push(count);
+        }
+        boundaries
+    }
+
+    fn previous_grapheme(&self, index: usize) -> usize {
+        self.grapheme_boundaries().into_iter().rev().find(|&boundary| boundary < index).unwrap_or(0)
+    }
+
+    fn next_grapheme(&self, index: usize) -> usize {
+        let boundaries = self.grapheme_boundaries();
+        boundaries.iter().copied().find(|&boundary| boundary > index).unwrap_or(*boundaries.last().unwrap())
+    }
+
+    /// 글자 묶음 중간의 인덱스를 묶음의 시작으로 옮긴다.
+    fn snap_to_grapheme(&self, index: usize) -> usize {
+        self.grapheme_boundaries().into_iter().rev().find(|&boundary| boundary <= index).unwrap_or(0)
+    }
+
+    /// 단어(유니코드 단어 경계로 나눈 조각 중 글자나 숫자가 있는 것)의 (시작, 끝) 목록
+    fn word_ranges(&self) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for segment in self.text.split_word_bounds() {
+            let end = start + segment.chars().count();
+            if segment.chars().any(|c| c.is_alphanumeric()) {
+                ranges.push((start, end));
+            }
+            start = end;
         }
+        ranges
+    }
+
+    /// index 앞에 있는 단어의 시작 (Ctrl+Left)
+    fn previous_word_start(&self, index: usize) -> usize {
+        self.word_ranges().into_iter().rev().map(|(start, _)| start).find(|&start| start < index).unwrap_or(0)
+    }
+
+    /// index 뒤에 있는 단어의 끝 (Ctrl+Right)
+    fn next_word_end(&self, index: usize) -> usize {
+        self.word_ranges().into_iter().map(|(_, end)| end).find(|&end| end > index).unwrap_or(self.text.chars().count())
+    }
+
+    pub fn move_cursor_left(&mut self) {
+        self.cursor_position = self.previous_grapheme(self.cursor_position);
         info!("move_cursor_left: {}, {}", self.cursor_position, self.text);
     }
 
     pub fn move_cursor_right(&mut self) {
-        if self.cursor_position < self.text.chars().count() {
-            self.cursor_position += 1;
+        self.cursor_position = self.next_grapheme(self.cursor_position);
+    }
+
+    /// 화면에서 왼쪽(toward_right가 아니면) 또는 오른쪽의 가장 가까운 글자 경계로 캐럿을 옮긴다.
+    /// 양방향 텍스트에서는 논리 순서와 다르게 움직인다. 줄 끝에 닿으면 문단 방향에 따라 이전/다음 줄로 넘어간다.
+    pub fn move_cursor_visually(&mut self, toward_right: bool) {
+        // 마지막으로 그린 줄 배치를 쓴다. (아직 그리지 않았으면 논리 순서로 움직인다)
+        let lines = &self.layout_cache;
+        if lines.is_empty() {
+            self.cursor_position = if toward_right != (self.direction == TextDirection::Rtl) { self.next_grapheme(self.cursor_position) } else { self.previous_grapheme(self.cursor_position) };
+            return;
         }
+        let line = &lines[line_index_at(lines, self.cursor_position)];
+        let current = line.offsets[self.cursor_position.clamp(line.start, line.end) - line.start];
+        let last = if line.hard_break || line.end == line.start { line.end } else { line.end - 1 };
+
+        let target = self.grapheme_boundaries().into_iter()
+            .filter(|&index| index >= line.start && index <= last && index != self.cursor_position)
+            .map(|index| (index, line.offsets[index - line.start] - current))
+            .filter(|(_, distance)| if toward_right { *distance > 0.0 } else { *distance < 0.0 })
+            .min_by(|a, b| a.1.abs().partial_cmp(&b.1.abs()).unwrap());
+        self.cursor_position = match target {
+            Some((index, _)) => index,
+            None if toward_right != (self.direction == TextDirection::Rtl) => self.next_grapheme(self.cursor_position),
+            None => self.previous_grapheme(self.cursor_position),
+        };
+    }
+
+    pub fn move_cursor_word_left(&mut self) {
+        self.cursor_position = self.previous_word_start(self.cursor_position);
+    }
+
+    pub fn move_cursor_word_right(&mut self) {
+        self.cursor_position = self.next_word_end(self.cursor_position);
     }
 
     pub fn get_row_index_at_cursor(&self) -> usize {
@@ -609,6 +2017,30 @@ impl TextBox{
         // 최소 50px
         self.width = text_width.clamp(50.0, f64::MAX) + 10.0; // padding 포함
     }
+
+    /// 편집기 밖(속성 창 등)에서 텍스트를 바꾼 뒤 너비와 높이를 다시 계산한다.
+    pub fn fit_to_text(&mut self, context: &CanvasRenderingContext2d) {
+        self.relayout(context);
+    }
+
+    /// 경로 위의 글자를 `<textPath>`로 내보낸다. 경로는 `<defs>`에 넣는다.
+    fn path_to_svg(&self, path: &TextPath, points: &[Point2D], line: &VisualLine, runs: &TextRuns) -> String {
+        let id = next_text_path_id();
+        let end = if line.justified { line.trimmed_end } else { line.end };
+        let content = runs.segments(line.start, end).iter().map(|(start, end, style)| {
+            let text: String = line.text.chars().skip(start - line.start).take(end - start).map(|c| if c == '\n' { ' ' } else { c }).collect();
+            let attributes = style.svg_attributes(&self.font);
+            if attributes.is_empty() { escape_xml(&text) } else { format!("<tspan {}>{}</tspan>", attributes, escape_xml(&text)) }
+        }).collect::<String>();
+        let length = if line.justified {
+            format!(r#" textLength="{}" lengthAdjust="spacing""#, line.offsets[line.trimmed_end - line.start] - line.offsets[0])
+        } else {
+            String::new()
+        };
+
+        format!(r##"<defs><path id="{}" d="{}"/></defs><text {} fill="{}"><textPath href="#{}" startOffset="{}"{}><tspan dy="{}">{}</tspan></textPath></text>"##,
+            id, svg_path_data(points), self.font.svg_attributes(), self.color, id, line.offsets[0], length, path.side.baseline_offset(line.size), content)
+    }
 }
 impl Shape for TextBox{
     fn color(&self) -> &str {
@@ -623,6 +2055,14 @@ impl Shape for TextBox{
         Point2D::new(self.position.x, self.position.y)
     }
 
+    fn set_color(&mut self, color: &str) {
+        self.color = color.to_string();
+    }
+
+    fn set_line_width(&mut self, _width: f64) {
+        // 텍스트 박스는 선 굵기가 없다.
+    }
+
     fn max_point(&self) -> Point2D{
         Point2D::new(self.position.x + self.width, self.position.y + self.height)
     }
@@ -674,6 +2114,14 @@ impl Shape for TextBox{
     }
 
     fn move_by(&mut self, dx: f64, dy: f64) {
+        // 경로 위의 글자는 경로를 따라 움직인다. (경로도 함께 옮기는 중이면 호출한 쪽에서 건너뛴다)
+        if let (Some(points), Some(line)) = (self.path_points(), self.layout_cache.first()) {
+            let (_, angle) = point_at_distance(&points, line.offsets[0]);
+            let shift = dx * angle.cos() + dy * angle.sin();
+            self.update_path(|path| path.start_offset = (path.start_offset + shift).max(0.0));
+            return;
+        }
+
         self.position.x += dx;
         self.position.y += dy;
     }
@@ -688,46 +2136,79 @@ impl Shape for TextBox{
         }
 
         if index == 0{
-            self.position.x += dx;
-            self.position.y += dy;
+            self.move_by(dx, dy);
+        }
+        else if self.path.is_some(){
+            // 경로 위에서는 너비와 높이를 바꾸지 않는다.
+        }
+        else if index == 2 || index == 3{
+            // 회전된 박스의 좌표계로 바꾼다.
+            let mut delta = Vector2D::new(dx, dy);
+            delta.rotate_by(-self.rotation);
+
+            self.set_wrap(TextWrap::Fixed);
+            if index == 2{
+                // 세로쓰기의 너비는 줄(열) 수에 따라 정해진다.
+                if self.direction != TextDirection::Vertical {
+                    self.width = (self.width + delta.x).max(self.font.size + PADDING * 2.0);
+                }
+            }
+            else{
+                self.box_height = (self.height + delta.y).max(self.font.size + PADDING * 2.0);
+                self.height = self.box_height;
+            }
         }
     }
 
     fn draw(&mut self, context: &CanvasRenderingContext2d, scale: f64){
         context.save();
+        self.appearance.apply(context);
 
         context.translate(self.position.x, self.position.y).unwrap();
         context.rotate(self.rotation).unwrap();
         context.translate(-self.position.x, -self.position.y).unwrap();
 
         if self.hovered{
-            context.set_stroke_style(&JsValue::from_str("#ff0000"));
+            context.set_stroke_style(&JsValue::from_str(&Theme::current().hover_color));
         }
         else{
             context.set_stroke_style(&JsValue::from_str(&self.color));
         }
 
-        context.set_font("20px sans-serif");
+        context.set_font(&self.font.to_css());
+
+        let lines = self.layout(context);
+        if let (Some(points), Some(line)) = (self.path_points(), lines.first()) {
+            // 경로 위의 글자는 박스 없이 그린다.
+            self.fit_to_path(line, &points);
+            self.draw_on_path(context, line, &points);
+        }
+        else {
+            // 줄바꿈을 계산하고 크기를 맞춘 뒤 박스를 그린다.
+            self.fit_to_layout(&lines);
         
-        /// ✅ Draw text box
-        context.set_fill_style(&JsValue::from_str(&self.background_color));
-        context.fill_rect(self.position.x, self.position.y, self.width, self.height + 5.0);
-        context.stroke_rect(self.position.x, self.position.y, self.width, self.height.max(self.font_size + self.line_gap) + 5.0);
+            // ✅ Draw text box
+            context.set_fill_style(&JsValue::from_str(&self.background_color));
+            context.fill_rect(self.position.x, self.position.y, self.width, self.height + 5.0);
+            if self.framed {
+                context.stroke_rect(self.position.x, self.position.y, self.width, self.height.max(self.font.size + self.line_gap) + 5.0);
+            }
 
-        context.set_fill_style(&self.color.as_str().into());
+            context.set_fill_style(&self.color.as_str().into());
 
-        let byte_index = self.get_byte_index_at_cursor();
-        let text_before_cursor = &self.text[..byte_index];
-        let text_after_cursor = &self.text[byte_index..];
-        let text_to_draw = format!("{}{}{}", text_before_cursor, self.composition_text, text_after_cursor);
-        let lines: Vec<&str> = text_to_draw.lines().collect();
-        for(line_idx, line) in lines.iter().enumerate(){
-            context
-                .fill_text(line, self.position.x + 5.0, self.position.y + 5.0 + self.font_size + (line_idx as f64) * (self.font_size + self.line_gap))
-                .unwrap();
+            if self.direction == TextDirection::Vertical {
+                self.draw_vertical(context, &lines);
+            }
+            else {
+                self.draw_lines(context, &lines);
+            }
         }
+        self.layout_cache = lines;
 
-        if self.selected{ self.draw_control_points(context, scale);}
+        if self.selected{
+            Effects::clear(context);
+            self.draw_control_points(context, scale);
+        }
 
         context.restore();
     }   
@@ -742,45 +2223,122 @@ impl Shape for TextBox{
         context.translate(-self.position.x, -self.position.y).unwrap();
 
         if self.hovered{
-            context.set_stroke_style(&JsValue::from_str("#ff0000"));
+            context.set_stroke_style(&JsValue::from_str(&Theme::current().hover_color));
         }
         else{
             context.set_stroke_style(&JsValue::from_str(&self.color));
         }
 
         context.set_fill_style(&"#000000".into()); // Black text
-        context.set_font("20px Arial");
+        context.set_font(&self.font.to_css());
         context.fill_text(&self.text, self.position.x, self.position.y).unwrap();
 
         context.restore();
     }
 
     fn draw_control_points(&self, context: &CanvasRenderingContext2d, scale: f64) {
-        let adjusted_width = 5.0 / scale;
+        Theme::current().draw_handles(context, &self.control_points(), scale);
+    }
 
-        context.save();
+    fn appearance(&self) -> &Appearance {
+        &self.appearance
+    }
+
+    fn appearance_mut(&mut self) -> &mut Appearance {
+        &mut self.appearance
+    }
 
-        let control_pts = self.control_points();
-        context.set_fill_style(&"#29B6F2".into()); // control points
-        for point in control_pts{
-            context.fill_rect(point.x - adjusted_width, point.y - adjusted_width, adjusted_width * 2.0, adjusted_width * 2.0);
+    fn apply_style(&mut self, style: &ShapeStyle) {
+        self.color = style.color.clone();
+        if let Some(font) = &style.font{
+            self.set_font(font.clone());
         }
+    }
 
-        context.set_stroke_style(&"#29B6F2".into()); // blue line
-        let adjusted_width = 0.5 / scale;
-        context.set_line_width(adjusted_width);
+    fn style(&self) -> ShapeStyle {
+        ShapeStyle{ color: self.color.clone(), line_width: self.line_width(), fill: None, stroke_style: StrokeStyle::default(), font: Some(self.font.clone()) }
+    }
 
-        // ✅ Set dash pattern: [Dash length, Gap length]
-        let dash_pattern = js_sys::Array::new();
-        dash_pattern.push(&(adjusted_width * 3.0).into());  // dash
-        dash_pattern.push(&(adjusted_width * 3.0).into());  // gap
-        context.set_line_dash(&dash_pattern).unwrap();
+    fn properties(&self) -> Vec<Property> {
+        vec![
+            Property::number("x", "X", self.position.x),
+            Property::number("y", "Y", self.position.y),
+            Property::text("text", "텍스트", &self.text),
+            Property::number("font_size", "글꼴 크기", self.font.size),
+            Property::number("line_gap", "줄 간격", self.line_gap),
+        ]
+    }
 
-        //context.begin_path();
-        //context.rect(self.center.x - self.radius_x, self.center.y - self.radius_y, self.radius_x * 2.0, self.radius_y * 2.0);
-        //context.stroke();
+    fn set_property(&mut self, name: &str, value: &PropertyValue) {
+        match (name, value) {
+            ("text", PropertyValue::Text(text)) => {
+                self.text = text.clone();
+                self.cursor_position = self.cursor_position.min(self.text.chars().count());
+                self.selection_anchor = None;
+                self.runs.fit(self.text.chars().count());
+            }
+            (_, value) => {
+                let Some(value) = value.as_number() else { return; };
+                match name {
+                    "x" => self.position.x = value,
+                    "y" => self.position.y = value,
+                    "font_size" => self.font.size = value.max(1.0),
+                    "line_gap" => self.line_gap = value.max(0.0),
+                    _ => {}
+                }
+            }
+        }
+        self.height = self.get_height(&self.text);
+    }
 
-        context.restore();
+    fn to_svg(&self) -> String {
+        // 마지막으로 그린 배치는 IME 조합 중인 글자를 포함하거나 오래되었을 수 있으므로 내보낼 때 다시 배치한다.
+        let mut exported = self.clone();
+        exported.composition_text.clear();
+        let lines = measuring_context().map_or_else(|| self.layout_cache.clone(), |context| exported.layout(&context));
+        if exported.path.is_none() {
+            exported.fit_to_layout(&lines);
+        }
+        let runs = exported.display_runs();
+        if let (Some(path), Some(points), Some(line)) = (exported.path.as_ref(), exported.path_points(), lines.first()) {
+            return exported.path_to_svg(path, &points, line, &runs);
+        }
+
+        let transform = format!("rotate({} {} {})", exported.rotation.to_degrees(), exported.position.x, exported.position.y);
+        let vertical = exported.direction == TextDirection::Vertical;
+        let tspans = lines.iter().map(|line| {
+            // 줄의 시작 쪽 가장자리. 오른쪽에서 왼쪽 문단은 오른쪽 끝에서 시작한다.
+            let (left, right) = line.span(line.start, line.trimmed_end);
+            let (x, y) = if vertical {
+                (exported.position.x + exported.column_x(line) + line.size * 0.5, exported.position.y + left)
+            } else {
+                (exported.position.x + if exported.direction == TextDirection::Rtl { right } else { left }, exported.position.y + line.top + line.size)
+            };
+            let end = if line.justified { line.trimmed_end } else { line.end };
+
+            // 서식 범위마다 <tspan>을 하나씩 만든다.
+            let content = runs.segments(line.start, end).iter().map(|(start, end, style)| {
+                let text: String = line.text.chars().skip(start - line.start).take(end - start).collect();
+                let attributes = style.svg_attributes(&exported.font);
+                if attributes.is_empty() { escape_xml(&text) } else { format!("<tspan {}>{}</tspan>", attributes, escape_xml(&text)) }
+            }).collect::<String>();
+
+            if line.justified {
+                format!(r#"<tspan x="{}" y="{}" textLength="{}" lengthAdjust="spacing">{}</tspan>"#,
+                    x, y, right - left, content)
+            }
+            else {
+                format!(r#"<tspan x="{}" y="{}">{}</tspan>"#, x, y, content)
+            }
+        }).collect::<String>();
+
+        let direction = match exported.direction {
+            TextDirection::Ltr => "",
+            TextDirection::Rtl => r#" direction="rtl""#,
+            TextDirection::Vertical => r#" writing-mode="vertical-rl" text-orientation="upright""#,
+        };
+        format!(r#"<g transform="{}"><text {}{} fill="{}">{}</text></g>"#,
+            transform, exported.font.svg_attributes(), direction, exported.color, tspans)
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -790,4 +2348,59 @@ impl Shape for TextBox{
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn bidi_levels_mark_rtl_runs_in_ltr_paragraph() {
+        assert_eq!(bidi_levels(&chars("ab אב"), TextDirection::Ltr), vec![0, 0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn bidi_levels_raise_ltr_runs_in_rtl_paragraph() {
+        assert_eq!(bidi_levels(&chars("אב cd"), TextDirection::Rtl), vec![1, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn visual_order_reverses_odd_levels() {
+        assert_eq!(visual_order(&[0, 0, 1, 1]), vec![0, 1, 3, 2]);
+        assert_eq!(visual_order(&[1, 1, 1, 2, 2]), vec![3, 4, 2, 1, 0]);
+        assert_eq!(visual_order(&[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn place_line_mixed_ltr_paragraph() {
+        let (glyphs, offsets) = place_line(&[1.0; 4], &[0, 0, 1, 1], 4, false);
+        assert_eq!(glyphs, vec![(0.0, 1.0), (1.0, 2.0), (3.0, 4.0), (2.0, 3.0)]);
+        // 오른쪽에서 왼쪽 글자 뒤의 캐럿은 그 글자의 왼쪽 가장자리에 놓인다.
+        assert_eq!(offsets, vec![0.0, 1.0, 2.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    fn place_line_mixed_rtl_paragraph() {
+        let (glyphs, offsets) = place_line(&[1.0; 5], &[1, 1, 1, 2, 2], 5, true);
+        assert_eq!(glyphs, vec![(4.0, 5.0), (3.0, 4.0), (2.0, 3.0), (0.0, 1.0), (1.0, 2.0)]);
+        assert_eq!(offsets, vec![5.0, 4.0, 3.0, 2.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn place_line_shifts_trailing_whitespace_left_in_rtl() {
+        // 줄 끝 공백은 왼쪽 끝으로 밀려나므로 글자들이 0에서 시작하도록 공백 너비만큼 왼쪽으로 옮긴다.
+        let (glyphs, offsets) = place_line(&[1.0; 3], &[1, 1, 1], 2, true);
+        assert_eq!(glyphs, vec![(1.0, 2.0), (0.0, 1.0), (-1.0, 0.0)]);
+        assert_eq!(offsets, vec![2.0, 1.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn place_line_empty_line_has_single_caret_offset() {
+        let (glyphs, offsets) = place_line(&[], &[], 0, true);
+        assert!(glyphs.is_empty());
+        assert_eq!(offsets, vec![0.0]);
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::geometry::{Point2D, Vector2D};
+use super::shape::Shape;
+
+/// 경로에 대한 글자의 위치
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathSide{
+    Above,      // 글자의 기준선이 경로 위에 놓인다.
+    Center,     // 글자의 가운데가 경로 위에 놓인다.
+    Below,      // 글자의 윗부분이 경로에 닿는다.
+}
+impl PathSide{
+    pub fn from_name(name: &str) -> Option<PathSide>{
+        match name {
+            "above" => Some(PathSide::Above),
+            "center" => Some(PathSide::Center),
+            "below" => Some(PathSide::Below),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str{
+        match self {
+            PathSide::Above => "above",
+            PathSide::Center => "center",
+            PathSide::Below => "below",
+        }
+    }
+
+    /// 기준선을 경로에서 법선 방향(진행 방향의 오른쪽이 +)으로 옮길 거리
+    pub fn baseline_offset(&self, size: f64) -> f64 {
+        match self {
+            PathSide::Above => 0.0,
+            PathSide::Center => size * 0.35,
+            PathSide::Below => size * 0.8,
+        }
+    }
+}
+
+/// 텍스트 박스가 따라가는 경로. 경로 도형을 공유하므로 경로를 편집하면 글자도 다시 배치된다.
+/// 경로로 쓸 수 있는 도형은 guide_path를 구현한 선, 베지어 곡선, 타원(호), 자유곡선이다.
+#[derive(Clone)]
+pub struct TextPath{
+    pub guide: Arc<Mutex<Box<dyn Shape>>>,
+    pub start_offset: f64,  // 경로 시작점에서 글자를 놓기 시작할 거리 (px)
+    pub side: PathSide,
+}
+impl fmt::Debug for TextPath{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextPath").field("start_offset", &self.start_offset).field("side", &self.side).finish()
+    }
+}
+impl TextPath{
+    pub fn new(guide: Arc<Mutex<Box<dyn Shape>>>) -> Self {
+        TextPath{ guide, start_offset: 0.0, side: PathSide::Above }
+    }
+
+    /// 경로 도형의 현재 꺾은선
+    pub fn points(&self) -> Vec<Point2D> {
+        self.guide.lock().unwrap().guide_path()
+    }
+}
+
+static TEXT_PATH_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// SVG로 내보낼 때 `<textPath>`가 참조할 경로의 id
+pub fn next_text_path_id() -> String {
+    format!("text-path-{}", TEXT_PATH_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// 꺾은선의 길이
+pub fn polyline_length(points: &[Point2D]) -> f64 {
+    points.windows(2).map(|pair| Vector2D::from_points(pair[0], pair[1]).length()).sum()
+}
+
+/// 꺾은선 위 distance 위치의 점과 진행 방향의 각도. 양 끝을 벗어나면 끝 선분의 방향으로 연장한다.
+pub fn point_at_distance(points: &[Point2D], distance: f64) -> (Point2D, f64) {
+    if points.len() < 2 {
+        return (points.first().copied().unwrap_or(Point2D::new(0.0, 0.0)), 0.0);
+    }
+
+    let mut remaining = distance;
+    let last = points.len() - 2;
+    for (i, pair) in points.windows(2).enumerate() {
+        let segment = Vector2D::from_points(pair[0], pair[1]);
+        let length = segment.length();
+        if length == 0.0 && i != last {
+            continue;
+        }
+        if remaining <= length || i == last {
+            let t = if length > 0.0 { remaining / length } else { 0.0 };
+            let point = Point2D::new(pair[0].x + segment.x * t, pair[0].y + segment.y * t);
+            return (point, segment.y.atan2(segment.x));
+        }
+        remaining -= length;
+    }
+    (points[0], 0.0)
+}
+
+/// 점을 꺾은선에 투영한다. (경로를 따른 거리, 경로까지의 거리)
+pub fn project_to_polyline(points: &[Point2D], point: Point2D) -> (f64, f64) {
+    let mut best = (0.0, f64::MAX);
+    let mut walked = 0.0;
+    for pair in points.windows(2) {
+        let segment = Vector2D::from_points(pair[0], pair[1]);
+        let length_sq = segment.dot(segment);
+        let t = if length_sq > 0.0 { (Vector2D::from_points(pair[0], point).dot(segment) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+        let foot = Point2D::new(pair[0].x + segment.x * t, pair[0].y + segment.y * t);
+        let distance = Vector2D::from_points(foot, point).length();
+        if distance < best.1 {
+            best = (walked + length_sq.sqrt() * t, distance);
+        }
+        walked += length_sq.sqrt();
+    }
+    best
+}
+
+/// SVG `<path>`의 d 속성
+pub fn svg_path_data(points: &[Point2D]) -> String {
+    points.iter().enumerate()
+        .map(|(i, point)| format!("{}{} {}", if i == 0 { "M" } else { " L" }, point.x, point.y))
+        .collect()
+}
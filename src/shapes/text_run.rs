@@ -0,0 +1,303 @@
+use super::shape::{TextFont, escape_xml};
+
+/// 위첨자/아래첨자
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Script{
+    #[default]
+    Normal,
+    Superscript,
+    Subscript,
+}
+
+/// 글자 범위에 적용하는 서식. 지정하지 않은 값(None, false)은 텍스트 박스의 글꼴과 색상을 따른다.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RunStyle{
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub color: Option<String>,
+    pub size: Option<f64>,      // px
+    pub script: Script,
+}
+
+/// 위첨자/아래첨자의 글자 크기 비율
+const SCRIPT_SCALE: f64 = 0.65;
+
+impl RunStyle{
+    /// 텍스트 박스의 글꼴에 이 서식을 적용한 글꼴
+    pub fn font(&self, base: &TextFont) -> TextFont {
+        let size = self.size.unwrap_or(base.size);
+        TextFont{
+            family: base.family.clone(),
+            size: if self.script == Script::Normal { size } else { size * SCRIPT_SCALE },
+            weight: if self.bold { base.weight.max(700) } else { base.weight },
+            italic: base.italic || self.italic,
+        }
+    }
+
+    /// 줄 높이 계산에 쓰는 글자 크기 (위첨자/아래첨자도 원래 크기로 자리를 차지한다)
+    pub fn line_size(&self, base: &TextFont) -> f64 {
+        self.size.unwrap_or(base.size)
+    }
+
+    /// 기준선에서 글자를 옮길 거리 (아래쪽이 +)
+    pub fn baseline_shift(&self, base: &TextFont) -> f64 {
+        let size = self.line_size(base);
+        match self.script {
+            Script::Normal => 0.0,
+            Script::Superscript => -size * 0.35,
+            Script::Subscript => size * 0.15,
+        }
+    }
+
+    /// SVG `<tspan>` 요소의 서식 속성 (기본 서식이면 빈 문자열)
+    pub fn svg_attributes(&self, base: &TextFont) -> String {
+        let font = self.font(base);
+        let mut attributes = Vec::new();
+        if font.size != base.size {
+            attributes.push(format!(r#"font-size="{}""#, font.size));
+        }
+        if self.bold {
+            attributes.push(format!(r#"font-weight="{}""#, font.weight));
+        }
+        if self.italic {
+            attributes.push(r#"font-style="italic""#.to_string());
+        }
+        let decorations = [(self.underline, "underline"), (self.strikethrough, "line-through")]
+            .iter().filter(|(on, _)| *on).map(|(_, name)| *name).collect::<Vec<_>>();
+        if !decorations.is_empty() {
+            attributes.push(format!(r#"text-decoration="{}""#, decorations.join(" ")));
+        }
+        if let Some(color) = &self.color {
+            attributes.push(format!(r#"fill="{}""#, escape_xml(color)));
+        }
+        match self.script {
+            Script::Normal => {}
+            Script::Superscript => attributes.push(r#"baseline-shift="super""#.to_string()),
+            Script::Subscript => attributes.push(r#"baseline-shift="sub""#.to_string()),
+        }
+        attributes.join(" ")
+    }
+}
+
+/// 같은 서식이 적용된 연속된 글자들
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun{
+    pub len: usize,     // 글자 수
+    pub style: RunStyle,
+}
+
+/// 텍스트 박스의 서식 범위 목록. 글자 수의 합은 텍스트의 글자 수와 같다.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextRuns{
+    runs: Vec<TextRun>,
+}
+
+impl TextRuns{
+    pub fn len(&self) -> usize {
+        self.runs.iter().map(|run| run.len).sum()
+    }
+
+    /// 텍스트를 직접 바꾼 경우(표 셀, 속성 창) 글자 수를 맞춘다. 늘어난 글자는 마지막 서식을 따른다.
+    pub fn fit(&mut self, len: usize) {
+        let current = self.len();
+        if current < len {
+            let style = self.runs.last().map(|run| run.style.clone()).unwrap_or_default();
+            self.insert(current, len - current, style);
+        }
+        else if current > len {
+            self.remove(len, current);
+        }
+    }
+
+    /// index 번째 글자의 서식
+    pub fn style_at(&self, index: usize) -> RunStyle {
+        let mut start = 0;
+        for run in &self.runs {
+            if index < start + run.len {
+                return run.style.clone();
+            }
+            start += run.len;
+        }
+        self.runs.last().map(|run| run.style.clone()).unwrap_or_default()
+    }
+
+    /// index 위치에 count 글자를 style 서식으로 넣는다.
+    pub fn insert(&mut self, index: usize, count: usize, style: RunStyle) {
+        if count == 0 {
+            return;
+        }
+        let position = self.split_at(index);
+        self.runs.insert(position, TextRun{ len: count, style });
+        self.normalize();
+    }
+
+    /// [start, end) 범위의 글자를 지운다.
+    pub fn remove(&mut self, start: usize, end: usize) {
+        if start >= end {
+            return;
+        }
+        let first = self.split_at(start);
+        let last = self.split_at(end);
+        self.runs.drain(first..last);
+        self.normalize();
+    }
+
+    /// [start, end) 범위의 서식을 바꾼다.
+    pub fn update_range(&mut self, start: usize, end: usize, f: impl Fn(&mut RunStyle)) {
+        if start >= end {
+            return;
+        }
+        let first = self.split_at(start);
+        let last = self.split_at(end);
+        self.runs[first..last].iter_mut().for_each(|run| f(&mut run.style));
+        self.normalize();
+    }
+
+    /// [start, end) 범위의 모든 글자가 조건을 만족하는지
+    pub fn all_in_range(&self, start: usize, end: usize, f: impl Fn(&RunStyle) -> bool) -> bool {
+        self.segments(start, end).iter().all(|(_, _, style)| f(style))
+    }
+
+    /// [start, end) 범위를 서식이 같은 구간 (시작, 끝, 서식)으로 나눈다.
+    pub fn segments(&self, start: usize, end: usize) -> Vec<(usize, usize, RunStyle)> {
+        let mut segments = Vec::new();
+        let mut run_start = 0;
+        for run in &self.runs {
+            let run_end = run_start + run.len;
+            let (from, to) = (run_start.max(start), run_end.min(end));
+            if from < to {
+                segments.push((from, to, run.style.clone()));
+            }
+            run_start = run_end;
+        }
+        segments
+    }
+
+    /// index 위치에서 범위를 나누고, index에서 시작하는 범위의 위치를 반환한다.
+    fn split_at(&mut self, index: usize) -> usize {
+        let mut start = 0;
+        for position in 0..self.runs.len() {
+            let len = self.runs[position].len;
+            if index == start {
+                return position;
+            }
+            if index < start + len {
+                let style = self.runs[position].style.clone();
+                self.runs[position].len = index - start;
+                self.runs.insert(position + 1, TextRun{ len: start + len - index, style });
+                return position + 1;
+            }
+            start += len;
+        }
+        self.runs.len()
+    }
+
+    /// 빈 범위를 지우고 서식이 같은 이웃 범위를 합친다.
+    fn normalize(&mut self) {
+        let mut runs: Vec<TextRun> = Vec::new();
+        for run in self.runs.drain(..).filter(|run| run.len > 0) {
+            match runs.last_mut() {
+                Some(last) if last.style == run.style => last.len += run.len,
+                _ => runs.push(run),
+            }
+        }
+        self.runs = runs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bold() -> RunStyle {
+        RunStyle{ bold: true, ..RunStyle::default() }
+    }
+
+    fn lens(runs: &TextRuns) -> Vec<(usize, bool)> {
+        runs.runs.iter().map(|run| (run.len, run.style.bold)).collect()
+    }
+
+    #[test]
+    fn split_at_divides_run_and_returns_following_position() {
+        let mut runs = TextRuns::default();
+        runs.insert(0, 5, RunStyle::default());
+
+        assert_eq!(runs.split_at(0), 0);
+        assert_eq!(runs.split_at(2), 1);
+        assert_eq!(lens(&runs), vec![(2, false), (3, false)]);
+        assert_eq!(runs.split_at(5), 2);
+        assert_eq!(runs.len(), 5);
+    }
+
+    #[test]
+    fn normalize_merges_equal_neighbours_and_drops_empty_runs() {
+        let mut runs = TextRuns{ runs: vec![
+            TextRun{ len: 2, style: RunStyle::default() },
+            TextRun{ len: 0, style: bold() },
+            TextRun{ len: 3, style: RunStyle::default() },
+            TextRun{ len: 1, style: bold() },
+        ]};
+        runs.normalize();
+
+        assert_eq!(lens(&runs), vec![(5, false), (1, true)]);
+    }
+
+    #[test]
+    fn update_range_changes_only_the_range() {
+        let mut runs = TextRuns::default();
+        runs.insert(0, 6, RunStyle::default());
+        runs.update_range(2, 4, |style| style.bold = true);
+
+        assert_eq!(lens(&runs), vec![(2, false), (2, true), (2, false)]);
+        assert!(runs.all_in_range(2, 4, |style| style.bold));
+        assert!(!runs.all_in_range(1, 4, |style| style.bold));
+
+        // 되돌리면 이웃 범위와 다시 합쳐진다.
+        runs.update_range(2, 4, |style| style.bold = false);
+        assert_eq!(lens(&runs), vec![(6, false)]);
+    }
+
+    #[test]
+    fn update_range_ignores_empty_range() {
+        let mut runs = TextRuns::default();
+        runs.insert(0, 3, RunStyle::default());
+        runs.update_range(2, 2, |style| style.bold = true);
+
+        assert_eq!(lens(&runs), vec![(3, false)]);
+    }
+
+    #[test]
+    fn insert_and_remove_keep_styles_around_the_edit() {
+        let mut runs = TextRuns::default();
+        runs.insert(0, 4, RunStyle::default());
+        runs.insert(2, 2, bold());
+        assert_eq!(lens(&runs), vec![(2, false), (2, true), (2, false)]);
+        assert_eq!(runs.style_at(3), bold());
+
+        runs.remove(1, 5);
+        assert_eq!(lens(&runs), vec![(2, false)]);
+    }
+
+    #[test]
+    fn segments_clip_runs_to_range() {
+        let mut runs = TextRuns::default();
+        runs.insert(0, 4, RunStyle::default());
+        runs.insert(4, 4, bold());
+
+        let segments = runs.segments(2, 6).into_iter().map(|(start, end, style)| (start, end, style.bold)).collect::<Vec<_>>();
+        assert_eq!(segments, vec![(2, 4, false), (4, 6, true)]);
+    }
+
+    #[test]
+    fn fit_extends_with_last_style_and_truncates() {
+        let mut runs = TextRuns::default();
+        runs.insert(0, 2, bold());
+        runs.fit(5);
+        assert_eq!(lens(&runs), vec![(5, true)]);
+
+        runs.fit(1);
+        assert_eq!(runs.len(), 1);
+    }
+}
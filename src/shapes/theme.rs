@@ -0,0 +1,135 @@
+use std::f64::consts::PI;
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use super::geometry::Point2D;
+
+/// 조절점 모양
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HandleShape{
+    Square,
+    Circle,
+}
+
+/// 선택, 마우스 오버, 조절점, 캐럿, 캔버스 배경의 색상과 모양.
+/// 크기와 굵기는 화면 픽셀 단위이며, 그릴 때 scale로 나누어 확대/축소와 관계없이 같은 크기로 보이게 한다.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme{
+    pub name: &'static str,
+    pub hover_color: String,        // 마우스가 올라간 도형의 선 색상
+    pub handle_fill: String,
+    pub handle_stroke: String,
+    pub handle_size: f64,           // 조절점 반 크기 (화면 픽셀)
+    pub handle_shape: HandleShape,
+    pub selection_color: String,    // 선택 윤곽선 색상
+    pub selection_width: f64,       // 선택 윤곽선 굵기 (화면 픽셀)
+    pub selection_dash: Vec<f64>,   // 선택 윤곽선 점선 패턴 (화면 픽셀, 비어 있으면 실선)
+    pub caret_color: String,
+    pub composition_color: String,  // IME 조합 중인 글자 강조 색상
+    pub find_color: String,         // 찾기 결과 강조 색상
+    pub canvas_background: String,
+}
+
+static CURRENT: Lazy<Arc<Mutex<Theme>>> = Lazy::new(|| Arc::new(Mutex::new(Theme::light())));
+
+impl Theme{
+    pub fn light() -> Self {
+        Theme{
+            name: "light",
+            hover_color: "#ff0000".to_string(),
+            handle_fill: "#29B6F2".to_string(),
+            handle_stroke: "#29B6F2".to_string(),
+            handle_size: 5.0,
+            handle_shape: HandleShape::Square,
+            selection_color: "#29B6F2".to_string(),
+            selection_width: 0.5,
+            selection_dash: vec![1.5, 1.5],
+            caret_color: "blue".to_string(),
+            composition_color: "rgba(0, 0, 255, 0.3)".to_string(),
+            find_color: "rgba(255, 193, 7, 0.5)".to_string(),
+            canvas_background: "#ffffff".to_string(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Theme{
+            name: "dark",
+            hover_color: "#ff8a65".to_string(),
+            handle_fill: "#1e1e1e".to_string(),
+            handle_stroke: "#4fc3f7".to_string(),
+            handle_size: 5.0,
+            handle_shape: HandleShape::Circle,
+            selection_color: "#4fc3f7".to_string(),
+            selection_width: 1.0,
+            selection_dash: vec![4.0, 3.0],
+            caret_color: "#ffeb3b".to_string(),
+            composition_color: "rgba(255, 235, 59, 0.3)".to_string(),
+            find_color: "rgba(255, 152, 0, 0.5)".to_string(),
+            canvas_background: "#2b2b2b".to_string(),
+        }
+    }
+
+    /// 기본 제공 테마
+    pub fn built_in() -> Vec<Theme> {
+        vec![Theme::light(), Theme::dark()]
+    }
+
+    pub fn from_name(name: &str) -> Option<Theme> {
+        Theme::built_in().into_iter().find(|theme| theme.name == name)
+    }
+
+    /// 현재 테마 (그릴 때마다 복사해서 사용한다)
+    pub fn current() -> Theme {
+        CURRENT.lock().unwrap().clone()
+    }
+
+    pub fn set_current(theme: Theme) {
+        *CURRENT.lock().unwrap() = theme;
+    }
+
+    /// 조절점 하나를 테마의 모양으로 그린다.
+    pub fn draw_handle(&self, context: &CanvasRenderingContext2d, point: Point2D, scale: f64) {
+        self.draw_handle_with_shape(context, point, scale, self.handle_shape);
+    }
+
+    /// 회전, 모서리 반지름처럼 크기 조절점과 구분해야 하는 조절점은 항상 원으로 그린다.
+    pub fn draw_round_handle(&self, context: &CanvasRenderingContext2d, point: Point2D, scale: f64) {
+        self.draw_handle_with_shape(context, point, scale, HandleShape::Circle);
+    }
+
+    pub fn draw_handles(&self, context: &CanvasRenderingContext2d, points: &[Point2D], scale: f64) {
+        points.iter().for_each(|point| self.draw_handle(context, *point, scale));
+    }
+
+    fn draw_handle_with_shape(&self, context: &CanvasRenderingContext2d, point: Point2D, scale: f64, shape: HandleShape) {
+        let half = self.handle_size / scale;
+
+        context.save();
+        let _ = context.set_line_dash(&js_sys::Array::new());
+        context.set_fill_style(&JsValue::from_str(&self.handle_fill));
+        context.set_stroke_style(&JsValue::from_str(&self.handle_stroke));
+        context.set_line_width(1.0 / scale);
+        context.begin_path();
+        match shape {
+            HandleShape::Square => context.rect(point.x - half, point.y - half, half * 2.0, half * 2.0),
+            HandleShape::Circle => { let _ = context.arc(point.x, point.y, half, 0.0, PI * 2.0); }
+        }
+        context.fill();
+        context.stroke();
+        context.restore();
+    }
+
+    /// 선택 윤곽선의 색상, 굵기, 점선 패턴을 설정한다. 호출한 쪽에서 경로를 만들고 stroke한다.
+    pub fn apply_selection_outline(&self, context: &CanvasRenderingContext2d, scale: f64) {
+        context.set_stroke_style(&JsValue::from_str(&self.selection_color));
+        context.set_line_width(self.selection_width / scale);
+
+        let dash_pattern = js_sys::Array::new();
+        for length in &self.selection_dash {
+            dash_pattern.push(&(length / scale).into());
+        }
+        let _ = context.set_line_dash(&dash_pattern);
+    }
+}
@@ -2,13 +2,16 @@ use std::{mem::offset_of, str, fmt};
 use log::info;
 
 use crate::shapes::geometry::{Point2D};
-use crate::shapes::shape::{Shape};
+use crate::shapes::shape::{Shape, Brush, Fill, StrokeStyle, ShapeFormat, TextFont};
+use crate::shapes::text_box::{TextAlign, VerticalAlign};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ActionMode {
     Selection,
     Eraser,
     Drawing,
+    Eyedropper,     // 캔버스에서 색상 추출
+    FormatPainter,  // 서식 복사
 }
 
 impl fmt::Display for ActionMode {
@@ -17,6 +20,8 @@ impl fmt::Display for ActionMode {
             ActionMode::Selection => write!(f, "Selection Mode"),
             ActionMode::Eraser => write!(f, "Eraser Mode"),
             ActionMode::Drawing => write!(f, "Drawing Mode"),
+            ActionMode::Eyedropper => write!(f, "Eyedropper Mode"),
+            ActionMode::FormatPainter => write!(f, "Format Painter Mode"),
         }
     }
 }
@@ -25,8 +30,12 @@ impl fmt::Display for ActionMode {
 pub enum DrawingMode {
     Pencil,
     Line,
+    Curve,
     Rectangle,
     Ellipse,
+    Polygon,
+    Star,
+    Table,
     Text
 }
 
@@ -35,8 +44,12 @@ impl fmt::Display for DrawingMode {
         match self {
             DrawingMode::Pencil => write!(f, "Pencil Mode"),
             DrawingMode::Line => write!(f, "Line Mode"),
+            DrawingMode::Curve => write!(f, "Curve Mode"),
             DrawingMode::Rectangle => write!(f, "Rectangle Mode"),
             DrawingMode::Ellipse => write!(f, "Ellipse Mode"),
+            DrawingMode::Polygon => write!(f, "Polygon Mode"),
+            DrawingMode::Star => write!(f, "Star Mode"),
+            DrawingMode::Table => write!(f, "Table Mode"),
             DrawingMode::Text => write!(f, "Text Mode"),
         }
     }
@@ -52,6 +65,14 @@ pub struct State{
     scale: f64,     // 기본 스케일
     offset: Point2D,
     fill_color: String,
+    shape_fill: Option<Fill>,   // 새로 그리는 닫힌 도형의 채우기
+    stroke_style: StrokeStyle,  // 새로 그리는 도형의 선 스타일
+    polygon_sides: u32,     // 정다각형의 변 개수 / 별의 꼭짓점 개수
+    star_inner_ratio: f64,  // 별의 안쪽 반지름 비율
+    brush: Brush,           // 펜슬 브러시 프리셋
+    copied_format: Option<ShapeFormat>,    // 서식 복사 도구로 복사한 서식
+    text_font: TextFont,    // 새로 만드는 텍스트 박스의 글꼴
+    text_align: (TextAlign, VerticalAlign), // 새로 만드는 텍스트 박스의 가로/세로 정렬
     selected_control_point: Option<(i32, i32)>  // shape index, control point index
 }
 
@@ -66,6 +87,14 @@ impl State{
             scale: 1.0,
             offset: Point2D::new(0.0, 0.0),
             fill_color: String::from("#ffffff"),
+            shape_fill: None,
+            stroke_style: StrokeStyle::default(),
+            polygon_sides: 5,
+            star_inner_ratio: 0.5,
+            brush: Brush::Pen,
+            copied_format: None,
+            text_font: TextFont::default(),
+            text_align: (TextAlign::Left, VerticalAlign::Top),
             selected_control_point: None
         }
     }
@@ -74,6 +103,27 @@ impl State{
         &self.fill_color
     }
 
+    /// 캔버스 배경색 (테마에서 지정)
+    pub fn set_fill_color(&mut self, value: &str) {
+        self.fill_color = value.to_string();
+    }
+
+    pub fn shape_fill(&self) -> Option<&Fill> {
+        self.shape_fill.as_ref()
+    }
+
+    pub fn set_shape_fill(&mut self, value: Option<Fill>) {
+        self.shape_fill = value;
+    }
+
+    pub fn stroke_style(&self) -> &StrokeStyle {
+        &self.stroke_style
+    }
+
+    pub fn set_stroke_style(&mut self, value: StrokeStyle) {
+        self.stroke_style = value;
+    }
+
     pub fn color(&self) -> &str {
         &self.color
     }
@@ -90,6 +140,54 @@ impl State{
         self.line_width = value;
     }
 
+    pub fn polygon_sides(&self) -> u32 {
+        self.polygon_sides
+    }
+
+    pub fn set_polygon_sides(&mut self, value: u32) {
+        self.polygon_sides = value;
+    }
+
+    pub fn star_inner_ratio(&self) -> f64 {
+        self.star_inner_ratio
+    }
+
+    pub fn set_star_inner_ratio(&mut self, value: f64) {
+        self.star_inner_ratio = value;
+    }
+
+    pub fn brush(&self) -> Brush {
+        self.brush
+    }
+
+    pub fn set_brush(&mut self, value: Brush) {
+        self.brush = value;
+    }
+
+    pub fn copied_format(&self) -> Option<&ShapeFormat> {
+        self.copied_format.as_ref()
+    }
+
+    pub fn set_copied_format(&mut self, value: Option<ShapeFormat>) {
+        self.copied_format = value;
+    }
+
+    pub fn text_font(&self) -> &TextFont {
+        &self.text_font
+    }
+
+    pub fn set_text_font(&mut self, value: TextFont) {
+        self.text_font = value;
+    }
+
+    pub fn text_align(&self) -> (TextAlign, VerticalAlign) {
+        self.text_align
+    }
+
+    pub fn set_text_align(&mut self, value: (TextAlign, VerticalAlign)) {
+        self.text_align = value;
+    }
+
     pub fn scale(&self) -> f64 {
         self.scale
     }
@@ -0,0 +1,118 @@
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use serde::{Serialize, Deserialize};
+use wasm_bindgen::prelude::*;
+use web_sys::DomParser;
+
+use crate::shapes::shape::{Shape, ShapeStyle};
+use crate::shapes::table::Table;
+use crate::symbol_library::SymbolLibrary;
+
+/// SVG 내보내기에서 스타일 목록을 담는 `<metadata>` 요소의 id
+const STYLE_METADATA_ID: &str = "named-styles";
+
+/// 이름으로 참조되는 스타일 (워드프로세서의 문단 스타일처럼 정의를 바꾸면 참조하는 도형이 모두 바뀐다)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedStyle {
+    name: String,
+    style: ShapeStyle,
+}
+
+impl NamedStyle {
+    pub fn new(name: &str, style: ShapeStyle) -> Self {
+        NamedStyle { name: name.to_string(), style }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn style(&self) -> &ShapeStyle {
+        &self.style
+    }
+}
+
+/// 문서와 함께 저장되는 스타일 목록
+pub struct StyleLibrary {
+    styles: Vec<NamedStyle>,
+}
+
+static INSTANCE: Lazy<Arc<Mutex<StyleLibrary>>> = Lazy::new(|| Arc::new(Mutex::new(StyleLibrary { styles: Vec::new() })));
+
+impl StyleLibrary {
+    pub fn instance() -> Arc<Mutex<StyleLibrary>> {
+        Arc::clone(&INSTANCE)
+    }
+
+    pub fn styles(&self) -> &[NamedStyle] {
+        &self.styles
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NamedStyle> {
+        self.styles.iter().find(|style| style.name == name)
+    }
+
+    /// 스타일을 정의한다. 같은 이름이 있으면 정의를 교체한다.
+    /// 참조하는 도형에 반영하려면 `restyle_shapes`를 호출한다.
+    pub fn define(&mut self, style: NamedStyle) {
+        match self.styles.iter().position(|s| s.name == style.name) {
+            Some(index) => self.styles[index] = style,
+            None => self.styles.push(style),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.styles.retain(|style| style.name != name);
+    }
+
+    /// 문서를 비울 때 그 문서의 스타일도 지운다.
+    pub fn clear(&mut self) {
+        self.styles.clear();
+    }
+
+    /// SVG 내보내기에 넣을 `<metadata>` 요소 (다른 문서에서 가져올 때 사용)
+    pub fn to_svg_metadata(&self) -> Result<String, JsValue> {
+        if self.styles.is_empty() {
+            return Ok(String::new());
+        }
+
+        let value = serde_wasm_bindgen::to_value(&self.styles)?;
+        let json = String::from(js_sys::JSON::stringify(&value)?);
+        Ok(format!(r#"<metadata id="{}"><![CDATA[{}]]></metadata>"#, STYLE_METADATA_ID, json))
+    }
+
+    /// 다른 문서(내보낸 SVG)에서 스타일을 가져온다. 같은 이름의 스타일은 교체하며, 가져온 스타일 이름 목록을 반환한다.
+    pub fn import_svg(&mut self, svg_text: &str) -> Result<Vec<String>, JsValue> {
+        let parser = DomParser::new()?;
+        let doc = parser.parse_from_string(svg_text, web_sys::SupportedType::ImageSvgXml)?;
+        let Some(metadata) = doc.get_element_by_id(STYLE_METADATA_ID) else { return Ok(Vec::new()); };
+
+        let value = js_sys::JSON::parse(&metadata.text_content().unwrap_or_default())?;
+        let styles: Vec<NamedStyle> = serde_wasm_bindgen::from_value(value)?;
+        let names = styles.iter().map(|style| style.name.clone()).collect();
+        for style in styles {
+            self.define(style);
+        }
+
+        Ok(names)
+    }
+}
+
+/// 이름 있는 스타일을 참조하는 문서의 도형과 심볼 마스터의 도형에 스타일 정의를 다시 적용한다.
+pub fn restyle_document(shapes: &[Arc<Mutex<Box<dyn Shape>>>], style: &NamedStyle) {
+    restyle_shapes(shapes, style);
+    SymbolLibrary::snapshot().iter().for_each(|definition| restyle_shapes(definition.shapes(), style));
+}
+
+/// 이름 있는 스타일을 참조하는 도형에 스타일 정의를 다시 적용한다. 표는 셀의 텍스트 박스도 확인한다.
+fn restyle_shapes(shapes: &[Arc<Mutex<Box<dyn Shape>>>], style: &NamedStyle) {
+    shapes.iter().for_each(|shape| {
+        let mut shape = shape.lock().unwrap();
+        if shape.style_name() == Some(style.name()) {
+            shape.apply_style(style.style());
+        }
+        if let Some(table) = shape.as_any().downcast_ref::<Table>() {
+            restyle_shapes(&table.cell_text_boxes(), style);
+        }
+    });
+}
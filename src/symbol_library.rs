@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use serde::{Serialize, Deserialize};
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, DomParser};
+
+use crate::shapes::geometry::Point2D;
+use crate::shapes::shape::{Shape, Svg, svg_with_compositing};
+use crate::shapes::symbol::SymbolInstance;
+
+/// 심볼 정의의 내용
+#[derive(Clone)]
+pub enum SymbolContent {
+    /// 선택한 도형으로 만든 심볼 (도형은 원래 좌표를 유지하고 origin 기준으로 그린다)
+    Shapes(Vec<Arc<Mutex<Box<dyn Shape>>>>),
+    /// 드롭하거나 가져온 SVG
+    Svg(String),
+}
+
+/// 이름으로 참조되는 심볼 정의(마스터)
+#[derive(Clone)]
+pub struct SymbolDefinition {
+    name: String,
+    width: f64,
+    height: f64,
+    origin: Point2D,
+    content: SymbolContent,
+}
+
+/// 심볼 라이브러리 파일에 저장되는 항목
+#[derive(Serialize, Deserialize)]
+struct SymbolFileEntry {
+    name: String,
+    width: f64,
+    height: f64,
+    svg: String,
+}
+
+impl SymbolDefinition {
+    /// 선택한 도형들로 심볼을 정의한다. 크기에는 그림자 등 효과가 차지하는 영역도 포함된다.
+    pub fn from_shapes(name: &str, shapes: Vec<Arc<Mutex<Box<dyn Shape>>>>) -> Self {
+        let (min_pt, max_pt) = shapes.iter().fold(
+            (Point2D::new(f64::MAX, f64::MAX), Point2D::new(f64::MIN, f64::MIN)),
+            |(min_acc, max_acc), shape| {
+                let shape = shape.lock().unwrap();
+                let (min_pt, max_pt) = shape.bounds();
+                (Point2D::new(min_acc.x.min(min_pt.x), min_acc.y.min(min_pt.y)), Point2D::new(max_acc.x.max(max_pt.x), max_acc.y.max(max_pt.y)))
+            });
+
+        shapes.iter().for_each(|shape| {
+            let mut shape = shape.lock().unwrap();
+            shape.set_selected(false);
+            shape.set_hovered(false);
+        });
+
+        SymbolDefinition {
+            name: name.to_string(),
+            width: (max_pt.x - min_pt.x).max(1.0),
+            height: (max_pt.y - min_pt.y).max(1.0),
+            origin: min_pt,
+            content: SymbolContent::Shapes(shapes),
+        }
+    }
+
+    /// SVG 문자열로 심볼을 정의한다. 크기는 width/height 또는 viewBox 속성에서 읽는다.
+    pub fn from_svg(name: &str, svg_text: &str) -> Self {
+        let (mut width, mut height) = (100.0, 100.0);
+
+        let parser = DomParser::new().unwrap();
+        if let Ok(doc) = parser.parse_from_string(svg_text, web_sys::SupportedType::ImageSvgXml) {
+            if let Some(svg_element) = doc.query_selector("svg").ok().flatten() {
+                let view_box: Vec<f64> = svg_element.get_attribute("viewBox").unwrap_or_default()
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter_map(|s| s.parse::<f64>().ok())
+                    .collect();
+                if view_box.len() == 4 {
+                    width = view_box[2];
+                    height = view_box[3];
+                }
+
+                let parse_length = |value: Option<String>| value.and_then(|v| v.trim_end_matches("px").parse::<f64>().ok());
+                if let Some(value) = parse_length(svg_element.get_attribute("width")) { width = value; }
+                if let Some(value) = parse_length(svg_element.get_attribute("height")) { height = value; }
+            }
+        }
+
+        SymbolDefinition {
+            name: name.to_string(),
+            width,
+            height,
+            origin: Point2D::new(0.0, 0.0),
+            content: SymbolContent::Svg(svg_text.to_string()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    /// 선택한 도형으로 만든 심볼의 도형 (SVG로 정의한 심볼이면 빈 목록)
+    pub fn shapes(&self) -> &[Arc<Mutex<Box<dyn Shape>>>] {
+        match &self.content {
+            SymbolContent::Shapes(shapes) => shapes,
+            SymbolContent::Svg(_) => &[],
+        }
+    }
+
+    /// 심볼 좌표계의 원점 (선택 영역으로 만든 경우 선택 영역의 왼쪽 위)
+    pub fn origin(&self) -> Point2D {
+        self.origin
+    }
+
+    /// SVG 문서에서 사용할 id
+    pub fn symbol_id(&self) -> String {
+        let id: String = self.name.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+        format!("symbol-{}", id)
+    }
+
+    /// (0, 0) ~ (width, height) 좌표계에 심볼을 그린다.
+    /// overrides가 있으면 SVG로 변환한 뒤 각 요소의 속성을 덮어써서 그린다.
+    pub fn draw(&self, context: &CanvasRenderingContext2d, scale: f64, overrides: &HashMap<String, String>) {
+        match &self.content {
+            SymbolContent::Shapes(shapes) if overrides.is_empty() => {
+                context.save();
+                context.translate(-self.origin.x, -self.origin.y).unwrap();
+                shapes.iter().for_each(|shape| shape.lock().unwrap().draw(context, scale));
+                context.restore();
+            }
+            _ => {
+                let mut svg = Svg::new(Point2D::new(0.0, 0.0), &self.to_svg_file());
+                svg.set_overrides(overrides.clone());
+                svg.draw(context, scale);
+            }
+        }
+    }
+
+    /// `<symbol>` 안에 들어갈 SVG 요소
+    fn to_svg_content(&self) -> String {
+        match &self.content {
+            SymbolContent::Shapes(shapes) => {
+                let elements = shapes.iter().map(|shape| svg_with_compositing(shape.lock().unwrap().as_ref())).collect::<String>();
+                format!(r#"<g transform="translate({} {})">{}</g>"#, -self.origin.x, -self.origin.y, elements)
+            }
+            SymbolContent::Svg(content) => {
+                match content.find("<svg") {
+                    Some(index) => content[index..].to_string(),
+                    None => content.clone(),
+                }
+            }
+        }
+    }
+
+    /// 단독으로 사용할 수 있는 SVG 문서 (썸네일, 파일 내보내기용)
+    pub fn to_svg_file(&self) -> String {
+        match &self.content {
+            SymbolContent::Svg(content) => content.clone(),
+            SymbolContent::Shapes(_) => format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">{}</svg>"#,
+                self.width, self.height, self.width, self.height, self.to_svg_content()),
+        }
+    }
+
+    /// `<defs>`에 들어갈 `<symbol>` 요소
+    pub fn to_svg_symbol(&self) -> String {
+        format!(r#"<symbol id="{}" viewBox="0 0 {} {}">{}</symbol>"#, self.symbol_id(), self.width, self.height, self.to_svg_content())
+    }
+}
+
+/// 문서와 함께 저장되는 심볼 라이브러리
+pub struct SymbolLibrary {
+    definitions: Vec<SymbolDefinition>,
+}
+
+static INSTANCE: Lazy<Arc<Mutex<SymbolLibrary>>> = Lazy::new(|| Arc::new(Mutex::new(SymbolLibrary { definitions: Vec::new() })));
+
+impl SymbolLibrary {
+    pub fn instance() -> Arc<Mutex<SymbolLibrary>> {
+        Arc::clone(&INSTANCE)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SymbolDefinition> {
+        self.definitions.iter().find(|definition| definition.name == name)
+    }
+
+    /// 심볼을 정의한다. 같은 이름이 있으면 마스터를 교체하므로 모든 인스턴스가 갱신된다.
+    /// 자기 자신을 (다른 마스터를 거쳐서라도) 포함하는 마스터는 거부한다.
+    pub fn define(&mut self, definition: SymbolDefinition) -> Result<(), String> {
+        if let SymbolContent::Shapes(shapes) = &definition.content {
+            if self.references(shapes, &definition.name) {
+                return Err(format!("symbol '{}' cannot contain itself", definition.name));
+            }
+        }
+
+        match self.definitions.iter().position(|d| d.name == definition.name) {
+            Some(index) => self.definitions[index] = definition,
+            None => self.definitions.push(definition),
+        }
+        Ok(())
+    }
+
+    /// 도형들이 직접 또는 다른 마스터를 거쳐 name 심볼의 인스턴스를 포함하는지 확인한다.
+    pub fn references(&self, shapes: &[Arc<Mutex<Box<dyn Shape>>>], name: &str) -> bool {
+        let mut visited = Vec::new();
+        self.references_from(shapes, name, &mut visited)
+    }
+
+    fn references_from(&self, shapes: &[Arc<Mutex<Box<dyn Shape>>>], name: &str, visited: &mut Vec<String>) -> bool {
+        shapes.iter().any(|shape| {
+            let symbol_name = shape.lock().unwrap().as_any().downcast_ref::<SymbolInstance>().map(|instance| instance.symbol_name().to_string());
+            let Some(symbol_name) = symbol_name else { return false; };
+            if symbol_name == name {
+                return true;
+            }
+            if visited.contains(&symbol_name) {
+                return false;
+            }
+            visited.push(symbol_name.clone());
+
+            match self.get(&symbol_name).map(|definition| &definition.content) {
+                Some(SymbolContent::Shapes(inner)) => self.references_from(inner, name, visited),
+                _ => false,
+            }
+        })
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.definitions.retain(|definition| definition.name != name);
+    }
+
+    /// 잠금을 풀어도 쓸 수 있도록 정의를 복사한다.
+    /// 마스터 안의 인스턴스가 그리거나 내보낼 때 라이브러리를 다시 잠그므로,
+    /// 마스터의 도형을 다룰 때는 잠금을 쥐고 있으면 안 된다.
+    pub fn snapshot() -> Vec<SymbolDefinition> {
+        Self::instance().lock().unwrap().definitions.clone()
+    }
+
+    /// SVG 내보내기에 사용할 `<defs>` 요소
+    pub fn to_svg_defs() -> String {
+        let definitions = Self::snapshot();
+        if definitions.is_empty() {
+            return String::new();
+        }
+
+        format!("<defs>{}</defs>", definitions.iter().map(|definition| definition.to_svg_symbol()).collect::<String>())
+    }
+
+    /// 라이브러리를 공유 가능한 JSON 문자열로 변환한다.
+    pub fn export_json() -> Result<String, JsValue> {
+        let entries: Vec<SymbolFileEntry> = Self::snapshot().iter().map(|definition| SymbolFileEntry {
+            name: definition.name.clone(),
+            width: definition.width,
+            height: definition.height,
+            svg: definition.to_svg_file(),
+        }).collect();
+
+        let value = serde_wasm_bindgen::to_value(&entries)?;
+        js_sys::JSON::stringify(&value).map(String::from)
+    }
+
+    /// JSON 문자열에서 심볼을 가져온다. 같은 이름의 심볼은 교체하며, 가져온 개수를 반환한다.
+    pub fn import_json(&mut self, json: &str) -> Result<usize, JsValue> {
+        let value = js_sys::JSON::parse(json)?;
+        let entries: Vec<SymbolFileEntry> = serde_wasm_bindgen::from_value(value)?;
+        let count = entries.len();
+        for entry in entries {
+            let mut definition = SymbolDefinition::from_svg(&entry.name, &entry.svg);
+            definition.width = entry.width;
+            definition.height = entry.height;
+            self.define(definition).map_err(|e| JsValue::from_str(&e))?;
+        }
+
+        Ok(count)
+    }
+}